@@ -3,22 +3,57 @@
 //! This module defines the command-line interface structure using `clap`.
 //! It handles argument parsing, validation, and help text generation.
 //!
+//! Merging is the default command, so `pdfcat a.pdf b.pdf -o out.pdf`
+//! and `pdfcat merge a.pdf b.pdf -o out.pdf` are equivalent.
+//!
 //! # Examples
 //!
 //! ```no_run
-//! use pdfcat::cli::Cli;
+//! use pdfcat::cli::{Cli, Command};
 //! use clap::Parser;
 //!
 //! let cli = Cli::parse();
-//! println!("Merging {} files", cli.inputs.len());
+//! if let Some(Command::Merge(args)) = cli.into_command() {
+//!     println!("Merging {} files", args.inputs.len());
+//! }
 //! ```
 
-use clap::Parser;
+use clap::{ArgGroup, Args, Parser, Subcommand};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use pdfcat::config::{CompressionLevel, Config, Metadata, OverwriteMode, PageRange, Rotation};
+use pdfcat::config::{
+    AnnotationFilter, Bates, BoxEdit, BoxKind, CompressionLevel, Config, Layout, MergeMode,
+    Metadata, OpenAt, OutputFormat, OverwriteMode, PageFit, PageLabels, PageLayout, PageMode,
+    PageRange, PageRangePolicy, PageSize, PdfVersion, Rotation, Stamp, StampPosition,
+    ViewerSettings, Watermark, WatermarkContent, WatermarkLayer,
+};
 use pdfcat::error::{PdfCatError, Result};
+use pdfcat::split::{DEFAULT_TEMPLATE, SplitMode, SplitOptions, parse_size_limit};
+
+// clap turns a literal `{n}` in help text into a line break, so help that
+// shows the placeholder is kept here and writes it with a word joiner
+// (U+2060) after the brace, which terminals don't display.
+
+/// Long help of `--header`.
+const HEADER_HELP: &str = "Text centered at the top of each page
+
+Placeholders: {\u{2060}n} (page number), {total} (page count), {file} (input file name), \
+{date} (today, as YYYY-MM-DD) and {bates} (the Bates number, with --bates).";
+
+/// Long help of `--footer`.
+const FOOTER_HELP: &str = "Text centered at the bottom of each page
+
+Takes the same placeholders as --header, e.g. --footer \"Page {\u{2060}n} of {total}\".";
+
+/// Long help of `--page-labels`.
+const PAGE_LABELS_HELP: &str = "Page labels (page numbers shown by viewers) of the output
+
+keep (default) keeps each input's labels, such as roman numerals; none writes no labels. \
+Anything else is a template ending in {\u{2060}n}:
+  {stem}   Input file name without extension
+  {\u{2060}n}      Page number within the input
+e.g. \"{stem}-{\u{2060}n}\" labels the pages of report.pdf report-1, report-2, ...";
 
 /// Concatenate PDF files into a single document.
 ///
@@ -31,15 +66,62 @@ use pdfcat::error::{PdfCatError, Result};
 #[command(about = "Concatenate PDF files into a single document", long_about = None)]
 #[command(author)]
 #[command(arg_required_else_help = true)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Cli {
+    /// Command to run
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Merge arguments when no command is given
+    #[command(flatten)]
+    pub merge: Option<MergeArgs>,
+}
+
+/// Available pdfcat commands.
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    /// Merge PDF files into a single document (default)
+    Merge(MergeArgs),
+
+    /// Show information about PDF files
+    Info(InfoArgs),
+
+    /// Split a PDF file into several files
+    Split(SplitArgs),
+
+    /// Write each page of a PDF file to its own file
+    Burst(BurstArgs),
+
+    /// Copy selected pages of a PDF file into a new file
+    Select(SelectArgs),
+}
+
+impl Cli {
+    /// Resolve the command to run.
+    ///
+    /// Returns `Command::Merge` when merge arguments were given without
+    /// a command name.
+    #[allow(unused)]
+    pub fn into_command(self) -> Option<Command> {
+        self.command.or(self.merge.map(Command::Merge))
+    }
+}
+
+/// Arguments for merging PDF files.
+#[derive(Args, Debug)]
+pub struct MergeArgs {
     /// Input PDF files to merge (in order)
     ///
     /// Specify multiple files or use glob patterns.
-    /// Files are merged in the order provided.
+    /// Files are merged in the order provided. JPEG and PNG images
+    /// become a page each, sized to the image (see --page-size to fit
+    /// them to paper).
     ///
     /// Examples:
     ///   pdfcat file1.pdf file2.pdf -o output.pdf
     ///   pdfcat chapter*.pdf -o book.pdf
+    ///   pdfcat scan*.jpg report.pdf -o out.pdf
     #[arg(required = true, value_name = "FILE")]
     pub inputs: Vec<PathBuf>,
 
@@ -133,6 +215,15 @@ pub struct Cli {
     #[arg(long)]
     pub continue_on_error: bool,
 
+    /// Attempt to repair damaged input PDFs
+    ///
+    /// Inputs with a corrupted cross-reference table or a missing page
+    /// tree are reconstructed from the objects found in the file.
+    /// A warning describing what was recovered is printed for each
+    /// repaired file.
+    #[arg(long)]
+    pub repair: bool,
+
     /// Read input file list from a file (one path per line)
     ///
     /// Instead of specifying files on command line, read from a file.
@@ -154,27 +245,664 @@ pub struct Cli {
     ///
     /// Apply the same page range to all input PDFs.
     /// Page numbers are 1-indexed. Use commas to separate ranges.
+    /// Pages are taken in the order listed, and may be repeated.
     ///
     /// Examples:
     ///   --pages "1-10"      # First 10 pages from each PDF
     ///   --pages "1,3,5"     # Pages 1, 3, and 5 from each PDF
     ///   --pages "1-5,10-15" # Pages 1-5 and 10-15 from each PDF
+    ///   --pages "-3-end"    # Last 3 pages from each PDF
+    ///   --pages "end-1"     # All pages in reverse order
+    ///   --pages "odd"       # Odd pages (also "even")
     #[arg(long, value_name = "RANGE")]
     pub pages: Option<String>,
 
+    /// How to treat --pages entries beyond an input's last page
+    ///
+    /// - lenient: Skip missing pages; fail only if nothing is selected (default)
+    /// - strict: Fail if the range refers to any missing page
+    ///
+    /// Ranges are checked against every input, including with --dry-run.
+    #[arg(long, value_name = "POLICY", default_value = "lenient")]
+    #[arg(value_parser = ["lenient", "strict"])]
+    pub range_policy: String,
+
     /// Rotate pages by specified degrees (90, 180, 270)
     ///
     /// Applies rotation to all pages in all input PDFs.
     #[arg(long, value_name = "DEGREES")]
     #[arg(value_parser = ["90", "180", "270"])]
     pub rotate: Option<String>,
+
+    /// Interleave pages from the inputs instead of concatenating them
+    ///
+    /// Takes the first page of each input, then the second page of each
+    /// input, and so on. Useful for combining separately scanned front
+    /// and back sides.
+    ///
+    /// Example:
+    ///   pdfcat --collate front.pdf back.pdf --reverse 2 -o scan.pdf
+    #[arg(long)]
+    pub collate: bool,
+
+    /// Make a PDF Portfolio instead of merging pages
+    ///
+    /// Each input is embedded unchanged, e.g. to keep signatures valid,
+    /// as an attachment of a cover page that lists the inputs. Viewers that
+    /// support portfolios show the files with their size and page count.
+    #[arg(long, conflicts_with = "collate")]
+    pub portfolio: bool,
+
+    /// Take the pages of the Nth input (1-indexed) in reverse order
+    ///
+    /// Can be repeated or given a comma-separated list.
+    #[arg(long, value_name = "N", value_delimiter = ',')]
+    pub reverse: Vec<usize>,
+
+    /// Start each input on an odd (right-hand) page for duplex printing
+    ///
+    /// A blank page, the size of the page before it, is added after each
+    /// input that would otherwise leave the next one on an even page.
+    #[arg(long)]
+    pub duplex: bool,
+
+    /// Insert a blank page after page N of the merged pages
+    ///
+    /// Use 0 to insert before the first page. Page numbers count the
+    /// merged pages before any blank pages are added. Can be repeated or
+    /// given a comma-separated list.
+    #[arg(long, value_name = "N", value_delimiter = ',')]
+    pub insert_blank: Vec<usize>,
+
+    /// Add a separator page before each input
+    ///
+    /// The separator shows the input's file name, title and page count.
+    /// With --bookmarks, each input's bookmark points at its separator.
+    #[arg(long)]
+    pub separators: bool,
+
+    /// Place several pages on each output sheet
+    ///
+    /// - 2up, 4up, 6up, 8up, 9up, 16up: Pages per sheet
+    /// - CxR: A grid of C columns and R rows, up to 32 each (e.g., 3x2)
+    /// - booklet: Two pages per side in saddle-stitch order; print
+    ///   double-sided, fold and staple
+    ///
+    /// Sheets have the size of the first page, turned to fit the pages best.
+    #[arg(long, value_name = "LAYOUT")]
+    pub layout: Option<String>,
+
+    /// Set or shrink the CropBox, the region shown and printed
+    ///
+    /// - 10, 10,20 or 10,20,10,20: Margins to take off the top, right,
+    ///   bottom and left, as the page is displayed
+    /// - rect:X0,Y0,X1,Y1: An absolute rectangle in page coordinates
+    /// - auto: Shrink to the bounds of the visible content
+    ///
+    /// Lengths are in points, or mm or in with a suffix (e.g., 5mm).
+    /// Append @RANGE to edit only some output pages (e.g., 5mm@2-end).
+    /// Can be repeated.
+    #[arg(long, value_name = "SPEC")]
+    pub crop: Vec<String>,
+
+    /// Set or shrink the TrimBox, the size of the finished page
+    ///
+    /// Takes the same specifications as --crop. Can be repeated.
+    #[arg(long, value_name = "SPEC")]
+    pub trim: Vec<String>,
+
+    /// Set or shrink the BleedBox, the region kept in production
+    ///
+    /// Takes the same specifications as --crop. Can be repeated.
+    #[arg(long, value_name = "SPEC")]
+    pub bleed: Vec<String>,
+
+    /// Normalize every page to a paper size
+    ///
+    /// - a3, a4, a5, letter, legal, tabloid: Named sizes
+    /// - WxH: Explicit dimensions in points (e.g., 612x792), or with an
+    ///   mm or in suffix (e.g., 210x297mm, 8.5x11in)
+    ///
+    /// Landscape pages get the landscape form of the size.
+    #[arg(long, value_name = "SIZE")]
+    pub page_size: Option<String>,
+
+    /// How pages are brought to --page-size
+    ///
+    /// - fit: Scale each page to fill the paper size (default)
+    /// - pad: Keep each page at its size, centered on the paper; larger
+    ///   pages are scaled down
+    #[arg(long, value_name = "MODE", default_value = "fit")]
+    #[arg(value_parser = ["fit", "pad"])]
+    pub page_fit: String,
+
+    /// Text centered at the top of each page
+    #[arg(long, value_name = "TEXT", long_help = HEADER_HELP)]
+    pub header: Option<String>,
+
+    /// Text centered at the bottom of each page
+    #[arg(long, value_name = "TEXT", long_help = FOOTER_HELP)]
+    pub footer: Option<String>,
+
+    /// Text at a position on each page, as POSITION=TEXT
+    ///
+    /// Positions: top-left, top-center, top-right, bottom-left,
+    /// bottom-center, bottom-right. Takes the same placeholders as
+    /// --header. Can be repeated.
+    #[arg(long, value_name = "POSITION=TEXT")]
+    pub stamp: Vec<String>,
+
+    /// Font size of headers, footers and stamps, in points
+    #[arg(long, value_name = "PT", default_value_t = Stamp::DEFAULT_FONT_SIZE)]
+    pub stamp_size: f32,
+
+    /// Output pages that get headers, footers and stamps (default: all)
+    #[arg(long, value_name = "RANGE")]
+    pub stamp_pages: Option<String>,
+
+    /// Stamp Bates numbers on every page, starting with this prefix
+    ///
+    /// The number is drawn at --bates-position unless a header, footer
+    /// or stamp shows it through the {bates} placeholder.
+    #[arg(long, value_name = "PREFIX")]
+    pub bates: Option<String>,
+
+    /// First Bates number
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub bates_start: u64,
+
+    /// Minimum number of digits in Bates numbers, padded with zeros
+    #[arg(long, value_name = "N", default_value_t = Bates::DEFAULT_DIGITS)]
+    pub bates_digits: usize,
+
+    /// Text after each Bates number
+    #[arg(long, value_name = "TEXT")]
+    pub bates_suffix: Option<String>,
+
+    /// Where Bates numbers are drawn
+    #[arg(long, value_name = "POSITION", default_value = "bottom-right")]
+    pub bates_position: String,
+
+    /// Write a log mapping Bates ranges to source files and pages
+    ///
+    /// The log is JSON if FILE ends in .json, and CSV otherwise.
+    #[arg(long, value_name = "FILE")]
+    pub bates_log: Option<PathBuf>,
+
+    /// Draw text across each page, e.g. "CONFIDENTIAL"
+    #[arg(long, value_name = "TEXT", conflicts_with = "watermark_pdf")]
+    pub watermark: Option<String>,
+
+    /// Draw a page of another PDF on each page, e.g. a letterhead
+    #[arg(long, value_name = "FILE")]
+    pub watermark_pdf: Option<PathBuf>,
+
+    /// Page of --watermark-pdf to use
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub watermark_page: u32,
+
+    /// Draw the watermark behind the page content instead of over it
+    #[arg(long)]
+    pub watermark_under: bool,
+
+    /// Watermark opacity, from 0 (invisible) to 1 (opaque)
+    #[arg(long, value_name = "OPACITY", default_value_t = Watermark::DEFAULT_OPACITY)]
+    pub watermark_opacity: f32,
+
+    /// Watermark rotation in degrees, counterclockwise
+    /// (default: diagonal for text, upright for PDF pages)
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    pub watermark_angle: Option<f32>,
+
+    /// Font size of a text watermark in points (default: as large as fits)
+    #[arg(long, value_name = "PT")]
+    pub watermark_size: Option<f32>,
+
+    /// Output pages that get the watermark (default: all)
+    #[arg(long, value_name = "RANGE")]
+    pub watermark_pages: Option<String>,
+
+    /// Page labels (page numbers shown by viewers) of the output
+    #[arg(
+        long,
+        value_name = "LABELS",
+        default_value = "keep",
+        long_help = PAGE_LABELS_HELP
+    )]
+    pub page_labels: String,
+
+    /// Embed a file in the output as an attachment (can be repeated)
+    ///
+    /// Attachments of the inputs are kept; files with the same name are
+    /// renamed, e.g. "data (2).xlsx".
+    #[arg(long, value_name = "FILE")]
+    pub attach: Vec<PathBuf>,
+
+    /// List each input's layers under its file name
+    ///
+    /// Layers (optional content groups) of all inputs are kept; by default
+    /// they are listed one after another in the layers panel.
+    #[arg(long)]
+    pub group_layers: bool,
+
+    /// Remove annotations of these types
+    ///
+    /// A comma-separated list of annotation subtypes, such as
+    /// Highlight,Ink. comments stands for all reviewer markup (notes,
+    /// highlights, drawings, stamps and their pop-ups) and keeps links and
+    /// form fields; all removes every annotation.
+    #[arg(long, value_name = "TYPES")]
+    pub remove_annotations: Option<String>,
+
+    /// Draw form fields and stamps into the page content
+    ///
+    /// Fields and stamps are drawn as they appear and can no longer be
+    /// edited. Flattening happens before --remove-annotations, so
+    /// --flatten --remove-annotations comments keeps stamps as drawn.
+    #[arg(long)]
+    pub flatten: bool,
+
+    /// Remove active content and hidden data from the inputs
+    ///
+    /// Removes JavaScript, launch actions and other actions run on events,
+    /// attachments, metadata, private application data and unused
+    /// objects. Links to pages and websites are kept, and so are files
+    /// added with --attach and metadata set with --title and the like.
+    /// What was removed is listed with --verbose or --format json.
+    #[arg(long)]
+    pub sanitize: bool,
+
+    /// Panel shown when the output opens
+    ///
+    /// none, outline, thumbs, fullscreen, layers or attachments. Without
+    /// it, the outline is shown with --bookmarks; otherwise the first
+    /// input's setting is kept.
+    #[arg(long, value_name = "MODE")]
+    pub page_mode: Option<String>,
+
+    /// How viewers arrange the output's pages
+    ///
+    /// single, continuous, two-column-left, two-column-right,
+    /// two-page-left or two-page-right (two pages at a time with odd
+    /// pages on the right, as in a book).
+    #[arg(long, value_name = "LAYOUT")]
+    pub page_layout: Option<String>,
+
+    /// Show the document title (see --title) in the window title bar
+    /// instead of the file name
+    #[arg(long)]
+    pub display_title: bool,
+
+    /// Resize the viewer window to fit the first page shown
+    #[arg(long)]
+    pub fit_window: bool,
+
+    /// Page and zoom the output opens at, as `PAGE[:ZOOM]`
+    ///
+    /// ZOOM is fit, fit-width or a percentage, e.g. 3:fit or 1:150%.
+    #[arg(long, value_name = "PAGE[:ZOOM]")]
+    pub open_at: Option<String>,
+
+    /// PDF version of the output (default: the newest of the inputs)
+    ///
+    /// 1.0 to 1.7, or 2.0. Features of later versions found in the output,
+    /// such as transparency or layers, are kept as they are and listed in
+    /// a warning, since older readers may not show them correctly.
+    #[arg(long, value_name = "VERSION")]
+    pub pdf_version: Option<String>,
+
+    /// Output format for progress and results
+    ///
+    /// - text: Human-readable messages (default)
+    /// - json: A single JSON document describing the run, printed to
+    ///   stdout when pdfcat exits. Warnings and errors go to stderr.
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    #[arg(value_parser = ["text", "json"])]
+    pub format: String,
+}
+
+/// Arguments for inspecting PDF files.
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// PDF files to inspect
+    ///
+    /// Reports version, page count, size, metadata, per-page sizes and
+    /// rotations, outline presence, encryption, fonts, attachments and
+    /// form fields for each file.
+    ///
+    /// Examples:
+    ///   pdfcat info report.pdf
+    ///   pdfcat info --format json *.pdf
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// List the size and rotation of every page
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Attempt to repair damaged PDFs before inspecting them
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Output format
+    ///
+    /// - text: Human-readable report (default)
+    /// - json: A JSON document with one entry per file
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    #[arg(value_parser = ["text", "json"])]
+    pub format: String,
+}
+
+/// Arguments for splitting a PDF file.
+#[derive(Args, Debug)]
+#[command(group(
+    ArgGroup::new("mode")
+        .required(true)
+        .args(["every", "bookmarks", "range", "max_size"])
+))]
+pub struct SplitArgs {
+    /// PDF file to split
+    #[arg(value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// Directory to write the parts to
+    #[arg(short, long, value_name = "DIR", default_value = ".")]
+    pub output_dir: PathBuf,
+
+    /// Start a new part every N pages
+    #[arg(long, value_name = "N")]
+    pub every: Option<u32>,
+
+    /// Start a new part at each top-level bookmark
+    ///
+    /// Pages before the first bookmark become a part of their own.
+    #[arg(long)]
+    pub bookmarks: bool,
+
+    /// Page range for one part (repeat for more parts)
+    ///
+    /// Examples:
+    ///   --range 1-10 --range 11-20
+    ///   --range "1,3,5" --range "2,4,6"
+    #[arg(long, value_name = "RANGE")]
+    pub range: Vec<String>,
+
+    /// Keep each part at or below this size (e.g., 500K, 10M, 1G)
+    ///
+    /// A single page larger than the limit becomes a part on its own.
+    #[arg(long, value_name = "SIZE")]
+    pub max_size: Option<String>,
+
+    /// File name template for the parts
+    ///
+    /// Placeholders:
+    ///   {stem}   Input file name without extension
+    ///   {n}      Part number, zero-padded
+    ///   {start}  First page of the part
+    ///   {end}    Last page of the part
+    ///   {title}  Bookmark title (with --bookmarks), otherwise the part number
+    #[arg(short, long, value_name = "TEMPLATE", default_value = DEFAULT_TEMPLATE)]
+    pub template: String,
+
+    /// Overwrite existing part files
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Suppress all non-error output
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Attempt to repair a damaged input PDF
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Output format
+    ///
+    /// - text: Human-readable messages (default)
+    /// - json: A JSON document listing the written parts
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    #[arg(value_parser = ["text", "json"])]
+    pub format: String,
+}
+
+/// Arguments for bursting a PDF file into single pages.
+#[derive(Args, Debug)]
+pub struct BurstArgs {
+    /// PDF file to burst
+    #[arg(value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// Directory to write the pages to
+    #[arg(short, long, value_name = "DIR", default_value = ".")]
+    pub output_dir: PathBuf,
+
+    /// File name template for the pages
+    ///
+    /// Placeholders:
+    ///   {stem}   Input file name without extension
+    ///   {n}      Page number, zero-padded
+    #[arg(short, long, value_name = "TEMPLATE", default_value = DEFAULT_TEMPLATE)]
+    pub template: String,
+
+    /// Overwrite existing page files
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Suppress all non-error output
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Attempt to repair a damaged input PDF
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Output format
+    ///
+    /// - text: Human-readable messages (default)
+    /// - json: A JSON document listing the written files
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    #[arg(value_parser = ["text", "json"])]
+    pub format: String,
+}
+
+impl BurstArgs {
+    /// Convert into the equivalent `split --every 1` arguments.
+    #[allow(unused)]
+    pub fn into_split_args(self) -> SplitArgs {
+        SplitArgs {
+            input: self.input,
+            output_dir: self.output_dir,
+            every: Some(1),
+            bookmarks: false,
+            range: Vec::new(),
+            max_size: None,
+            template: self.template,
+            force: self.force,
+            quiet: self.quiet,
+            repair: self.repair,
+            format: self.format,
+        }
+    }
+}
+
+/// Arguments for selecting pages from a PDF file.
+#[derive(Args, Debug)]
+pub struct SelectArgs {
+    /// PDF file to select pages from
+    #[arg(value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// Output PDF file path
+    #[arg(short, long, value_name = "FILE")]
+    pub output: PathBuf,
+
+    /// Pages to copy, in output order (e.g., "end-1" or "1,1,-2-end")
+    ///
+    /// Page numbers are 1-indexed; "end" is the last page and negative
+    /// numbers count back from it. Ranges may descend, "odd" and "even"
+    /// select alternate pages, and pages may be repeated.
+    #[arg(short, long, value_name = "RANGE")]
+    pub pages: String,
+
+    /// How to treat pages beyond the last page of the input
+    ///
+    /// - lenient: Skip missing pages; fail only if nothing is selected (default)
+    /// - strict: Fail if the range refers to any missing page
+    #[arg(long, value_name = "POLICY", default_value = "lenient")]
+    #[arg(value_parser = ["lenient", "strict"])]
+    pub range_policy: String,
+
+    /// Rotate the selected pages (90, 180, 270)
+    #[arg(long, value_name = "DEGREES")]
+    #[arg(value_parser = ["90", "180", "270"])]
+    pub rotate: Option<String>,
+
+    /// Compression level for output PDF
+    #[arg(short, long, value_name = "LEVEL", default_value = "standard")]
+    #[arg(value_parser = ["none", "standard", "maximum"])]
+    pub compression: String,
+
+    /// Force overwrite of existing output file without confirmation
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Never overwrite existing output file
+    #[arg(long, conflicts_with = "force")]
+    pub no_clobber: bool,
+
+    /// Suppress all non-error output
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Attempt to repair a damaged input PDF
+    #[arg(long)]
+    pub repair: bool,
+
+    /// Output format
+    ///
+    /// - text: Human-readable messages (default)
+    /// - json: A single JSON document describing the run
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    #[arg(value_parser = ["text", "json"])]
+    pub format: String,
 }
 
-impl Cli {
+impl SelectArgs {
+    /// Convert into the equivalent single-input merge arguments.
+    #[allow(unused)]
+    pub fn into_merge_args(self) -> MergeArgs {
+        MergeArgs {
+            inputs: vec![self.input],
+            output: self.output,
+            dry_run: false,
+            verbose: false,
+            force: self.force,
+            no_clobber: self.no_clobber,
+            quiet: self.quiet,
+            bookmarks: false,
+            compression: self.compression,
+            title: None,
+            author: None,
+            subject: None,
+            keywords: None,
+            continue_on_error: false,
+            repair: self.repair,
+            input_list: None,
+            jobs: None,
+            pages: Some(self.pages),
+            range_policy: self.range_policy,
+            rotate: self.rotate,
+            collate: false,
+            portfolio: false,
+            reverse: Vec::new(),
+            duplex: false,
+            insert_blank: Vec::new(),
+            separators: false,
+            layout: None,
+            crop: Vec::new(),
+            trim: Vec::new(),
+            bleed: Vec::new(),
+            page_size: None,
+            page_fit: "fit".to_string(),
+            header: None,
+            footer: None,
+            stamp: Vec::new(),
+            stamp_size: Stamp::DEFAULT_FONT_SIZE,
+            stamp_pages: None,
+            bates: None,
+            bates_start: 1,
+            bates_digits: Bates::DEFAULT_DIGITS,
+            bates_suffix: None,
+            bates_position: "bottom-right".to_string(),
+            bates_log: None,
+            watermark: None,
+            watermark_pdf: None,
+            watermark_page: 1,
+            watermark_under: false,
+            watermark_opacity: Watermark::DEFAULT_OPACITY,
+            watermark_angle: None,
+            watermark_size: None,
+            watermark_pages: None,
+            page_labels: "keep".to_string(),
+            attach: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            page_mode: None,
+            page_layout: None,
+            display_title: false,
+            fit_window: false,
+            open_at: None,
+            pdf_version: None,
+            format: self.format,
+        }
+    }
+}
+
+impl SplitArgs {
+    /// Convert CLI arguments into split options.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page range or size limit is invalid.
+    #[allow(unused)]
+    pub fn to_options(&self) -> Result<SplitOptions> {
+        let mode = if let Some(every) = self.every {
+            SplitMode::EveryPages(every)
+        } else if self.bookmarks {
+            SplitMode::Bookmarks
+        } else if let Some(ref max_size) = self.max_size {
+            SplitMode::MaxSize(parse_size_limit(max_size)?)
+        } else {
+            let ranges = self
+                .range
+                .iter()
+                .map(|range| PageRange::parse(range))
+                .collect::<Result<Vec<_>>>()?;
+            SplitMode::Ranges(ranges)
+        };
+
+        if mode == SplitMode::EveryPages(0) {
+            return Err(PdfCatError::invalid_config(
+                "Pages per part must be at least 1",
+            ));
+        }
+
+        Ok(SplitOptions {
+            mode,
+            output_dir: self.output_dir.clone(),
+            template: self.template.clone(),
+            overwrite: self.force,
+        })
+    }
+}
+
+impl MergeArgs {
     /// Convert CLI arguments into a validated Config.
     ///
     /// This method performs the following:
-    /// - Parses compression level and rotation
+    /// - Parses compression level, rotation and output format
     /// - Resolves overwrite mode
     /// - Parses page ranges
     /// - Constructs metadata
@@ -191,11 +919,12 @@ impl Cli {
     /// # Examples
     ///
     /// ```no_run
-    /// use pdfcat::cli::Cli;
+    /// use pdfcat::cli::{Cli, Command};
     /// use clap::Parser;
     ///
-    /// let cli = Cli::parse();
-    /// let config = cli.to_config().expect("Invalid configuration");
+    /// if let Some(Command::Merge(args)) = Cli::parse().into_command() {
+    ///     let config = args.to_config().expect("Invalid configuration");
+    /// }
     /// ```
     #[allow(unused)]
     pub fn to_config(&self) -> Result<Config> {
@@ -216,6 +945,10 @@ impl Cli {
             None
         };
 
+        // Parse output format
+        let output_format = OutputFormat::from_str(&self.format)
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
         // Determine overwrite mode
         let overwrite_mode = if self.force {
             OverwriteMode::Force
@@ -235,6 +968,160 @@ impl Cli {
             None
         };
 
+        // Parse page range policy
+        let page_range_policy = PageRangePolicy::from_str(&self.range_policy)
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
+        // Determine merge mode
+        let merge_mode = if self.collate {
+            MergeMode::Collate
+        } else if self.portfolio {
+            MergeMode::Portfolio
+        } else {
+            MergeMode::Concatenate
+        };
+
+        // Parse layout if provided
+        let layout = self.layout.as_deref().map(Layout::from_str).transpose()?;
+
+        // Parse page box edits; crop boxes first, since the others
+        // default to them
+        let box_edits = [
+            (BoxKind::Crop, &self.crop),
+            (BoxKind::Bleed, &self.bleed),
+            (BoxKind::Trim, &self.trim),
+        ]
+        .into_iter()
+        .flat_map(|(kind, specs)| specs.iter().map(move |spec| BoxEdit::parse(kind, spec)))
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
+        // Parse page size if provided
+        let page_size = self
+            .page_size
+            .as_deref()
+            .map(PageSize::from_str)
+            .transpose()
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
+        // Parse page fit
+        let page_fit = PageFit::from_str(&self.page_fit)
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
+        // Collect stamps: header, footer, then explicit stamps
+        let stamp_pages = self
+            .stamp_pages
+            .as_deref()
+            .map(PageRange::parse)
+            .transpose()
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+        if !(self.stamp_size > 0.0 && self.stamp_size.is_finite()) {
+            return Err(PdfCatError::invalid_config(format!(
+                "Invalid stamp size: {}. Must be a positive number of points",
+                self.stamp_size
+            )));
+        }
+
+        let mut stamps = Vec::new();
+        if let Some(ref header) = self.header {
+            stamps.push(Stamp::new(header, StampPosition::TopCenter));
+        }
+        if let Some(ref footer) = self.footer {
+            stamps.push(Stamp::new(footer, StampPosition::BottomCenter));
+        }
+        for spec in &self.stamp {
+            stamps.push(Stamp::parse(spec)?);
+        }
+        for stamp in &mut stamps {
+            stamp.font_size = self.stamp_size;
+            stamp.pages = stamp_pages.clone();
+        }
+
+        // Parse Bates numbering
+        let bates = match self.bates {
+            Some(ref prefix) => Some(Bates {
+                start: self.bates_start,
+                digits: self.bates_digits,
+                suffix: self.bates_suffix.clone().unwrap_or_default(),
+                position: StampPosition::from_str(&self.bates_position)
+                    .map_err(|e| PdfCatError::invalid_config(e.to_string()))?,
+                font_size: self.stamp_size,
+                ..Bates::new(prefix)
+            }),
+            None => None,
+        };
+
+        // Parse watermark
+        let content = match (&self.watermark, &self.watermark_pdf) {
+            (Some(text), _) => Some(WatermarkContent::Text(text.clone())),
+            (None, Some(path)) => Some(WatermarkContent::Page {
+                path: path.clone(),
+                page: self.watermark_page,
+            }),
+            (None, None) => None,
+        };
+        let mut watermarks = Vec::new();
+        if let Some(content) = content {
+            watermarks.push(Watermark {
+                content,
+                layer: if self.watermark_under {
+                    WatermarkLayer::Under
+                } else {
+                    WatermarkLayer::Over
+                },
+                opacity: self.watermark_opacity,
+                angle: self.watermark_angle,
+                font_size: self.watermark_size,
+                pages: self
+                    .watermark_pages
+                    .as_deref()
+                    .map(PageRange::parse)
+                    .transpose()
+                    .map_err(|e| PdfCatError::invalid_config(e.to_string()))?,
+            });
+        }
+
+        // Parse page labels
+        let page_labels = PageLabels::from_str(&self.page_labels)?;
+
+        // Parse annotations to remove
+        let remove_annotations = self
+            .remove_annotations
+            .as_deref()
+            .map(AnnotationFilter::from_str)
+            .transpose()
+            .map_err(|e| PdfCatError::invalid_config(e.to_string()))?;
+
+        // Parse viewer settings
+        let viewer = ViewerSettings {
+            page_mode: self
+                .page_mode
+                .as_deref()
+                .map(PageMode::from_str)
+                .transpose()
+                .map_err(|e| PdfCatError::invalid_config(e.to_string()))?,
+            page_layout: self
+                .page_layout
+                .as_deref()
+                .map(PageLayout::from_str)
+                .transpose()
+                .map_err(|e| PdfCatError::invalid_config(e.to_string()))?,
+            display_doc_title: self.display_title,
+            fit_window: self.fit_window,
+            open_at: self
+                .open_at
+                .as_deref()
+                .map(OpenAt::from_str)
+                .transpose()
+                .map_err(|e| PdfCatError::invalid_config(e.to_string()))?,
+        };
+
+        let pdf_version = self
+            .pdf_version
+            .as_deref()
+            .map(PdfVersion::from_str)
+            .transpose()?;
+
         // Construct metadata
         let metadata = Metadata::new(
             self.title.clone(),
@@ -255,9 +1142,33 @@ impl Cli {
             compression,
             metadata,
             continue_on_error: self.continue_on_error,
+            repair: self.repair,
             jobs: self.jobs,
             page_range,
+            page_range_policy,
             rotation,
+            merge_mode,
+            reverse_inputs: self.reverse.clone(),
+            duplex: self.duplex,
+            blank_pages: self.insert_blank.clone(),
+            separators: self.separators,
+            layout,
+            box_edits,
+            page_size,
+            page_fit,
+            stamps,
+            bates,
+            bates_log: self.bates_log.clone(),
+            watermarks,
+            page_labels,
+            attachments: self.attach.clone(),
+            group_layers: self.group_layers,
+            remove_annotations,
+            flatten: self.flatten,
+            sanitize: self.sanitize,
+            viewer,
+            pdf_version,
+            output_format,
         };
 
         // Validate the configuration
@@ -426,8 +1337,8 @@ impl Cli {
 mod tests {
     use super::*;
 
-    fn create_test_cli(inputs: Vec<&str>, output: &str) -> Cli {
-        Cli {
+    fn create_test_cli(inputs: Vec<&str>, output: &str) -> MergeArgs {
+        MergeArgs {
             inputs: inputs.iter().map(PathBuf::from).collect(),
             output: PathBuf::from(output),
             dry_run: false,
@@ -442,10 +1353,56 @@ mod tests {
             subject: None,
             keywords: None,
             continue_on_error: false,
+            repair: false,
             input_list: None,
             jobs: None,
             pages: None,
+            range_policy: "lenient".to_string(),
             rotate: None,
+            collate: false,
+            portfolio: false,
+            reverse: Vec::new(),
+            duplex: false,
+            insert_blank: Vec::new(),
+            separators: false,
+            layout: None,
+            crop: Vec::new(),
+            trim: Vec::new(),
+            bleed: Vec::new(),
+            page_size: None,
+            page_fit: "fit".to_string(),
+            header: None,
+            footer: None,
+            stamp: Vec::new(),
+            stamp_size: Stamp::DEFAULT_FONT_SIZE,
+            stamp_pages: None,
+            bates: None,
+            bates_start: 1,
+            bates_digits: Bates::DEFAULT_DIGITS,
+            bates_suffix: None,
+            bates_position: "bottom-right".to_string(),
+            bates_log: None,
+            watermark: None,
+            watermark_pdf: None,
+            watermark_page: 1,
+            watermark_under: false,
+            watermark_opacity: Watermark::DEFAULT_OPACITY,
+            watermark_angle: None,
+            watermark_size: None,
+            watermark_pages: None,
+            page_labels: "keep".to_string(),
+            attach: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            page_mode: None,
+            page_layout: None,
+            display_title: false,
+            fit_window: false,
+            open_at: None,
+            pdf_version: None,
+            format: "text".to_string(),
         }
     }
 
@@ -528,6 +1485,324 @@ mod tests {
         assert!(!page_range.contains(7));
     }
 
+    #[test]
+    fn test_cli_with_range_policy() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert_eq!(
+            cli.to_config().unwrap().page_range_policy,
+            PageRangePolicy::Lenient
+        );
+
+        cli.range_policy = "strict".to_string();
+        assert_eq!(
+            cli.to_config().unwrap().page_range_policy,
+            PageRangePolicy::Strict
+        );
+    }
+
+    #[test]
+    fn test_parse_portfolio() {
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "signed.pdf",
+            "-o",
+            "out.pdf",
+            "--portfolio",
+            "--attach",
+            "budget.xlsx",
+        ])
+        .unwrap();
+
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("expected merge command");
+        };
+        let config = args.to_config().unwrap();
+        assert_eq!(config.merge_mode, MergeMode::Portfolio);
+
+        let result = Cli::try_parse_from([
+            "pdfcat",
+            "a.pdf",
+            "-o",
+            "out.pdf",
+            "--portfolio",
+            "--collate",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_collate_with_reverse() {
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "front.pdf",
+            "back.pdf",
+            "-o",
+            "out.pdf",
+            "--collate",
+            "--reverse",
+            "2",
+        ])
+        .unwrap();
+
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("expected merge command");
+        };
+        let config = args.to_config().unwrap();
+        assert_eq!(config.merge_mode, MergeMode::Collate);
+        assert_eq!(config.reverse_inputs, vec![2]);
+    }
+
+    #[test]
+    fn test_cli_with_layout() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.layout = Some("booklet".to_string());
+        assert_eq!(cli.to_config().unwrap().layout, Some(Layout::Booklet));
+
+        cli.layout = Some("sideways".to_string());
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_blank_pages() {
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "a.pdf",
+            "b.pdf",
+            "-o",
+            "out.pdf",
+            "--duplex",
+            "--insert-blank",
+            "0,3",
+        ])
+        .unwrap();
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("Expected merge command");
+        };
+        let config = args.to_config().unwrap();
+        assert!(config.duplex);
+        assert_eq!(config.blank_pages, vec![0, 3]);
+
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "a.pdf",
+            "b.pdf",
+            "-o",
+            "out.pdf",
+            "--duplex",
+            "--collate",
+        ])
+        .unwrap();
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("Expected merge command");
+        };
+        assert!(args.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_stamps() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.footer = Some("Page {n} of {total}".to_string());
+        cli.stamp = vec!["top-right={file}".to_string()];
+        cli.stamp_size = 8.0;
+        cli.stamp_pages = Some("2-end".to_string());
+
+        let config = cli.to_config().unwrap();
+        assert_eq!(config.stamps.len(), 2);
+        assert_eq!(config.stamps[0].position, StampPosition::BottomCenter);
+        assert_eq!(config.stamps[1].text, "{file}");
+        assert!(config.stamps.iter().all(|stamp| stamp.font_size == 8.0));
+        assert!(config.stamps.iter().all(|stamp| stamp.pages.is_some()));
+
+        cli.stamp = vec!["left={file}".to_string()];
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_bates() {
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "a.pdf",
+            "-o",
+            "out.pdf",
+            "--bates",
+            "ACME-",
+            "--bates-start",
+            "42",
+            "--bates-digits",
+            "4",
+            "--bates-log",
+            "bates.csv",
+        ])
+        .unwrap();
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("expected merge command");
+        };
+
+        let config = args.to_config().unwrap();
+        let bates = config.bates.unwrap();
+        assert_eq!(bates.number(0), "ACME-0042");
+        assert_eq!(bates.position, StampPosition::BottomRight);
+        assert_eq!(config.bates_log, Some(PathBuf::from("bates.csv")));
+
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.bates_log = Some(PathBuf::from("bates.csv"));
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_watermark() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.watermark = Some("DRAFT".to_string());
+        cli.watermark_angle = Some(-30.0);
+        cli.watermark_pages = Some("1".to_string());
+
+        let config = cli.to_config().unwrap();
+        let watermark = &config.watermarks[0];
+        assert_eq!(
+            watermark.content,
+            WatermarkContent::Text("DRAFT".to_string())
+        );
+        assert_eq!(watermark.layer, WatermarkLayer::Over);
+        assert_eq!(watermark.angle, Some(-30.0));
+        assert!(watermark.pages.is_some());
+
+        let cli = Cli::try_parse_from([
+            "pdfcat",
+            "a.pdf",
+            "-o",
+            "out.pdf",
+            "--watermark-pdf",
+            "letterhead.pdf",
+            "--watermark-under",
+        ])
+        .unwrap();
+        let Some(Command::Merge(args)) = cli.into_command() else {
+            panic!("expected merge command");
+        };
+        let config = args.to_config().unwrap();
+        assert_eq!(config.watermarks[0].layer, WatermarkLayer::Under);
+        assert_eq!(
+            config.watermarks[0].content,
+            WatermarkContent::Page {
+                path: PathBuf::from("letterhead.pdf"),
+                page: 1
+            }
+        );
+
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.watermark = Some("DRAFT".to_string());
+        cli.watermark_opacity = 1.5;
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_attachments() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.attach = vec![PathBuf::from("data.xlsx"), PathBuf::from("notes.txt")];
+
+        let config = cli.to_config().unwrap();
+        assert_eq!(config.attachments, cli.attach);
+    }
+
+    #[test]
+    fn test_cli_with_annotation_options() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.remove_annotations = Some("comments,link".to_string());
+        cli.flatten = true;
+
+        let config = cli.to_config().unwrap();
+        let filter = config.remove_annotations.unwrap();
+        assert!(filter.matches(b"Highlight") && filter.matches(b"Link"));
+        assert!(!filter.matches(b"Widget"));
+        assert!(config.flatten);
+
+        cli.remove_annotations = Some("scribbles".to_string());
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_sanitize() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert!(!cli.to_config().unwrap().sanitize);
+
+        cli.sanitize = true;
+        assert!(cli.to_config().unwrap().sanitize);
+    }
+
+    #[test]
+    fn test_cli_with_group_layers() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert!(!cli.to_config().unwrap().group_layers);
+
+        cli.group_layers = true;
+        assert!(cli.to_config().unwrap().group_layers);
+    }
+
+    #[test]
+    fn test_cli_with_page_labels() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert_eq!(cli.to_config().unwrap().page_labels, PageLabels::Keep);
+
+        cli.page_labels = "{stem}-{n}".to_string();
+        assert_eq!(
+            cli.to_config().unwrap().page_labels,
+            PageLabels::Generate("{stem}-{n}".to_string())
+        );
+
+        cli.page_labels = "{n} of {stem}".to_string();
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_page_labels_help_shows_template() {
+        let mut command = <Cli as clap::CommandFactory>::command();
+        let help = command
+            .render_long_help()
+            .to_string()
+            .replace('\u{2060}', "");
+
+        assert!(help.contains("template ending in {n}:"));
+        assert!(help.contains("\"{stem}-{n}\" labels the pages of report.pdf report-1, report-2"));
+    }
+
+    #[test]
+    fn test_stamp_help_shows_placeholders() {
+        let mut command = <Cli as clap::CommandFactory>::command();
+        let help = command
+            .render_long_help()
+            .to_string()
+            .replace('\u{2060}', "");
+
+        assert!(help.contains("Placeholders: {n} (page number), {total} (page count)"));
+        assert!(help.contains("--footer \"Page {n} of {total}\""));
+    }
+
+    #[test]
+    fn test_cli_with_box_edits() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.trim = vec!["3mm".to_string()];
+        cli.crop = vec!["auto@1".to_string()];
+        let config = cli.to_config().unwrap();
+        let kinds: Vec<BoxKind> = config.box_edits.iter().map(|edit| edit.kind).collect();
+        assert_eq!(kinds, vec![BoxKind::Crop, BoxKind::Trim]);
+
+        cli.bleed = vec!["rect:1,2".to_string()];
+        assert!(cli.to_config().is_err());
+    }
+
+    #[test]
+    fn test_cli_with_page_size() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        cli.page_size = Some("a4".to_string());
+        cli.page_fit = "pad".to_string();
+        let config = cli.to_config().unwrap();
+        assert_eq!(config.page_size, Some(PageSize::A4));
+        assert_eq!(config.page_fit, PageFit::Pad);
+
+        cli.page_size = Some("huge".to_string());
+        assert!(cli.to_config().is_err());
+    }
+
     #[test]
     fn test_cli_with_invalid_page_range() {
         let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
@@ -536,6 +1811,27 @@ mod tests {
         assert!(cli.to_config().is_err());
     }
 
+    #[test]
+    fn test_cli_with_repair() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert!(!cli.to_config().unwrap().repair);
+
+        cli.repair = true;
+        assert!(cli.to_config().unwrap().repair);
+    }
+
+    #[test]
+    fn test_cli_with_format() {
+        let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
+        assert_eq!(cli.to_config().unwrap().output_format, OutputFormat::Text);
+
+        cli.format = "json".to_string();
+        assert_eq!(cli.to_config().unwrap().output_format, OutputFormat::Json);
+
+        cli.format = "xml".to_string();
+        assert!(cli.to_config().is_err());
+    }
+
     #[test]
     fn test_cli_with_metadata() {
         let mut cli = create_test_cli(vec!["a.pdf"], "out.pdf");
@@ -579,6 +1875,123 @@ mod tests {
         assert!(cli.validate().is_err());
     }
 
+    #[test]
+    fn test_parse_default_merge_command() {
+        let cli = Cli::try_parse_from(["pdfcat", "a.pdf", "b.pdf", "-o", "out.pdf"]).unwrap();
+
+        match cli.into_command() {
+            Some(Command::Merge(args)) => {
+                assert_eq!(args.inputs.len(), 2);
+                assert_eq!(args.output, PathBuf::from("out.pdf"));
+            }
+            other => panic!("Expected merge command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_merge_subcommand() {
+        let cli = Cli::try_parse_from(["pdfcat", "merge", "a.pdf", "-o", "out.pdf"]).unwrap();
+        assert!(matches!(cli.into_command(), Some(Command::Merge(_))));
+    }
+
+    #[test]
+    fn test_parse_info_subcommand() {
+        let cli = Cli::try_parse_from(["pdfcat", "info", "--format", "json", "a.pdf"]).unwrap();
+
+        match cli.into_command() {
+            Some(Command::Info(args)) => {
+                assert_eq!(args.files, vec![PathBuf::from("a.pdf")]);
+                assert_eq!(args.format, "json");
+            }
+            other => panic!("Expected info command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_split_subcommand() {
+        let cli = Cli::try_parse_from(["pdfcat", "split", "book.pdf", "--every", "5"]).unwrap();
+
+        match cli.into_command() {
+            Some(Command::Split(args)) => {
+                let options = args.to_options().unwrap();
+                assert_eq!(options.mode, SplitMode::EveryPages(5));
+                assert_eq!(options.template, DEFAULT_TEMPLATE);
+                assert!(!options.overwrite);
+            }
+            other => panic!("Expected split command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_split_ranges_and_size() {
+        let cli = Cli::try_parse_from([
+            "pdfcat", "split", "book.pdf", "--range", "1-3", "--range", "4,6",
+        ])
+        .unwrap();
+        let Some(Command::Split(args)) = cli.into_command() else {
+            panic!("Expected split command");
+        };
+        match args.to_options().unwrap().mode {
+            SplitMode::Ranges(ranges) => assert_eq!(ranges.len(), 2),
+            mode => panic!("Expected ranges, got {mode:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["pdfcat", "split", "book.pdf", "--max-size", "2M"]).unwrap();
+        let Some(Command::Split(args)) = cli.into_command() else {
+            panic!("Expected split command");
+        };
+        assert_eq!(
+            args.to_options().unwrap().mode,
+            SplitMode::MaxSize(2 * 1024 * 1024)
+        );
+    }
+
+    #[test]
+    fn test_parse_split_requires_single_mode() {
+        assert!(Cli::try_parse_from(["pdfcat", "split", "book.pdf"]).is_err());
+        assert!(
+            Cli::try_parse_from(["pdfcat", "split", "book.pdf", "--every", "2", "--bookmarks"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_burst_subcommand() {
+        let cli = Cli::try_parse_from(["pdfcat", "burst", "book.pdf", "-o", "pages"]).unwrap();
+
+        let Some(Command::Burst(args)) = cli.into_command() else {
+            panic!("expected burst command");
+        };
+        let options = args.into_split_args().to_options().unwrap();
+        assert_eq!(options.mode, SplitMode::EveryPages(1));
+        assert_eq!(options.output_dir, PathBuf::from("pages"));
+    }
+
+    #[test]
+    fn test_parse_select_subcommand() {
+        let cli = Cli::try_parse_from([
+            "pdfcat", "select", "book.pdf", "--pages", "end-1", "-o", "out.pdf",
+        ])
+        .unwrap();
+
+        let Some(Command::Select(args)) = cli.into_command() else {
+            panic!("expected select command");
+        };
+        let config = args.into_merge_args().to_config().unwrap();
+        assert_eq!(config.inputs, vec![PathBuf::from("book.pdf")]);
+        assert_eq!(config.page_range.unwrap().to_pages(3), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_parse_select_requires_pages() {
+        assert!(Cli::try_parse_from(["pdfcat", "select", "book.pdf", "-o", "out.pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_merge_requires_output() {
+        assert!(Cli::try_parse_from(["pdfcat", "a.pdf"]).is_err());
+    }
+
     #[tokio::test]
     async fn test_get_all_inputs_no_list() {
         let cli = create_test_cli(vec![], "out.pdf");
@@ -7,36 +7,194 @@ mod cli;
 use clap::Parser;
 use std::process;
 
-use crate::cli::Cli;
-use pdfcat::config::Config;
+use crate::cli::{Cli, Command, InfoArgs, MergeArgs, SplitArgs};
+use pdfcat::config::{Config, MergeMode, PageRange};
 use pdfcat::error::PdfCatError;
-use pdfcat::io::PdfWriter;
+use pdfcat::inspect::Inspector;
+use pdfcat::io::{PdfReader, PdfWriter};
 use pdfcat::merge::Merger;
-use pdfcat::output::{OutputFormatter, display_validation_summary};
-use pdfcat::validation::Validator; // display_load_statistics 
+use pdfcat::merge::bates;
+use pdfcat::output::{
+    InfoReport, OutputFormatter, RunReport, SplitReport, display_document_info,
+    display_mixed_page_sizes, display_tag_loss, display_unsupported_features,
+    display_validation_summary,
+};
+use pdfcat::split::Splitter;
+use pdfcat::validation::{SkippedFile, Validator}; // display_load_statistics 
 
 #[tokio::main]
 async fn main() {
     // Parse CLI arguments
     let cli = Cli::parse();
 
-    // Run the application and handle errors
-    if let Err(err) = run(cli).await {
+    // Run the requested command and handle errors
+    let result = match cli.into_command() {
+        Some(Command::Merge(args)) => merge(args).await,
+        Some(Command::Info(args)) => info(args).await,
+        Some(Command::Split(args)) => split(args).await,
+        Some(Command::Burst(args)) => split(args.into_split_args()).await,
+        Some(Command::Select(args)) => merge(args.into_merge_args()).await,
+        None => Err(PdfCatError::NoFilesToMerge),
+    };
+
+    if let Err(err) = result {
         eprintln!("Error: {err}");
         process::exit(err.exit_code());
     }
 }
 
+/// Run the merge command, emitting a JSON report if requested.
+async fn merge(args: MergeArgs) -> Result<(), PdfCatError> {
+    let json = args.format == "json";
+    let mut report = RunReport::new(args.output.clone(), args.dry_run);
+
+    let result = run(args, &mut report).await;
+
+    match &result {
+        Ok(()) => report.success = true,
+        Err(err) => report.record_error(err),
+    }
+
+    if json {
+        match report.to_json() {
+            Ok(json) => println!("{json}"),
+            Err(err) => eprintln!("Error: {err}"),
+        }
+    }
+
+    result
+}
+
+/// Run the info command.
+///
+/// Every file is inspected even if some fail. The error for the last
+/// failing file determines the exit code; earlier failures are printed
+/// as they would be with `--continue-on-error`.
+async fn info(args: InfoArgs) -> Result<(), PdfCatError> {
+    let formatter = if args.format == "json" {
+        OutputFormatter::json()
+    } else {
+        OutputFormatter::new(false, args.verbose)
+    };
+
+    let inspector = if args.repair {
+        Inspector::with_repair()
+    } else {
+        Inspector::new()
+    };
+
+    let mut report = InfoReport::default();
+    let mut errors = Vec::new();
+
+    for path in &args.files {
+        match inspector.inspect(path).await {
+            Ok(info) => report.files.push(info),
+            Err(err) => {
+                report.skipped.push(SkippedFile::new(path.clone(), &err));
+                errors.push(err);
+            }
+        }
+    }
+
+    if formatter.is_json() {
+        formatter.print_json(&report)?;
+    } else {
+        for info in &report.files {
+            display_document_info(&formatter, info);
+        }
+    }
+
+    let last_error = errors.pop();
+    for err in errors {
+        formatter.error(&err.to_string());
+    }
+
+    match last_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Run the split command, emitting a JSON report if requested.
+async fn split(args: SplitArgs) -> Result<(), PdfCatError> {
+    let formatter = if args.format == "json" {
+        OutputFormatter::json()
+    } else {
+        OutputFormatter::new(args.quiet, false)
+    };
+
+    let mut report = SplitReport::new(args.input.clone());
+    let result = run_split(&args, &formatter, &mut report).await;
+
+    match &result {
+        Ok(()) => report.success = true,
+        Err(err) => report.record_error(err),
+    }
+
+    if formatter.is_json() {
+        formatter.print_json(&report)?;
+    }
+
+    result
+}
+
+/// Split logic, recording written parts in `report`.
+async fn run_split(
+    args: &SplitArgs,
+    formatter: &OutputFormatter,
+    report: &mut SplitReport,
+) -> Result<(), PdfCatError> {
+    let options = args.to_options()?;
+
+    if !args.input.is_file() {
+        return Err(PdfCatError::file_not_found(args.input.clone()));
+    }
+
+    let reader = if args.repair {
+        PdfReader::with_repair()
+    } else {
+        PdfReader::new()
+    };
+    let loaded = reader.load(&args.input).await?;
+
+    if let Some(ref repair) = loaded.repair {
+        formatter.warning(&format!("Repaired {}: {repair}", args.input.display()));
+    }
+
+    formatter.info(&format!(
+        "Splitting {} ({} pages)...",
+        args.input.display(),
+        loaded.page_count
+    ));
+
+    let splitter = Splitter::new(options);
+    report.parts = splitter.split(&args.input, &loaded.document).await?;
+
+    for output in &report.parts {
+        formatter.success(&format!(
+            "Wrote {} (pages {}, {})",
+            output.part.path.display(),
+            PageRange::from_pages(&output.part.pages),
+            output.format_file_size()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Main application logic.
-async fn run(cli: Cli) -> Result<(), PdfCatError> {
+///
+/// The outcome of each stage is recorded in `report` so it can be
+/// emitted as JSON even when a later stage fails.
+async fn run(args: MergeArgs, report: &mut RunReport) -> Result<(), PdfCatError> {
     // Validate CLI arguments
-    cli.validate()?;
+    args.validate()?;
 
     // Get all inputs (including from input-list if specified)
-    let all_inputs = cli.get_all_inputs().await?;
+    let all_inputs = args.get_all_inputs().await?;
 
     // Convert CLI to config
-    let mut config = cli.to_config()?;
+    let mut config = args.to_config()?;
     config.inputs = all_inputs;
 
     // Create output formatter
@@ -50,11 +208,20 @@ async fn run(cli: Cli) -> Result<(), PdfCatError> {
 
     // Validate configuration and inputs
     formatter.info("Validating input files...");
-    let validator = Validator::new();
+    let validator = if config.repair {
+        Validator::with_repair()
+    } else {
+        Validator::new()
+    };
     let validation_summary = validator.validate_config(&config).await?;
+    report.record_validation(&validation_summary);
 
     if formatter.should_print() {
         display_validation_summary(&formatter, &validation_summary);
+        if config.page_size.is_none() && config.merge_mode != MergeMode::Portfolio {
+            display_mixed_page_sizes(&formatter, &validation_summary);
+        }
+        display_tag_loss(&formatter, &validation_summary, &config);
         formatter.blank_line();
     }
 
@@ -81,6 +248,8 @@ async fn run(cli: Cli) -> Result<(), PdfCatError> {
 
     let merger = Merger::new();
     let result = merger.merge(&config).await?;
+    report.record_merge(&result);
+    display_unsupported_features(&formatter, &result);
 
     if formatter.should_print() {
         formatter.blank_line();
@@ -99,6 +268,13 @@ async fn run(cli: Cli) -> Result<(), PdfCatError> {
     let write_stats = writer
         .save_with_stats(&result.document, &config.output)
         .await?;
+    report.record_output(&write_stats);
+
+    // Write the Bates log next to the output
+    if let Some(ref log_path) = config.bates_log {
+        bates::write_log(log_path, &result.bates_log).await?;
+        formatter.info(&format!("Wrote Bates log to: {}", log_path.display()));
+    }
 
     if formatter.should_print() {
         formatter.blank_line();
@@ -113,6 +289,13 @@ async fn run(cli: Cli) -> Result<(), PdfCatError> {
             formatter.section("Statistics");
             formatter.detail("Input files", &result.statistics.files_merged.to_string());
             formatter.detail("Total pages", &result.statistics.total_pages.to_string());
+            formatter.detail("PDF version", &result.statistics.pdf_version.to_string());
+            if result.statistics.blank_pages_added > 0 {
+                formatter.detail(
+                    "Blank pages added",
+                    &result.statistics.blank_pages_added.to_string(),
+                );
+            }
             formatter.detail("Input size", &result.statistics.format_input_size());
             formatter.detail("Output size", &write_stats.format_file_size());
             formatter.detail(
@@ -139,6 +322,14 @@ async fn run(cli: Cli) -> Result<(), PdfCatError> {
             if !config.metadata.is_empty() {
                 formatter.detail("Metadata", "Set");
             }
+
+            if !result.sanitized_files.is_empty() {
+                formatter.blank_line();
+                formatter.section("Sanitized");
+                for (path, sanitized) in &result.sanitized_files {
+                    formatter.detail(&path.display().to_string(), &sanitized.to_string());
+                }
+            }
         }
     }
 
@@ -169,7 +360,7 @@ async fn handle_output_overwrite(
         OverwriteMode::Prompt => {
             // Ask user for confirmation
             if formatter.is_quiet() {
-                // In quiet mode, treat as no-clobber
+                // In quiet and JSON modes, treat as no-clobber
                 return Err(PdfCatError::output_exists(config.output.clone()));
             }
 
@@ -201,7 +392,10 @@ async fn handle_output_overwrite(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pdfcat::config::{CompressionLevel, Metadata, OverwriteMode};
+    use pdfcat::config::{
+        CompressionLevel, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit, PageLabels,
+        PageRangePolicy, ViewerSettings,
+    };
     use std::path::PathBuf;
 
     fn create_test_config() -> Config {
@@ -216,9 +410,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         }
     }
 
@@ -6,7 +6,10 @@
 //! using criterion for statistical analysis.
 
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use pdfcat::config::{CompressionLevel, Config, Metadata, OverwriteMode};
+use pdfcat::config::{
+    CompressionLevel, Config, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit,
+    PageLabels, PageRangePolicy, ViewerSettings,
+};
 use pdfcat::io::{PdfReader, PdfWriter};
 use pdfcat::merge::merge_pdfs;
 use pdfcat::validation::Validator;
@@ -148,9 +151,33 @@ fn bench_merge_two_pdfs(c: &mut Criterion) {
                 compression: CompressionLevel::Standard,
                 metadata: Metadata::default(),
                 continue_on_error: false,
+                repair: false,
                 jobs: None,
                 page_range: None,
+                page_range_policy: PageRangePolicy::Lenient,
                 rotation: None,
+                merge_mode: MergeMode::Concatenate,
+                reverse_inputs: Vec::new(),
+                duplex: false,
+                blank_pages: Vec::new(),
+                separators: false,
+                layout: None,
+                box_edits: Vec::new(),
+                page_size: None,
+                page_fit: PageFit::Fit,
+                stamps: Vec::new(),
+                bates: None,
+                bates_log: None,
+                watermarks: Vec::new(),
+                page_labels: PageLabels::Keep,
+                attachments: Vec::new(),
+                group_layers: false,
+                remove_annotations: None,
+                flatten: false,
+                sanitize: false,
+                viewer: ViewerSettings::default(),
+                pdf_version: None,
+                output_format: OutputFormat::Text,
             };
 
             let result = merge_pdfs(std::hint::black_box(&config)).await;
@@ -199,9 +226,33 @@ fn bench_merge_compression(c: &mut Criterion) {
                         compression: level,
                         metadata: Metadata::default(),
                         continue_on_error: false,
+                        repair: false,
                         jobs: None,
                         page_range: None,
+                        page_range_policy: PageRangePolicy::Lenient,
                         rotation: None,
+                        merge_mode: MergeMode::Concatenate,
+                        reverse_inputs: Vec::new(),
+                        duplex: false,
+                        blank_pages: Vec::new(),
+                        separators: false,
+                        layout: None,
+                        box_edits: Vec::new(),
+                        page_size: None,
+                        page_fit: PageFit::Fit,
+                        stamps: Vec::new(),
+                        bates: None,
+                        bates_log: None,
+                        watermarks: Vec::new(),
+                        page_labels: PageLabels::Keep,
+                        attachments: Vec::new(),
+                        group_layers: false,
+                        remove_annotations: None,
+                        flatten: false,
+                        sanitize: false,
+                        viewer: ViewerSettings::default(),
+                        pdf_version: None,
+                        output_format: OutputFormat::Text,
                     };
 
                     let result = merge_pdfs(std::hint::black_box(&config)).await;
@@ -242,9 +293,33 @@ fn bench_merge_with_bookmarks(c: &mut Criterion) {
                 compression: CompressionLevel::Standard,
                 metadata: Metadata::default(),
                 continue_on_error: false,
+                repair: false,
                 jobs: None,
                 page_range: None,
+                page_range_policy: PageRangePolicy::Lenient,
                 rotation: None,
+                merge_mode: MergeMode::Concatenate,
+                reverse_inputs: Vec::new(),
+                duplex: false,
+                blank_pages: Vec::new(),
+                separators: false,
+                layout: None,
+                box_edits: Vec::new(),
+                page_size: None,
+                page_fit: PageFit::Fit,
+                stamps: Vec::new(),
+                bates: None,
+                bates_log: None,
+                watermarks: Vec::new(),
+                page_labels: PageLabels::Keep,
+                attachments: Vec::new(),
+                group_layers: false,
+                remove_annotations: None,
+                flatten: false,
+                sanitize: false,
+                viewer: ViewerSettings::default(),
+                pdf_version: None,
+                output_format: OutputFormat::Text,
             };
 
             let result = merge_pdfs(std::hint::black_box(&config)).await;
@@ -331,9 +406,33 @@ fn bench_merge_scaling(c: &mut Criterion) {
                         compression: CompressionLevel::Standard,
                         metadata: Metadata::default(),
                         continue_on_error: false,
+                        repair: false,
                         jobs: Some(4),
                         page_range: None,
+                        page_range_policy: PageRangePolicy::Lenient,
                         rotation: None,
+                        merge_mode: MergeMode::Concatenate,
+                        reverse_inputs: Vec::new(),
+                        duplex: false,
+                        blank_pages: Vec::new(),
+                        separators: false,
+                        layout: None,
+                        box_edits: Vec::new(),
+                        page_size: None,
+                        page_fit: PageFit::Fit,
+                        stamps: Vec::new(),
+                        bates: None,
+                        bates_log: None,
+                        watermarks: Vec::new(),
+                        page_labels: PageLabels::Keep,
+                        attachments: Vec::new(),
+                        group_layers: false,
+                        remove_annotations: None,
+                        flatten: false,
+                        sanitize: false,
+                        viewer: ViewerSettings::default(),
+                        pdf_version: None,
+                        output_format: OutputFormat::Text,
                     };
 
                     let result = merge_pdfs(std::hint::black_box(&config)).await;
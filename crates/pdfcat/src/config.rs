@@ -10,7 +10,11 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{PdfCatError, Result};
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 /// Compression level for the output PDF.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -50,6 +54,39 @@ impl FromStr for CompressionLevel {
     }
 }
 
+/// Format of the output printed to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human-readable status messages.
+    #[default]
+    Text,
+    /// A single JSON document describing the whole run.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = crate::PdfCatError;
+    /// Parse output format from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - String representation: "text" or "json"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid output format.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!("Invalid output format: {s}. Must be one of: text, json"),
+            }),
+        }
+    }
+}
+
 /// Page rotation in degrees.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -100,6 +137,13 @@ impl Rotation {
 /// - "1-5" - range of pages (inclusive)
 /// - "1,3,5" - multiple individual pages
 /// - "1-5,10-15" - combination of ranges
+/// - "end" or "-1" - the last page, "-2" the one before it, and so on
+/// - "-3-end" - the last three pages
+/// - "10-1" or "end-1" - a range in descending order
+/// - "odd" and "even" - every odd or even page
+///
+/// Pages are selected in the order they are listed, and a page listed
+/// more than once is selected more than once.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub struct PageRange {
@@ -109,157 +153,1570 @@ pub struct PageRange {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 enum PageRangeItem {
-    Single(u32),
-    Range(u32, u32),
+    Single(PageRef),
+    Range(PageRef, PageRef),
+    Odd,
+    Even,
+}
+
+/// A page position, counted from the start or from the end of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PageRef {
+    /// 1-indexed page number.
+    Page(u32),
+    /// 1-indexed position counted back from the last page.
+    FromEnd(u32),
+}
+
+impl PageRef {
+    /// Parse a page reference at the start of `s`, returning the rest.
+    fn parse_prefix(s: &str) -> Result<(Self, &str)> {
+        let s = s.trim_start();
+
+        if s.get(..3)
+            .is_some_and(|word| word.eq_ignore_ascii_case("end"))
+        {
+            return Ok((Self::FromEnd(1), &s[3..]));
+        }
+
+        let (from_end, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let len = digits.bytes().take_while(u8::is_ascii_digit).count();
+
+        let number: u32 = digits[..len]
+            .parse()
+            .map_err(|_| PdfCatError::invalid_config(format!("Invalid page number: {s}")))?;
+
+        if number == 0 {
+            return Err(PdfCatError::invalid_config(
+                "Page numbers must be positive (1-indexed)",
+            ));
+        }
+
+        let page = if from_end {
+            Self::FromEnd(number)
+        } else {
+            Self::Page(number)
+        };
+
+        Ok((page, &digits[len..]))
+    }
+
+    /// Resolve to a 1-indexed page number, which may lie outside the document.
+    fn resolve(self, max_pages: u32) -> i64 {
+        match self {
+            Self::Page(page) => i64::from(page),
+            Self::FromEnd(offset) => i64::from(max_pages) - i64::from(offset) + 1,
+        }
+    }
+}
+
+impl fmt::Display for PageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Page(page) => write!(f, "{page}"),
+            Self::FromEnd(1) => write!(f, "end"),
+            Self::FromEnd(offset) => write!(f, "-{offset}"),
+        }
+    }
+}
+
+impl PageRangeItem {
+    /// Parse a single comma-separated item.
+    fn parse(part: &str) -> Result<Self> {
+        if part.eq_ignore_ascii_case("odd") {
+            return Ok(Self::Odd);
+        }
+        if part.eq_ignore_ascii_case("even") {
+            return Ok(Self::Even);
+        }
+
+        let (start, rest) = PageRef::parse_prefix(part)?;
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(Self::Single(start));
+        }
+
+        let invalid = || {
+            PdfCatError::invalid_config(format!(
+                "Invalid page range format: {part}. Expected format like '1-5'"
+            ))
+        };
+
+        let rest = rest.strip_prefix('-').ok_or_else(invalid)?;
+        let (end, rest) = PageRef::parse_prefix(rest)?;
+        if !rest.trim().is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self::Range(start, end))
+    }
+
+    /// Append the pages selected by this item, in order.
+    fn push_pages(&self, max_pages: u32, pages: &mut Vec<u32>) {
+        let last = i64::from(max_pages);
+        let clamp = |page: i64| page.clamp(0, last + 1) as u32;
+
+        match self {
+            Self::Single(page) => {
+                let page = page.resolve(max_pages);
+                if (1..=last).contains(&page) {
+                    pages.push(page as u32);
+                }
+            }
+            Self::Range(start, end) => {
+                let start = clamp(start.resolve(max_pages));
+                let end = clamp(end.resolve(max_pages));
+                if start <= end {
+                    pages.extend(start.max(1)..=end.min(max_pages));
+                } else {
+                    pages.extend((end.max(1)..=start.min(max_pages)).rev());
+                }
+            }
+            Self::Odd => pages.extend((1..=max_pages).step_by(2)),
+            Self::Even => pages.extend((2..=max_pages).step_by(2)),
+        }
+    }
+}
+
+impl fmt::Display for PageRangeItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(page) => write!(f, "{page}"),
+            Self::Range(start, end) => write!(f, "{start}-{end}"),
+            Self::Odd => write!(f, "odd"),
+            Self::Even => write!(f, "even"),
+        }
+    }
+}
+
+impl PageRange {
+    /// Parse a page range string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Page range string (e.g., "1-5,10,15-20", "end-1" or "odd,-1")
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string format is invalid or contains invalid page numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::PageRange;
+    ///
+    /// let range = PageRange::parse("1-5,10").unwrap();
+    /// assert!(range.contains(3));
+    /// assert!(range.contains(10));
+    /// assert!(!range.contains(7));
+    ///
+    /// let reversed = PageRange::parse("-3-end,1,1").unwrap();
+    /// assert_eq!(reversed.to_pages(10), vec![8, 9, 10, 1, 1]);
+    /// ```
+    pub fn parse(s: &str) -> Result<Self> {
+        if s.trim().is_empty() {
+            return Err(PdfCatError::invalid_config("Page range cannot be empty"));
+        }
+
+        let ranges = s
+            .split(',')
+            .map(|part| PageRangeItem::parse(part.trim()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { ranges })
+    }
+
+    /// Create a page range covering exactly the given pages.
+    ///
+    /// Runs of consecutive pages, ascending or descending, are collapsed
+    /// into ranges. Order and duplicates are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `pages` - 1-indexed page numbers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::PageRange;
+    ///
+    /// let range = PageRange::from_pages(&[1, 2, 3, 7, 5, 4]);
+    /// assert_eq!(range.to_string(), "1-3,7,5-4");
+    /// ```
+    pub fn from_pages(pages: &[u32]) -> Self {
+        let mut ranges = Vec::new();
+        let mut iter = pages.iter().copied().peekable();
+
+        while let Some(start) = iter.next() {
+            let mut end = start;
+            if iter.peek() == Some(&(end + 1)) {
+                while iter.peek() == Some(&(end + 1)) {
+                    end += 1;
+                    iter.next();
+                }
+            } else {
+                while end > 1 && iter.peek() == Some(&(end - 1)) {
+                    end -= 1;
+                    iter.next();
+                }
+            }
+
+            if start == end {
+                ranges.push(PageRangeItem::Single(PageRef::Page(start)));
+            } else {
+                ranges.push(PageRangeItem::Range(
+                    PageRef::Page(start),
+                    PageRef::Page(end),
+                ));
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Check if a page number is included in this range.
+    ///
+    /// Items counted from the end of the document (such as "end" or "-2")
+    /// depend on the page count and never match here; use
+    /// [`to_pages`](Self::to_pages) when the page count is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - 1-indexed page number
+    pub fn contains(&self, page: u32) -> bool {
+        self.ranges.iter().any(|item| match item {
+            PageRangeItem::Single(PageRef::Page(p)) => *p == page,
+            PageRangeItem::Range(PageRef::Page(start), PageRef::Page(end)) => {
+                page >= *start.min(end) && page <= *start.max(end)
+            }
+            PageRangeItem::Odd => page % 2 == 1,
+            PageRangeItem::Even => page > 0 && page.is_multiple_of(2),
+            _ => false,
+        })
+    }
+
+    /// Check whether every page this range refers to exists in a document.
+    ///
+    /// `odd` and `even` always fit, since they only select existing pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_pages` - Number of pages in the document
+    pub fn fits(&self, max_pages: u32) -> bool {
+        let fits = |page: &PageRef| (1..=i64::from(max_pages)).contains(&page.resolve(max_pages));
+
+        self.ranges.iter().all(|item| match item {
+            PageRangeItem::Single(page) => fits(page),
+            PageRangeItem::Range(start, end) => fits(start) && fits(end),
+            PageRangeItem::Odd | PageRangeItem::Even => true,
+        })
+    }
+
+    /// Resolve this range against a document, applying an out-of-range policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the document, used in error messages
+    /// * `max_pages` - Number of pages in the document
+    /// * `policy` - How to treat pages beyond the end of the document
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PdfCatError::InvalidPageRange`] if no page is selected, or
+    /// if `policy` is strict and the range refers to a missing page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::{PageRange, PageRangePolicy};
+    /// use std::path::Path;
+    ///
+    /// let range = PageRange::parse("1-10").unwrap();
+    /// let path = Path::new("short.pdf");
+    ///
+    /// let pages = range.resolve(path, 5, PageRangePolicy::Lenient).unwrap();
+    /// assert_eq!(pages, vec![1, 2, 3, 4, 5]);
+    /// assert!(range.resolve(path, 5, PageRangePolicy::Strict).is_err());
+    /// ```
+    pub fn resolve(
+        &self,
+        path: &Path,
+        max_pages: u32,
+        policy: PageRangePolicy,
+    ) -> Result<Vec<u32>> {
+        let pages = self.to_pages(max_pages);
+
+        if pages.is_empty() || (policy == PageRangePolicy::Strict && !self.fits(max_pages)) {
+            return Err(PdfCatError::InvalidPageRange {
+                path: path.to_path_buf(),
+                range: self.to_string(),
+                total_pages: max_pages as usize,
+            });
+        }
+
+        Ok(pages)
+    }
+
+    /// Get all page numbers included in this range for a document.
+    ///
+    /// Pages outside the document are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_pages` - Number of pages in the document
+    ///
+    /// # Returns
+    ///
+    /// A vector of 1-indexed page numbers in selection order, including
+    /// any repeated pages.
+    pub fn to_pages(&self, max_pages: u32) -> Vec<u32> {
+        let mut pages = Vec::new();
+        for item in &self.ranges {
+            item.push_pages(max_pages, &mut pages);
+        }
+        pages
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.ranges.iter().map(ToString::to_string).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// How page ranges treat pages that do not exist in a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageRangePolicy {
+    /// Skip pages beyond the end of the document (default).
+    ///
+    /// It is still an error if no page at all is selected.
+    #[default]
+    Lenient,
+    /// Reject a range that refers to any page beyond the end of the document.
+    Strict,
+}
+
+impl FromStr for PageRangePolicy {
+    type Err = crate::PdfCatError;
+    /// Parse page range policy from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - String representation: "lenient" or "strict"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid policy.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "lenient" => Ok(Self::Lenient),
+            "strict" => Ok(Self::Strict),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!("Invalid page range policy: {s}. Must be one of: lenient, strict"),
+            }),
+        }
+    }
+}
+
+/// PDF metadata to set on the output document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Document title.
+    pub title: Option<String>,
+    /// Document author.
+    pub author: Option<String>,
+    /// Document subject.
+    pub subject: Option<String>,
+    /// Document keywords (comma-separated).
+    pub keywords: Option<String>,
+}
+
+impl Metadata {
+    /// Check if any metadata fields are set.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.author.is_none()
+            && self.subject.is_none()
+            && self.keywords.is_none()
+    }
+
+    /// Create metadata from optional strings, trimming whitespace.
+    pub fn new(
+        title: Option<String>,
+        author: Option<String>,
+        subject: Option<String>,
+        keywords: Option<String>,
+    ) -> Self {
+        let to_string_opt = |opt: Option<String>| {
+            opt.filter(|s| !s.trim().is_empty())
+                .map(|s| s.trim().to_string())
+        };
+
+        Self {
+            title: to_string_opt(title),
+            author: to_string_opt(author),
+            subject: to_string_opt(subject),
+            keywords: to_string_opt(keywords),
+        }
+    }
+}
+
+/// How pages from several inputs are ordered in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    /// All pages of each input, one input after another (default).
+    #[default]
+    Concatenate,
+    /// Interleave pages: the first page of each input, then the second
+    /// page of each input, and so on. Inputs that run out of pages are
+    /// skipped.
+    Collate,
+    /// Keep each input intact as an attachment of a cover page, in a PDF
+    /// Portfolio. Options that change pages don't apply.
+    Portfolio,
+}
+
+/// Imposition layout placing several pages on each output sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Pages in a grid, filled left to right and top to bottom.
+    Grid {
+        /// Number of pages across each sheet.
+        columns: u32,
+        /// Number of pages down each sheet.
+        rows: u32,
+    },
+    /// Two pages per sheet side in saddle-stitch order, so that the
+    /// printed sheets can be folded and stapled into a booklet.
+    Booklet,
+}
+
+impl Layout {
+    /// Largest number of columns or rows of a grid.
+    pub const MAX_GRID_SIZE: u32 = 32;
+
+    /// Number of pages placed on each sheet.
+    pub fn pages_per_sheet(&self) -> u32 {
+        match self {
+            Self::Grid { columns, rows } => columns.saturating_mul(*rows),
+            Self::Booklet => 2,
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = crate::PdfCatError;
+    /// Parse a layout from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "2up", "4up", "6up", "8up", "9up", "16up", a grid such as
+    ///   "3x2" (columns x rows), or "booklet"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't describe a valid layout.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let s = s.trim().to_lowercase();
+        let invalid = || PdfCatError::InvalidConfig {
+            message: format!(
+                "Invalid layout: {s}. Must be one of: 2up, 4up, 6up, 8up, 9up, 16up, booklet, or a grid like 3x2"
+            ),
+        };
+
+        let (columns, rows) = match s.trim_end_matches("up").trim_end_matches('-') {
+            "booklet" => return Ok(Self::Booklet),
+            "2" => (2, 1),
+            "4" => (2, 2),
+            "6" => (3, 2),
+            "8" => (4, 2),
+            "9" => (3, 3),
+            "16" => (4, 4),
+            grid => {
+                let (columns, rows) = grid.split_once('x').ok_or_else(invalid)?;
+                (
+                    columns.trim().parse().map_err(|_| invalid())?,
+                    rows.trim().parse().map_err(|_| invalid())?,
+                )
+            }
+        };
+
+        if columns == 0 || rows == 0 {
+            return Err(invalid());
+        }
+        if columns > Self::MAX_GRID_SIZE || rows > Self::MAX_GRID_SIZE {
+            return Err(PdfCatError::invalid_config(format!(
+                "Invalid layout: {s}. Grids can have at most {max} columns and {max} rows",
+                max = Self::MAX_GRID_SIZE
+            )));
+        }
+
+        Ok(Self::Grid { columns, rows })
+    }
+}
+
+/// Target paper size for normalizing pages, in whole points.
+///
+/// Sizes are given in portrait orientation; each page is laid out on the
+/// portrait or landscape form of the size, matching its own orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSize {
+    /// Width in points.
+    pub width: u32,
+    /// Height in points.
+    pub height: u32,
+}
+
+impl PageSize {
+    /// ISO A3 (297 x 420 mm).
+    pub const A3: Self = Self::new(842, 1191);
+    /// ISO A4 (210 x 297 mm).
+    pub const A4: Self = Self::new(595, 842);
+    /// ISO A5 (148 x 210 mm).
+    pub const A5: Self = Self::new(420, 595);
+    /// US Letter (8.5 x 11 in).
+    pub const LETTER: Self = Self::new(612, 792);
+    /// US Legal (8.5 x 14 in).
+    pub const LEGAL: Self = Self::new(612, 1008);
+    /// US Tabloid (11 x 17 in).
+    pub const TABLOID: Self = Self::new(792, 1224);
+
+    /// Create a page size, swapping the sides if needed so that it is in
+    /// portrait orientation.
+    pub const fn new(width: u32, height: u32) -> Self {
+        if width > height {
+            Self {
+                width: height,
+                height: width,
+            }
+        } else {
+            Self { width, height }
+        }
+    }
+
+    /// Width and height in the orientation of a `width` x `height` page.
+    pub fn oriented(&self, width: f32, height: f32) -> (f32, f32) {
+        if width > height {
+            (self.height as f32, self.width as f32)
+        } else {
+            (self.width as f32, self.height as f32)
+        }
+    }
+}
+
+impl FromStr for PageSize {
+    type Err = crate::PdfCatError;
+    /// Parse a page size from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - A named size ("a3", "a4", "a5", "letter", "legal",
+    ///   "tabloid") or explicit dimensions such as "612x792" (points),
+    ///   "210x297mm" or "8.5x11in"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't describe a valid size.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let s = s.trim().to_lowercase();
+        let invalid = || PdfCatError::InvalidConfig {
+            message: format!(
+                "Invalid page size: {s}. Must be one of: a3, a4, a5, letter, legal, tabloid, or dimensions like 612x792, 210x297mm or 8.5x11in"
+            ),
+        };
+
+        match s.as_str() {
+            "a3" => return Ok(Self::A3),
+            "a4" => return Ok(Self::A4),
+            "a5" => return Ok(Self::A5),
+            "letter" => return Ok(Self::LETTER),
+            "legal" => return Ok(Self::LEGAL),
+            "tabloid" => return Ok(Self::TABLOID),
+            _ => {}
+        }
+
+        let (dimensions, points_per_unit) = split_unit(&s);
+
+        let (width, height) = dimensions.split_once('x').ok_or_else(invalid)?;
+        let to_points = |value: &str| -> crate::Result<u32> {
+            let value: f64 = value.trim().parse().map_err(|_| invalid())?;
+            let points = (value * points_per_unit).round();
+            if !(1.0..=14400.0).contains(&points) {
+                return Err(invalid());
+            }
+            Ok(points as u32)
+        };
+
+        Ok(Self::new(to_points(width)?, to_points(height)?))
+    }
+}
+
+/// Split a length unit suffix ("pt", "mm" or "in") off a string.
+///
+/// Returns the rest of the string and the number of points per unit.
+/// Strings without a suffix are in points.
+fn split_unit(s: &str) -> (&str, f64) {
+    if let Some(rest) = s.strip_suffix("mm") {
+        (rest, 72.0 / 25.4)
+    } else if let Some(rest) = s.strip_suffix("in") {
+        (rest, 72.0)
+    } else {
+        (s.strip_suffix("pt").unwrap_or(s), 1.0)
+    }
+}
+
+/// A page boundary box that can be edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoxKind {
+    /// `CropBox`: the region viewers display and printers print.
+    Crop,
+    /// `TrimBox`: the intended size of the finished page.
+    Trim,
+    /// `BleedBox`: the region to clip to in production.
+    Bleed,
+}
+
+impl BoxKind {
+    /// Name of the box in the page dictionary.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Crop => "CropBox",
+            Self::Trim => "TrimBox",
+            Self::Bleed => "BleedBox",
+        }
+    }
+}
+
+/// How a page box is changed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BoxChange {
+    /// Move the sides of the box inwards by `[top, right, bottom, left]`
+    /// points, as the page is displayed. Negative margins grow the box.
+    Margins([f32; 4]),
+    /// Set the box to `[x0, y0, x1, y1]` in the page's user space.
+    Rect([f32; 4]),
+    /// Shrink the box to the bounds of the page's visible content.
+    Auto,
+}
+
+/// An edit of one page box on some or all pages of the output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxEdit {
+    /// Box to edit.
+    pub kind: BoxKind,
+    /// Change to make.
+    pub change: BoxChange,
+    /// Output pages to edit (None = all pages).
+    pub pages: Option<PageRange>,
+}
+
+impl BoxEdit {
+    /// Parse a box edit from its specification.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Box to edit
+    /// * `spec` - One of:
+    ///   - margins: "10" (all sides), "10,20" (top and bottom, left and
+    ///     right) or "10,20,10,20" (top, right, bottom, left)
+    ///   - a rectangle: "rect:x0,y0,x1,y1"
+    ///   - "auto" to shrink to the visible content
+    ///
+    ///   Lengths are in points, or in millimeters or inches with an "mm"
+    ///   or "in" suffix; lengths without a suffix take the unit of the
+    ///   last one. A page range may follow an "@", as in "5mm@1-4".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the specification or page range is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::{BoxChange, BoxEdit, BoxKind};
+    ///
+    /// let edit = BoxEdit::parse(BoxKind::Trim, "1in,0.5in@2-end").unwrap();
+    /// assert_eq!(edit.change, BoxChange::Margins([72.0, 36.0, 72.0, 36.0]));
+    /// assert!(edit.pages.is_some());
+    /// ```
+    pub fn parse(kind: BoxKind, spec: &str) -> Result<Self> {
+        let invalid = || {
+            PdfCatError::invalid_config(format!(
+                "Invalid {} specification: {spec}. Expected margins like 10 or 5mm,10mm, a rectangle like rect:0,0,612,792, or auto",
+                kind.key()
+            ))
+        };
+
+        let (change, pages) = match spec.split_once('@') {
+            Some((change, pages)) => (change, Some(PageRange::parse(pages)?)),
+            None => (spec, None),
+        };
+        let change = change.trim().to_lowercase();
+
+        // Values without a unit take the unit of the last value, so that
+        // "5,10mm" and "5mm,10mm" mean the same.
+        let parse_lengths = |s: &str| -> Result<Vec<f32>> {
+            let values: Vec<&str> = s.split(',').map(str::trim).collect();
+            let default_unit = split_unit(values.last().copied().unwrap_or_default()).1;
+            values
+                .into_iter()
+                .map(|value| {
+                    let (number, points_per_unit) = match split_unit(value) {
+                        (number, _) if number.len() == value.len() => (number, default_unit),
+                        split => split,
+                    };
+                    let number: f64 = number.trim().parse().map_err(|_| invalid())?;
+                    if !number.is_finite() {
+                        return Err(invalid());
+                    }
+                    Ok((number * points_per_unit) as f32)
+                })
+                .collect()
+        };
+
+        let change = if change == "auto" {
+            BoxChange::Auto
+        } else if let Some(rect) = change.strip_prefix("rect:") {
+            match parse_lengths(rect)?[..] {
+                [x0, y0, x1, y1] if x0 != x1 && y0 != y1 => {
+                    BoxChange::Rect([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+                }
+                _ => return Err(invalid()),
+            }
+        } else {
+            match parse_lengths(&change)?[..] {
+                [all] => BoxChange::Margins([all; 4]),
+                [vertical, horizontal] => {
+                    BoxChange::Margins([vertical, horizontal, vertical, horizontal])
+                }
+                [top, right, bottom, left] => BoxChange::Margins([top, right, bottom, left]),
+                _ => return Err(invalid()),
+            }
+        };
+
+        Ok(Self {
+            kind,
+            change,
+            pages,
+        })
+    }
+}
+
+/// How pages are brought to the target page size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageFit {
+    /// Scale each page up or down to fill the target size (default).
+    #[default]
+    Fit,
+    /// Keep each page at its size and center it on the target size.
+    /// Pages larger than the target are scaled down.
+    Pad,
+}
+
+impl FromStr for PageFit {
+    type Err = crate::PdfCatError;
+    /// Parse page fit from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - String representation: "fit" or "pad"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid fit.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fit" => Ok(Self::Fit),
+            "pad" => Ok(Self::Pad),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!("Invalid page fit: {s}. Must be one of: fit, pad"),
+            }),
+        }
+    }
+}
+
+/// Where a stamp is drawn on a page, as the page is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StampPosition {
+    /// Top left corner.
+    TopLeft,
+    /// Centered at the top.
+    TopCenter,
+    /// Top right corner.
+    TopRight,
+    /// Bottom left corner.
+    BottomLeft,
+    /// Centered at the bottom.
+    BottomCenter,
+    /// Bottom right corner.
+    BottomRight,
+}
+
+impl FromStr for StampPosition {
+    type Err = crate::PdfCatError;
+    /// Parse a stamp position from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "top-left", "top-center", "top-right", "bottom-left",
+    ///   "bottom-center" or "bottom-right"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid position.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "top-left" => Ok(Self::TopLeft),
+            "top-center" => Ok(Self::TopCenter),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-center" => Ok(Self::BottomCenter),
+            "bottom-right" => Ok(Self::BottomRight),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!(
+                    "Invalid stamp position: {s}. Must be one of: top-left, top-center, top-right, bottom-left, bottom-center, bottom-right"
+                ),
+            }),
+        }
+    }
+}
+
+/// Text drawn on each page of the output, such as a page number, header
+/// or footer.
+///
+/// The text may contain placeholders:
+/// - `{n}`: The page number in the output
+/// - `{total}`: The number of pages in the output
+/// - `{file}`: The file name of the input the page came from
+/// - `{date}`: Today's date as YYYY-MM-DD
+/// - `{bates}`: The page's Bates number, if [`Config::bates`] is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stamp {
+    /// Text template.
+    pub text: String,
+    /// Where the text is drawn.
+    pub position: StampPosition,
+    /// Font size in points.
+    pub font_size: f32,
+    /// Output pages to stamp (None = all pages).
+    pub pages: Option<PageRange>,
+}
+
+impl Stamp {
+    /// Default font size in points.
+    pub const DEFAULT_FONT_SIZE: f32 = 10.0;
+
+    /// Create a stamp drawn on every page at the default font size.
+    pub fn new(text: impl Into<String>, position: StampPosition) -> Self {
+        Self {
+            text: text.into(),
+            position,
+            font_size: Self::DEFAULT_FONT_SIZE,
+            pages: None,
+        }
+    }
+
+    /// Parse a stamp from a "POSITION=TEXT" specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid or the text is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::{Stamp, StampPosition};
+    ///
+    /// let stamp = Stamp::parse("bottom-right=Page {n} of {total}").unwrap();
+    /// assert_eq!(stamp.position, StampPosition::BottomRight);
+    /// assert_eq!(stamp.text, "Page {n} of {total}");
+    /// ```
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (position, text) = spec.split_once('=').ok_or_else(|| {
+            PdfCatError::invalid_config(format!(
+                "Invalid stamp: {spec}. Expected POSITION=TEXT, e.g. bottom-center=Page {{n}}"
+            ))
+        })?;
+
+        if text.trim().is_empty() {
+            return Err(PdfCatError::invalid_config(format!(
+                "Invalid stamp: {spec}. The text cannot be empty"
+            )));
+        }
+
+        Ok(Self::new(text, position.parse()?))
+    }
+}
+
+/// Bates numbering: a prefix, a zero-padded counter and a suffix stamped
+/// on every page of the output, as used to identify pages in legal
+/// productions.
+///
+/// The number of a page can also be used in [`Stamp`] text through the
+/// `{bates}` placeholder. When no stamp uses it, the number is drawn on
+/// its own at [`Bates::position`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bates {
+    /// Text before the counter.
+    pub prefix: String,
+    /// Counter value of the first page.
+    pub start: u64,
+    /// Minimum number of digits; the counter is padded with zeros.
+    pub digits: usize,
+    /// Text after the counter.
+    pub suffix: String,
+    /// Where the number is drawn.
+    pub position: StampPosition,
+    /// Font size in points.
+    pub font_size: f32,
+}
+
+impl Bates {
+    /// Default number of digits.
+    pub const DEFAULT_DIGITS: usize = 6;
+
+    /// Create Bates numbering starting at 1 with six digits, drawn in the
+    /// bottom right corner.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            start: 1,
+            digits: Self::DEFAULT_DIGITS,
+            suffix: String::new(),
+            position: StampPosition::BottomRight,
+            font_size: Stamp::DEFAULT_FONT_SIZE,
+        }
+    }
+
+    /// The Bates number of a page, counting pages of the output from 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::Bates;
+    ///
+    /// let mut bates = Bates::new("ACME");
+    /// bates.start = 120;
+    /// assert_eq!(bates.number(0), "ACME000120");
+    /// assert_eq!(bates.number(5), "ACME000125");
+    /// ```
+    pub fn number(&self, index: usize) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix,
+            self.start + index as u64,
+            self.suffix,
+            width = self.digits
+        )
+    }
+}
+
+/// What a watermark draws.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatermarkContent {
+    /// A line of text, such as "CONFIDENTIAL".
+    Text(String),
+    /// A page of another PDF, such as a letterhead.
+    Page {
+        /// Path of the PDF.
+        path: PathBuf,
+        /// 1-indexed page number in the PDF.
+        page: u32,
+    },
+}
+
+/// Whether a watermark is drawn over or under the page content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatermarkLayer {
+    /// On top of the page content (default).
+    #[default]
+    Over,
+    /// Behind the page content. Hidden by pages with an opaque background.
+    Under,
+}
+
+/// Text or a page of another PDF drawn on each page of the output.
+///
+/// A watermark is centered on each page as it is displayed. Text is set
+/// in gray Helvetica; a PDF page is scaled to fit, keeping its aspect
+/// ratio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watermark {
+    /// What is drawn.
+    pub content: WatermarkContent,
+    /// Over or under the page content.
+    pub layer: WatermarkLayer,
+    /// Opacity from 0 (invisible) to 1 (opaque).
+    pub opacity: f32,
+    /// Counterclockwise rotation in degrees (None = along the page's
+    /// diagonal for text, upright for PDF pages).
+    pub angle: Option<f32>,
+    /// Font size of text in points (None = as large as fits the page).
+    pub font_size: Option<f32>,
+    /// Output pages to watermark (None = all pages).
+    pub pages: Option<PageRange>,
+}
+
+impl Watermark {
+    /// Default opacity.
+    pub const DEFAULT_OPACITY: f32 = 0.3;
+
+    /// Create a diagonal text watermark over every page.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(WatermarkContent::Text(text.into()))
+    }
+
+    /// Create a watermark over every page from a page of another PDF.
+    pub fn page(path: impl Into<PathBuf>, page: u32) -> Self {
+        Self::new(WatermarkContent::Page {
+            path: path.into(),
+            page,
+        })
+    }
+
+    fn new(content: WatermarkContent) -> Self {
+        Self {
+            content,
+            layer: WatermarkLayer::Over,
+            opacity: Self::DEFAULT_OPACITY,
+            angle: None,
+            font_size: None,
+            pages: None,
+        }
+    }
+}
+
+/// How the output's page labels are made.
+///
+/// Page labels are the page numbers a viewer shows, such as "iv" or
+/// "A-1", in place of a page's position in the document.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PageLabels {
+    /// Keep each input's page labels (default). Pages of inputs without
+    /// labels are labeled with their position in the output.
+    #[default]
+    Keep,
+    /// Label each input's pages from a template ending in `{n}`, such as
+    /// "{stem}-{n}". `{stem}` is the input's file name without its
+    /// extension and `{n}` counts the input's pages from 1.
+    Generate(String),
+    /// Write no page labels.
+    Remove,
+}
+
+impl FromStr for PageLabels {
+    type Err = crate::PdfCatError;
+    /// Parse page labels from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "keep", "none", or a template ending in "{n}"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a template doesn't end in "{n}" or uses it more
+    /// than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::PageLabels;
+    ///
+    /// assert_eq!("keep".parse::<PageLabels>().unwrap(), PageLabels::Keep);
+    /// assert_eq!(
+    ///     "{stem}-{n}".parse::<PageLabels>().unwrap(),
+    ///     PageLabels::Generate("{stem}-{n}".to_string())
+    /// );
+    /// assert!("page {n} of {stem}".parse::<PageLabels>().is_err());
+    /// ```
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "none" => Ok(Self::Remove),
+            _ if s.ends_with("{n}") && s.matches("{n}").count() == 1 => {
+                Ok(Self::Generate(s.to_string()))
+            }
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!(
+                    "Invalid page labels: {s}. Must be keep, none, or a template ending in {{n}}, e.g. {{stem}}-{{n}}"
+                ),
+            }),
+        }
+    }
+}
+
+/// Annotation subtypes known to the PDF specification.
+const ANNOTATION_SUBTYPES: &[&str] = &[
+    "Text",
+    "Link",
+    "FreeText",
+    "Line",
+    "Square",
+    "Circle",
+    "Polygon",
+    "PolyLine",
+    "Highlight",
+    "Underline",
+    "Squiggly",
+    "StrikeOut",
+    "Caret",
+    "Stamp",
+    "Ink",
+    "Popup",
+    "FileAttachment",
+    "Sound",
+    "Movie",
+    "Screen",
+    "Widget",
+    "PrinterMark",
+    "TrapNet",
+    "Watermark",
+    "3D",
+    "Redact",
+    "Projection",
+    "RichMedia",
+];
+
+/// Annotations to remove from the output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnotationFilter {
+    /// Remove every annotation, links and form fields included.
+    All,
+    /// Remove annotations of these subtypes, such as "Highlight".
+    Subtypes(Vec<String>),
+}
+
+impl AnnotationFilter {
+    /// Subtypes of markup annotations: the comments, highlights and
+    /// drawings reviewers add, with their pop-up notes.
+    pub const COMMENTS: &[&str] = &[
+        "Text",
+        "FreeText",
+        "Line",
+        "Square",
+        "Circle",
+        "Polygon",
+        "PolyLine",
+        "Highlight",
+        "Underline",
+        "Squiggly",
+        "StrikeOut",
+        "Caret",
+        "Stamp",
+        "Ink",
+        "Popup",
+        "FileAttachment",
+        "Sound",
+        "Redact",
+    ];
+
+    /// Check whether annotations of a subtype are removed.
+    pub fn matches(&self, subtype: &[u8]) -> bool {
+        match self {
+            Self::All => true,
+            Self::Subtypes(subtypes) => subtypes.iter().any(|s| s.as_bytes() == subtype),
+        }
+    }
+}
+
+impl FromStr for AnnotationFilter {
+    type Err = crate::PdfCatError;
+    /// Parse annotations to remove from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "all", or a comma-separated list of subtypes, such as
+    ///   "Highlight,Ink", where "comments" stands for all markup subtypes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a subtype is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::AnnotationFilter;
+    ///
+    /// let filter: AnnotationFilter = "comments".parse().unwrap();
+    /// assert!(filter.matches(b"Highlight"));
+    /// assert!(!filter.matches(b"Link"));
+    ///
+    /// let filter: AnnotationFilter = "ink,popup".parse().unwrap();
+    /// assert!(filter.matches(b"Ink"));
+    /// assert!("Scribble".parse::<AnnotationFilter>().is_err());
+    /// ```
+    fn from_str(s: &str) -> crate::Result<Self> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+
+        let mut subtypes: Vec<String> = Vec::new();
+        for name in s.split(',').map(str::trim) {
+            let added: Vec<&str> = if name.eq_ignore_ascii_case("comments") {
+                Self::COMMENTS.to_vec()
+            } else {
+                let subtype = ANNOTATION_SUBTYPES
+                    .iter()
+                    .find(|subtype| subtype.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| PdfCatError::InvalidConfig {
+                        message: format!(
+                            "Invalid annotation type: {name}. Must be all, comments, or a subtype such as Highlight, Ink or Link"
+                        ),
+                    })?;
+                vec![subtype]
+            };
+
+            for subtype in added {
+                if !subtypes.iter().any(|s| s == subtype) {
+                    subtypes.push(subtype.to_string());
+                }
+            }
+        }
+
+        Ok(Self::Subtypes(subtypes))
+    }
+}
+
+/// Panel a viewer shows next to the pages when the document opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageMode {
+    /// No panel.
+    None,
+    /// The outline (bookmarks).
+    Outline,
+    /// Page thumbnails.
+    Thumbs,
+    /// Full-screen mode, with no menus or panels.
+    FullScreen,
+    /// The layers (optional content groups).
+    Layers,
+    /// The attachments.
+    Attachments,
+}
+
+impl PageMode {
+    /// Name of the page mode in the PDF catalog.
+    pub fn as_name(&self) -> &'static str {
+        match self {
+            Self::None => "UseNone",
+            Self::Outline => "UseOutlines",
+            Self::Thumbs => "UseThumbs",
+            Self::FullScreen => "FullScreen",
+            Self::Layers => "UseOC",
+            Self::Attachments => "UseAttachments",
+        }
+    }
+}
+
+impl FromStr for PageMode {
+    type Err = crate::PdfCatError;
+    /// Parse a page mode from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "none", "outline", "thumbs", "fullscreen", "layers" or
+    ///   "attachments"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid page mode.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "outline" | "bookmarks" => Ok(Self::Outline),
+            "thumbs" | "thumbnails" => Ok(Self::Thumbs),
+            "fullscreen" | "full-screen" => Ok(Self::FullScreen),
+            "layers" => Ok(Self::Layers),
+            "attachments" => Ok(Self::Attachments),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!(
+                    "Invalid page mode: {s}. Must be one of: none, outline, thumbs, fullscreen, layers, attachments"
+                ),
+            }),
+        }
+    }
+}
+
+/// How a viewer arranges pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PageLayout {
+    /// One page at a time.
+    Single,
+    /// Pages in one continuous column.
+    Continuous,
+    /// Pages in two continuous columns, odd pages on the left.
+    TwoColumnLeft,
+    /// Pages in two continuous columns, odd pages on the right.
+    TwoColumnRight,
+    /// Two pages at a time, odd pages on the left.
+    TwoPageLeft,
+    /// Two pages at a time, odd pages on the right, as in a book.
+    TwoPageRight,
+}
+
+impl PageLayout {
+    /// Name of the page layout in the PDF catalog.
+    pub fn as_name(&self) -> &'static str {
+        match self {
+            Self::Single => "SinglePage",
+            Self::Continuous => "OneColumn",
+            Self::TwoColumnLeft => "TwoColumnLeft",
+            Self::TwoColumnRight => "TwoColumnRight",
+            Self::TwoPageLeft => "TwoPageLeft",
+            Self::TwoPageRight => "TwoPageRight",
+        }
+    }
+}
+
+impl FromStr for PageLayout {
+    type Err = crate::PdfCatError;
+    /// Parse a page layout from string.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - "single", "continuous", "two-column-left",
+    ///   "two-column-right", "two-page-left" or "two-page-right"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string doesn't match a valid page layout.
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "single" => Ok(Self::Single),
+            "continuous" => Ok(Self::Continuous),
+            "two-column-left" => Ok(Self::TwoColumnLeft),
+            "two-column-right" => Ok(Self::TwoColumnRight),
+            "two-page-left" => Ok(Self::TwoPageLeft),
+            "two-page-right" => Ok(Self::TwoPageRight),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!(
+                    "Invalid page layout: {s}. Must be one of: single, continuous, two-column-left, two-column-right, two-page-left, two-page-right"
+                ),
+            }),
+        }
+    }
+}
+
+/// Zoom a viewer opens a page at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Zoom {
+    /// The whole page in the window.
+    Fit,
+    /// The page's width in the window.
+    FitWidth,
+    /// A magnification in percent, such as 150.
+    Percent(f32),
+}
+
+/// Page and zoom a viewer opens the document at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OpenAt {
+    /// 1-indexed page of the output.
+    pub page: u32,
+
+    /// Zoom to open the page at (None = the viewer's choice).
+    pub zoom: Option<Zoom>,
 }
 
-impl PageRange {
-    /// Parse a page range string.
+impl FromStr for OpenAt {
+    type Err = crate::PdfCatError;
+    /// Parse a page and zoom from string.
     ///
     /// # Arguments
     ///
-    /// * `s` - Page range string (e.g., "1-5,10,15-20")
+    /// * `s` - A page number, optionally followed by a colon and "fit",
+    ///   "fit-width" or a percentage
     ///
     /// # Errors
     ///
-    /// Returns an error if the string format is invalid or contains invalid page numbers.
+    /// Returns an error if the page is not a positive number or the zoom
+    /// is not valid.
     ///
     /// # Examples
     ///
     /// ```
-    /// use pdfcat::config::PageRange;
+    /// use pdfcat::config::{OpenAt, Zoom};
     ///
-    /// let range = PageRange::parse("1-5,10").unwrap();
-    /// assert!(range.contains(3));
-    /// assert!(range.contains(10));
-    /// assert!(!range.contains(7));
+    /// let open_at: OpenAt = "3:150%".parse().unwrap();
+    /// assert_eq!(open_at, OpenAt { page: 3, zoom: Some(Zoom::Percent(150.0)) });
+    /// assert_eq!("1".parse::<OpenAt>().unwrap().zoom, None);
+    /// assert!("0:fit".parse::<OpenAt>().is_err());
     /// ```
-    pub fn parse(s: &str) -> Result<Self> {
-        let mut ranges = Vec::new();
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = || PdfCatError::InvalidConfig {
+            message: format!(
+                "Invalid page to open at: {s}. Must be a page number, optionally followed by :fit, :fit-width or a zoom such as :150%"
+            ),
+        };
 
-        for part in s.split(',') {
-            let part = part.trim();
+        let (page, zoom) = match s.trim().split_once(':') {
+            Some((page, zoom)) => (page, Some(zoom.trim().to_lowercase())),
+            None => (s.trim(), None),
+        };
+        let page: u32 = page.trim().parse().map_err(|_| invalid())?;
+        if page == 0 {
+            return Err(invalid());
+        }
 
-            if part.contains('-') {
-                let parts: Vec<&str> = part.split('-').collect();
-                if parts.len() != 2 {
-                    return Err(PdfCatError::invalid_config(
-                        "Invalid page range format: {part}. Expected format like '1-5'",
-                    ));
+        let zoom = match zoom.as_deref() {
+            None => None,
+            Some("fit") => Some(Zoom::Fit),
+            Some("fit-width") => Some(Zoom::FitWidth),
+            Some(percent) => {
+                let percent: f32 = percent
+                    .trim_end_matches('%')
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid())?;
+                if !(percent.is_finite() && percent > 0.0) {
+                    return Err(invalid());
                 }
+                Some(Zoom::Percent(percent))
+            }
+        };
 
-                let start: u32 = parts[0].trim().parse().map_err(|_| {
-                    crate::PdfCatError::invalid_config(format!("Invalid page number: {}", parts[0]))
-                })?;
+        Ok(Self { page, zoom })
+    }
+}
 
-                let end: u32 = parts[1].trim().parse().map_err(|_| {
-                    crate::PdfCatError::invalid_config(format!("Invalid page number: {}", parts[1]))
-                })?;
+/// Settings of how viewers show the output, written to its catalog.
+///
+/// Settings left unset keep the first input's, except that the outline is
+/// shown when bookmarks are added.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerSettings {
+    /// Panel shown when the document opens.
+    pub page_mode: Option<PageMode>,
 
-                if start == 0 || end == 0 {
-                    return Err(PdfCatError::invalid_config(
-                        "Page numbers must be positive (1-indexed)",
-                    ));
-                }
+    /// How pages are arranged.
+    pub page_layout: Option<PageLayout>,
 
-                if start > end {
-                    return Err(PdfCatError::invalid_config(format!(
-                        "Invalid range {start}-{end}: start page must be less than or equal to end page"
-                    )));
-                }
+    /// Whether the window title shows the document's title instead of its
+    /// file name.
+    pub display_doc_title: bool,
 
-                ranges.push(PageRangeItem::Range(start, end));
-            } else {
-                let page: u32 = part.parse().map_err(|_| {
-                    crate::PdfCatError::invalid_config(format!("Invalid page number: {part}"))
-                })?;
-
-                if page == 0 {
-                    return Err(PdfCatError::invalid_config(
-                        "Page numbers must be positive (1-indexed)",
-                    ));
-                }
+    /// Whether the window is resized to fit the first page shown.
+    pub fit_window: bool,
 
-                ranges.push(PageRangeItem::Single(page));
-            }
-        }
+    /// Page and zoom the document opens at.
+    pub open_at: Option<OpenAt>,
+}
 
-        if ranges.is_empty() {
-            return Err(PdfCatError::invalid_config("Page range cannot be empty"));
-        }
+impl ViewerSettings {
+    /// Check if no setting is given.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
 
-        Ok(Self { ranges })
+/// A PDF version, such as 1.7.
+///
+/// Versions are ordered, so the newest of several can be found with `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PdfVersion {
+    /// Major version.
+    pub major: u8,
+
+    /// Minor version.
+    pub minor: u8,
+}
+
+impl PdfVersion {
+    /// Create a PDF version.
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
     }
+}
 
-    /// Check if a page number is included in this range.
-    ///
-    /// # Arguments
-    ///
-    /// * `page` - 1-indexed page number
-    pub fn contains(&self, page: u32) -> bool {
-        self.ranges.iter().any(|item| match item {
-            PageRangeItem::Single(p) => *p == page,
-            PageRangeItem::Range(start, end) => page >= *start && page <= *end,
-        })
+impl Default for PdfVersion {
+    fn default() -> Self {
+        Self::new(1, 4)
     }
+}
+
+impl fmt::Display for PdfVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
 
-    /// Get all page numbers included in this range up to a maximum.
+impl FromStr for PdfVersion {
+    type Err = crate::PdfCatError;
+    /// Parse a PDF version from string.
     ///
     /// # Arguments
     ///
-    /// * `max_pages` - Maximum page number to consider
+    /// * `s` - "1.0" to "1.7", or "2.0"
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A sorted vector of 1-indexed page numbers.
-    pub fn to_pages(&self, max_pages: u32) -> Vec<u32> {
-        let mut pages: Vec<u32> = (1..=max_pages).filter(|p| self.contains(*p)).collect();
-        pages.sort_unstable();
-        pages.dedup();
-        pages
+    /// Returns an error if the string is not a known PDF version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::config::PdfVersion;
+    ///
+    /// assert_eq!("1.7".parse::<PdfVersion>().unwrap(), PdfVersion::new(1, 7));
+    /// assert!("1.8".parse::<PdfVersion>().is_err());
+    /// ```
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let version = s
+            .trim()
+            .split_once('.')
+            .and_then(|(major, minor)| Some(Self::new(major.parse().ok()?, minor.parse().ok()?)));
+        match version {
+            Some(
+                version @ Self {
+                    major: 1,
+                    minor: 0..=7,
+                }
+                | version @ Self { major: 2, minor: 0 },
+            ) => Ok(version),
+            _ => Err(PdfCatError::InvalidConfig {
+                message: format!("Invalid PDF version: {s}. Must be 1.0 to 1.7, or 2.0"),
+            }),
+        }
     }
 }
 
-/// PDF metadata to set on the output document.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Metadata {
-    /// Document title.
-    pub title: Option<String>,
-    /// Document author.
-    pub author: Option<String>,
-    /// Document subject.
-    pub subject: Option<String>,
-    /// Document keywords (comma-separated).
-    pub keywords: Option<String>,
-}
+impl TryFrom<String> for PdfVersion {
+    type Error = crate::PdfCatError;
 
-impl Metadata {
-    /// Check if any metadata fields are set.
-    pub fn is_empty(&self) -> bool {
-        self.title.is_none()
-            && self.author.is_none()
-            && self.subject.is_none()
-            && self.keywords.is_none()
+    fn try_from(s: String) -> crate::Result<Self> {
+        s.parse()
     }
+}
 
-    /// Create metadata from optional strings, trimming whitespace.
-    pub fn new(
-        title: Option<String>,
-        author: Option<String>,
-        subject: Option<String>,
-        keywords: Option<String>,
-    ) -> Self {
-        let to_string_opt = |opt: Option<String>| {
-            opt.filter(|s| !s.trim().is_empty())
-                .map(|s| s.trim().to_string())
-        };
-
-        Self {
-            title: to_string_opt(title),
-            author: to_string_opt(author),
-            subject: to_string_opt(subject),
-            keywords: to_string_opt(keywords),
-        }
+impl From<PdfVersion> for String {
+    fn from(version: PdfVersion) -> Self {
+        version.to_string()
     }
 }
 
@@ -280,7 +1737,7 @@ pub enum OverwriteMode {
 ///
 /// This structure contains all settings needed to perform a merge,
 /// derived and validated from CLI arguments.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     /// Input PDF file paths (in merge order).
@@ -313,14 +1770,92 @@ pub struct Config {
     /// Continue on errors instead of stopping.
     pub continue_on_error: bool,
 
+    /// Attempt to repair damaged inputs instead of rejecting them.
+    pub repair: bool,
+
     /// Number of parallel jobs (None = auto-detect).
     pub jobs: Option<usize>,
 
     /// Page range to extract from each input.
     pub page_range: Option<PageRange>,
 
+    /// How to treat page range entries beyond an input's last page.
+    pub page_range_policy: PageRangePolicy,
+
     /// Rotation to apply to all pages.
     pub rotation: Option<Rotation>,
+
+    /// How pages from the inputs are ordered.
+    pub merge_mode: MergeMode,
+
+    /// 1-indexed positions of inputs whose pages are taken in reverse order.
+    pub reverse_inputs: Vec<usize>,
+
+    /// Start every input on an odd page by adding a blank page after
+    /// inputs that end on an odd page.
+    pub duplex: bool,
+
+    /// Add a blank page after each of these page numbers of the merged
+    /// pages (0 = before the first page).
+    pub blank_pages: Vec<usize>,
+
+    /// Add a separator page before each input, showing its file name,
+    /// title and page count.
+    pub separators: bool,
+
+    /// Imposition layout applied to the merged pages.
+    pub layout: Option<Layout>,
+
+    /// Page box edits, applied in order to the merged pages.
+    pub box_edits: Vec<BoxEdit>,
+
+    /// Paper size every page is normalized to.
+    pub page_size: Option<PageSize>,
+
+    /// How pages are brought to `page_size`.
+    pub page_fit: PageFit,
+
+    /// Text stamped on the output pages.
+    pub stamps: Vec<Stamp>,
+
+    /// Bates numbering for every output page (None = no Bates numbers).
+    pub bates: Option<Bates>,
+
+    /// File to write the Bates log to, as CSV or, for a `.json` file, JSON.
+    pub bates_log: Option<PathBuf>,
+
+    /// Watermarks drawn on output pages, in order.
+    pub watermarks: Vec<Watermark>,
+
+    /// How the output's page labels are made.
+    pub page_labels: PageLabels,
+
+    /// Files to embed in the output as attachments.
+    pub attachments: Vec<PathBuf>,
+
+    /// Whether to list each input's layers under its file name.
+    pub group_layers: bool,
+
+    /// Annotations to remove from the output (None = keep them all).
+    pub remove_annotations: Option<AnnotationFilter>,
+
+    /// Whether to draw form fields and stamp annotations into the page
+    /// content, in place of the annotations.
+    pub flatten: bool,
+
+    /// Whether to remove active content (JavaScript, launch actions) and
+    /// hidden data (attachments, metadata, private application data) from
+    /// the inputs.
+    pub sanitize: bool,
+
+    /// How viewers show the output.
+    pub viewer: ViewerSettings,
+
+    /// PDF version of the output (None = the newest version of the inputs).
+    pub pdf_version: Option<PdfVersion>,
+
+    /// Format of the output printed to stdout.
+    pub output_format: OutputFormat,
 }
 
 impl Config {
@@ -361,6 +1896,66 @@ impl Config {
             ));
         }
 
+        if self.duplex && self.merge_mode == MergeMode::Collate {
+            return Err(PdfCatError::invalid_config(
+                "Cannot use --duplex with --collate".to_string(),
+            ));
+        }
+
+        if self.separators && self.merge_mode == MergeMode::Collate {
+            return Err(PdfCatError::invalid_config(
+                "Cannot use --separators with --collate".to_string(),
+            ));
+        }
+
+        for watermark in &self.watermarks {
+            if !(0.0..=1.0).contains(&watermark.opacity) {
+                return Err(PdfCatError::invalid_config(format!(
+                    "Invalid watermark opacity: {}. Must be between 0 and 1",
+                    watermark.opacity
+                )));
+            }
+            if let WatermarkContent::Page { page: 0, .. } = watermark.content {
+                return Err(PdfCatError::invalid_config(
+                    "Watermark page numbers start at 1".to_string(),
+                ));
+            }
+        }
+
+        if self.merge_mode == MergeMode::Portfolio {
+            let page_options = [
+                (self.bookmarks, "--bookmarks"),
+                (self.page_range.is_some(), "--pages"),
+                (self.rotation.is_some(), "--rotate"),
+                (!self.reverse_inputs.is_empty(), "--reverse"),
+                (self.duplex, "--duplex"),
+                (!self.blank_pages.is_empty(), "--insert-blank"),
+                (self.separators, "--separators"),
+                (self.layout.is_some(), "--layout"),
+                (!self.box_edits.is_empty(), "--crop, --trim or --bleed"),
+                (self.page_size.is_some(), "--page-size"),
+                (!self.stamps.is_empty(), "--header, --footer or --stamp"),
+                (self.bates.is_some(), "--bates"),
+                (!self.watermarks.is_empty(), "--watermark"),
+                (self.page_labels != PageLabels::Keep, "--page-labels"),
+                (self.group_layers, "--group-layers"),
+                (self.remove_annotations.is_some(), "--remove-annotations"),
+                (self.flatten, "--flatten"),
+                (self.sanitize, "--sanitize"),
+            ];
+            if let Some((_, option)) = page_options.iter().find(|(used, _)| *used) {
+                return Err(PdfCatError::invalid_config(format!(
+                    "Cannot use {option} with --portfolio: portfolio inputs are embedded unchanged"
+                )));
+            }
+        }
+
+        if self.bates_log.is_some() && self.bates.is_none() {
+            return Err(PdfCatError::invalid_config(
+                "Cannot use --bates-log without --bates".to_string(),
+            ));
+        }
+
         // Validate that output path is not in inputs
         for input in &self.inputs {
             if input == &self.output {
@@ -387,9 +1982,10 @@ impl Config {
 
     /// Check if output should be displayed.
     ///
-    /// Returns false if in quiet mode and not doing a dry run.
+    /// Returns false if in quiet mode and not doing a dry run, or if
+    /// machine-readable output was requested.
     pub fn should_print(&self) -> bool {
-        !self.quiet || self.dry_run
+        self.output_format == OutputFormat::Text && (!self.quiet || self.dry_run)
     }
 }
 
@@ -418,6 +2014,13 @@ mod tests {
         assert!(CompressionLevel::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!(OutputFormat::from_str("text").unwrap(), OutputFormat::Text);
+        assert_eq!(OutputFormat::from_str("JSON").unwrap(), OutputFormat::Json);
+        assert!(OutputFormat::from_str("xml").is_err());
+    }
+
     #[test]
     fn test_rotation_from_degrees() {
         assert_eq!(Rotation::from_degrees(90).unwrap(), Rotation::Clockwise90);
@@ -472,13 +2075,283 @@ mod tests {
         assert_eq!(range.to_pages(10), vec![2, 3, 4, 6]);
     }
 
+    #[test]
+    fn test_page_range_from_pages() {
+        let range = PageRange::from_pages(&[1, 2, 3, 5, 7, 8]);
+        assert_eq!(range.to_string(), "1-3,5,7-8");
+        assert_eq!(range.to_pages(10), vec![1, 2, 3, 5, 7, 8]);
+        assert_eq!(PageRange::parse(&range.to_string()).unwrap(), range);
+
+        let range = PageRange::from_pages(&[4, 3, 2, 2, 9]);
+        assert_eq!(range.to_string(), "4-2,2,9");
+        assert_eq!(range.to_pages(10), vec![4, 3, 2, 2, 9]);
+    }
+
     #[test]
     fn test_page_range_invalid() {
         assert!(PageRange::parse("0").is_err());
-        assert!(PageRange::parse("5-3").is_err());
+        assert!(PageRange::parse("-0").is_err());
         assert!(PageRange::parse("abc").is_err());
         assert!(PageRange::parse("").is_err());
+        assert!(PageRange::parse("1,").is_err());
         assert!(PageRange::parse("1-2-3").is_err());
+        assert!(PageRange::parse("endless").is_err());
+    }
+
+    #[test]
+    fn test_page_range_descending() {
+        let range = PageRange::parse("5-3").unwrap();
+        assert_eq!(range.to_pages(10), vec![5, 4, 3]);
+        assert!(range.contains(4));
+
+        let reversed = PageRange::parse("end-1").unwrap();
+        assert_eq!(reversed.to_pages(4), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_page_range_from_end() {
+        let range = PageRange::parse("-1").unwrap();
+        assert_eq!(range.to_pages(7), vec![7]);
+        assert_eq!(range, PageRange::parse("end").unwrap());
+
+        let range = PageRange::parse("-3--1").unwrap();
+        assert_eq!(range.to_pages(7), vec![5, 6, 7]);
+        assert_eq!(range.to_pages(2), vec![1, 2]);
+
+        let range = PageRange::parse("2-end").unwrap();
+        assert_eq!(range.to_pages(4), vec![2, 3, 4]);
+        assert!(!range.contains(3));
+    }
+
+    #[test]
+    fn test_page_range_odd_even() {
+        assert_eq!(PageRange::parse("odd").unwrap().to_pages(5), vec![1, 3, 5]);
+        assert_eq!(PageRange::parse("EVEN").unwrap().to_pages(5), vec![2, 4]);
+        assert!(PageRange::parse("even").unwrap().contains(2));
+    }
+
+    #[test]
+    fn test_page_range_order_and_duplicates() {
+        let range = PageRange::parse("3,1,3, 2 - 1").unwrap();
+        assert_eq!(range.to_pages(5), vec![3, 1, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_page_range_out_of_document() {
+        let range = PageRange::parse("4-8,-9,9-4").unwrap();
+        assert_eq!(range.to_pages(5), vec![4, 5, 5, 4]);
+    }
+
+    #[test]
+    fn test_page_range_resolve_policy() {
+        let path = Path::new("five.pdf");
+
+        let range = PageRange::parse("4-8").unwrap();
+        assert_eq!(
+            range.resolve(path, 5, PageRangePolicy::Lenient).unwrap(),
+            vec![4, 5]
+        );
+
+        let err = range.resolve(path, 5, PageRangePolicy::Strict).unwrap_err();
+        match err {
+            PdfCatError::InvalidPageRange {
+                path,
+                range,
+                total_pages,
+            } => {
+                assert_eq!(path, PathBuf::from("five.pdf"));
+                assert_eq!(range, "4-8");
+                assert_eq!(total_pages, 5);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        let missing = PageRange::parse("7-9").unwrap();
+        assert!(missing.resolve(path, 5, PageRangePolicy::Lenient).is_err());
+
+        let relative = PageRange::parse("-5-end,odd").unwrap();
+        assert!(relative.resolve(path, 5, PageRangePolicy::Strict).is_ok());
+        assert!(relative.resolve(path, 4, PageRangePolicy::Strict).is_err());
+    }
+
+    #[test]
+    fn test_page_range_policy_from_str() {
+        assert_eq!(
+            "strict".parse::<PageRangePolicy>().unwrap(),
+            PageRangePolicy::Strict
+        );
+        assert_eq!(
+            "Lenient".parse::<PageRangePolicy>().unwrap(),
+            PageRangePolicy::Lenient
+        );
+        assert!("loose".parse::<PageRangePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_page_range_display_round_trip() {
+        for input in ["1-3,7", "end-1", "-3-end", "odd,-2,4,4"] {
+            let range = PageRange::parse(input).unwrap();
+            assert_eq!(range.to_string(), input);
+            assert_eq!(PageRange::parse(&range.to_string()).unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn test_page_size_from_str() {
+        assert_eq!("A4".parse::<PageSize>().unwrap(), PageSize::A4);
+        assert_eq!("letter".parse::<PageSize>().unwrap(), PageSize::LETTER);
+        assert_eq!(
+            "792x612".parse::<PageSize>().unwrap(),
+            PageSize::new(612, 792)
+        );
+        assert_eq!(
+            "210x297mm".parse::<PageSize>().unwrap(),
+            PageSize::new(595, 842)
+        );
+        assert_eq!("8.5x11in".parse::<PageSize>().unwrap(), PageSize::LETTER);
+        assert!("0x100".parse::<PageSize>().is_err());
+        assert!("a4x".parse::<PageSize>().is_err());
+        assert!("b5".parse::<PageSize>().is_err());
+
+        assert_eq!(PageSize::A4.oriented(842.0, 595.0), (842.0, 595.0));
+        assert_eq!(PageSize::A4.oriented(612.0, 792.0), (595.0, 842.0));
+    }
+
+    #[test]
+    fn test_box_edit_parse() {
+        let edit = BoxEdit::parse(BoxKind::Crop, "10").unwrap();
+        assert_eq!(edit.kind, BoxKind::Crop);
+        assert_eq!(edit.change, BoxChange::Margins([10.0; 4]));
+        assert_eq!(edit.pages, None);
+
+        let edit = BoxEdit::parse(BoxKind::Trim, "1,2,3,4@odd").unwrap();
+        assert_eq!(edit.change, BoxChange::Margins([1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(edit.pages.unwrap().to_pages(4), vec![1, 3]);
+
+        let edit = BoxEdit::parse(BoxKind::Bleed, "rect:10,10,0,0mm").unwrap();
+        let mm = 72.0 / 25.4;
+        assert_eq!(
+            edit.change,
+            BoxChange::Rect([0.0, 0.0, (10.0 * mm) as f32, (10.0 * mm) as f32])
+        );
+
+        let edit = BoxEdit::parse(BoxKind::Crop, "AUTO@1").unwrap();
+        assert_eq!(edit.change, BoxChange::Auto);
+
+        assert!(BoxEdit::parse(BoxKind::Crop, "").is_err());
+        assert!(BoxEdit::parse(BoxKind::Crop, "1,2,3").is_err());
+        assert!(BoxEdit::parse(BoxKind::Crop, "rect:0,0,0,10").is_err());
+        assert!(BoxEdit::parse(BoxKind::Crop, "10@x").is_err());
+        assert!(BoxEdit::parse(BoxKind::Crop, "nan").is_err());
+    }
+
+    #[test]
+    fn test_stamp_parse() {
+        let stamp = Stamp::parse("top-left={file} - {date}").unwrap();
+        assert_eq!(stamp.position, StampPosition::TopLeft);
+        assert_eq!(stamp.text, "{file} - {date}");
+        assert_eq!(stamp.font_size, Stamp::DEFAULT_FONT_SIZE);
+
+        // Only the first "=" separates the position
+        let stamp = Stamp::parse("Bottom-Center=a=b").unwrap();
+        assert_eq!(stamp.position, StampPosition::BottomCenter);
+        assert_eq!(stamp.text, "a=b");
+
+        assert!(Stamp::parse("Page {n}").is_err());
+        assert!(Stamp::parse("middle=Page {n}").is_err());
+        assert!(Stamp::parse("top-right= ").is_err());
+    }
+
+    #[test]
+    fn test_page_fit_from_str() {
+        assert_eq!("fit".parse::<PageFit>().unwrap(), PageFit::Fit);
+        assert_eq!("PAD".parse::<PageFit>().unwrap(), PageFit::Pad);
+        assert!("stretch".parse::<PageFit>().is_err());
+    }
+
+    #[test]
+    fn test_layout_from_str() {
+        assert_eq!(
+            "2up".parse::<Layout>().unwrap(),
+            Layout::Grid {
+                columns: 2,
+                rows: 1
+            }
+        );
+        assert_eq!(
+            "4-up".parse::<Layout>().unwrap(),
+            Layout::Grid {
+                columns: 2,
+                rows: 2
+            }
+        );
+        assert_eq!(
+            "3x4".parse::<Layout>().unwrap(),
+            Layout::Grid {
+                columns: 3,
+                rows: 4
+            }
+        );
+        assert_eq!("Booklet".parse::<Layout>().unwrap(), Layout::Booklet);
+        assert_eq!(Layout::Booklet.pages_per_sheet(), 2);
+
+        assert!("0x2".parse::<Layout>().is_err());
+        assert!("32x32".parse::<Layout>().is_ok());
+        assert!("33x1".parse::<Layout>().is_err());
+        assert!("65536x65536".parse::<Layout>().is_err());
+        assert_eq!(
+            Layout::Grid {
+                columns: 65536,
+                rows: 65536
+            }
+            .pages_per_sheet(),
+            u32::MAX
+        );
+        assert!("5up".parse::<Layout>().is_err());
+        assert!("wide".parse::<Layout>().is_err());
+    }
+
+    #[test]
+    fn test_viewer_settings_from_str() {
+        assert_eq!("Outline".parse::<PageMode>().unwrap(), PageMode::Outline);
+        assert_eq!(PageMode::Layers.as_name(), "UseOC");
+        assert!("panel".parse::<PageMode>().is_err());
+
+        assert_eq!(
+            "two-page-right".parse::<PageLayout>().unwrap(),
+            PageLayout::TwoPageRight
+        );
+        assert_eq!(PageLayout::Continuous.as_name(), "OneColumn");
+        assert!("spread".parse::<PageLayout>().is_err());
+
+        assert_eq!(
+            "2:fit-width".parse::<OpenAt>().unwrap(),
+            OpenAt {
+                page: 2,
+                zoom: Some(Zoom::FitWidth)
+            }
+        );
+        assert_eq!(
+            "4: 80".parse::<OpenAt>().unwrap().zoom,
+            Some(Zoom::Percent(80.0))
+        );
+        assert!("fit".parse::<OpenAt>().is_err());
+        assert!("1:0%".parse::<OpenAt>().is_err());
+        assert!("1:wide".parse::<OpenAt>().is_err());
+    }
+
+    #[test]
+    fn test_pdf_version_from_str() {
+        assert_eq!("2.0".parse::<PdfVersion>().unwrap(), PdfVersion::new(2, 0));
+        assert!("1.10".parse::<PdfVersion>().is_err());
+        assert!("1".parse::<PdfVersion>().is_err());
+
+        assert!(PdfVersion::new(1, 7) < PdfVersion::new(2, 0));
+        assert_eq!(PdfVersion::new(1, 5).to_string(), "1.5");
+        assert_eq!(
+            serde_json::to_string(&PdfVersion::new(1, 6)).unwrap(),
+            "\"1.6\""
+        );
     }
 
     #[test]
@@ -521,9 +2394,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         };
 
         assert!(config.validate().is_ok());
@@ -545,6 +2442,18 @@ mod tests {
         assert!(config.validate().is_err());
         config.jobs = None;
 
+        // Test page options with a portfolio
+        config.merge_mode = MergeMode::Portfolio;
+        config.attachments = vec![PathBuf::from("data.xlsx")];
+        assert!(config.validate().is_ok());
+        config.duplex = true;
+        assert!(config.validate().is_err());
+        config.duplex = false;
+        config.group_layers = true;
+        assert!(config.validate().is_err());
+        config.group_layers = false;
+        config.merge_mode = MergeMode::Concatenate;
+
         // Test output same as input
         config.output = PathBuf::from("a.pdf");
         assert!(config.validate().is_err());
@@ -563,9 +2472,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: Some(4),
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         };
 
         assert_eq!(config.effective_jobs(), 4);
@@ -591,9 +2524,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         };
 
         assert!(config.should_print());
@@ -11,6 +11,7 @@
 //! - **Validation Errors**: Invalid arguments or configuration
 //! - **Merge Errors**: Problems during the merge process
 
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io;
 use std::path::PathBuf;
@@ -125,6 +126,12 @@ pub enum PdfCatError {
         reason: String,
     },
 
+    /// Split operation failed.
+    SplitFailed {
+        /// Description of what went wrong.
+        reason: String,
+    },
+
     /// Bookmark operation failed.
     BookmarkFailed {
         /// Path to the PDF where bookmark operation failed.
@@ -268,6 +275,9 @@ impl fmt::Display for PdfCatError {
             Self::MergeFailed { reason } => {
                 write!(f, "Merge operation failed: {reason}")
             }
+            Self::SplitFailed { reason } => {
+                write!(f, "Split operation failed: {reason}")
+            }
             Self::BookmarkFailed { path, reason } => {
                 write!(
                     f,
@@ -308,6 +318,30 @@ impl std::error::Error for PdfCatError {
     }
 }
 
+/// Serializable description of an error for machine-readable output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorReport {
+    /// Stable identifier of the error kind (e.g. "fileNotFound").
+    pub kind: String,
+
+    /// Human-readable error message.
+    pub message: String,
+
+    /// Process exit code associated with the error.
+    pub exit_code: i32,
+}
+
+impl From<&PdfCatError> for ErrorReport {
+    fn from(err: &PdfCatError) -> Self {
+        Self {
+            kind: err.kind().to_string(),
+            message: err.to_string(),
+            exit_code: err.exit_code(),
+        }
+    }
+}
+
 impl From<io::Error> for PdfCatError {
     fn from(err: io::Error) -> Self {
         Self::Io { source: err }
@@ -364,6 +398,13 @@ impl PdfCatError {
         }
     }
 
+    /// Create a SplitFailed error.
+    pub fn split_failed(reason: impl Into<String>) -> Self {
+        Self::SplitFailed {
+            reason: reason.into(),
+        }
+    }
+
     /// Create an InvalidConfig error.
     pub fn invalid_config(message: impl Into<String>) -> Self {
         Self::InvalidConfig {
@@ -405,6 +446,36 @@ impl PdfCatError {
         )
     }
 
+    /// Get a stable identifier for the kind of this error.
+    ///
+    /// Intended for machine-readable output, where scripts should match on
+    /// the kind rather than on the message text.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::FileNotFound { .. } => "fileNotFound",
+            Self::FileNotAccessible { .. } => "fileNotAccessible",
+            Self::NotAFile { .. } => "notAFile",
+            Self::FailedToLoadPdf { .. } => "failedToLoadPdf",
+            Self::CorruptedPdf { .. } => "corruptedPdf",
+            Self::EncryptedPdf { .. } => "encryptedPdf",
+            Self::NoFilesToMerge => "noFilesToMerge",
+            Self::OutputExists { .. } => "outputExists",
+            Self::FailedToCreateOutput { .. } => "failedToCreateOutput",
+            Self::FailedToWrite { .. } => "failedToWrite",
+            Self::FailedToReadInputList { .. } => "failedToReadInputList",
+            Self::InvalidInputList { .. } => "invalidInputList",
+            Self::InvalidPageRange { .. } => "invalidPageRange",
+            Self::MergeFailed { .. } => "mergeFailed",
+            Self::SplitFailed { .. } => "splitFailed",
+            Self::BookmarkFailed { .. } => "bookmarkFailed",
+            Self::MetadataFailed { .. } => "metadataFailed",
+            Self::InvalidConfig { .. } => "invalidConfig",
+            Self::Cancelled => "cancelled",
+            Self::Io { .. } => "io",
+            Self::Other { .. } => "other",
+        }
+    }
+
     /// Get the exit code for this error.
     ///
     /// Returns the appropriate process exit code based on error type.
@@ -424,6 +495,7 @@ impl PdfCatError {
             Self::InvalidInputList { .. } => 1,
             Self::InvalidPageRange { .. } => 1,
             Self::MergeFailed { .. } => 6,
+            Self::SplitFailed { .. } => 6,
             Self::BookmarkFailed { .. } => 6,
             Self::MetadataFailed { .. } => 6,
             Self::InvalidConfig { .. } => 1,
@@ -533,6 +605,16 @@ mod tests {
         assert_eq!(PdfCatError::Cancelled.exit_code(), 130);
     }
 
+    #[test]
+    fn test_error_report() {
+        let err = PdfCatError::file_not_found(PathBuf::from("missing.pdf"));
+        let report = ErrorReport::from(&err);
+
+        assert_eq!(report.kind, "fileNotFound");
+        assert_eq!(report.exit_code, 2);
+        assert!(report.message.contains("missing.pdf"));
+    }
+
     #[test]
     fn test_from_io_error() {
         let io_err = io::Error::new(io::ErrorKind::NotFound, "not found");
@@ -0,0 +1,407 @@
+//! PDF inspection.
+//!
+//! This module gathers everything pdfcat knows about a PDF file without
+//! modifying it:
+//! - Validation details (version, page count, size, object count)
+//! - Document metadata
+//! - Per-page sizes and rotations
+//! - Outline, font, attachment and form field information
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use pdfcat::inspect::Inspector;
+//! use std::path::PathBuf;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let inspector = Inspector::new();
+//! let info = inspector.inspect(&PathBuf::from("document.pdf")).await?;
+//!
+//! println!("{} pages, {} fonts", info.pages.len(), info.fonts.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use crate::config::Metadata;
+use crate::error::{PdfCatError, Result};
+use crate::io::PdfReader;
+use crate::merge::layout::{inherited_attribute, rect};
+use crate::merge::trees::name_tree_entries;
+use crate::merge::{BookmarkManager, MetadataManager};
+use crate::validation::ValidationResult;
+
+/// Size and orientation of a single page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    /// Page number (1-indexed).
+    pub number: u32,
+
+    /// Page width in points.
+    pub width: f32,
+
+    /// Page height in points.
+    pub height: f32,
+
+    /// Page rotation in degrees.
+    pub rotation: i64,
+}
+
+/// Everything pdfcat knows about a PDF file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+    /// Validation details for the file.
+    #[serde(flatten)]
+    pub summary: ValidationResult,
+
+    /// Document metadata from the Info dictionary.
+    pub metadata: Metadata,
+
+    /// Size and rotation of each page.
+    pub pages: Vec<PageInfo>,
+
+    /// Whether the document has an outline (bookmarks).
+    pub has_outline: bool,
+
+    /// Names of the fonts used by the pages.
+    pub fonts: Vec<String>,
+
+    /// Names of embedded file attachments.
+    pub attachments: Vec<String>,
+
+    /// Fully qualified names of interactive form fields.
+    pub form_fields: Vec<String>,
+}
+
+/// Inspector for PDF files.
+#[derive(Debug, Clone, Default)]
+pub struct Inspector {
+    /// Whether to attempt repairing files that fail to load.
+    repair: bool,
+}
+
+impl Inspector {
+    /// Create a new inspector.
+    pub fn new() -> Self {
+        Self { repair: false }
+    }
+
+    /// Create a new inspector that repairs damaged files.
+    pub fn with_repair() -> Self {
+        Self { repair: true }
+    }
+
+    /// Inspect a PDF file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the PDF file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file doesn't exist or cannot be loaded.
+    pub async fn inspect(&self, path: &Path) -> Result<DocumentInfo> {
+        if !path.exists() {
+            return Err(PdfCatError::file_not_found(path.to_path_buf()));
+        }
+
+        if !path.is_file() {
+            return Err(PdfCatError::NotAFile {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let reader = if self.repair {
+            PdfReader::with_repair()
+        } else {
+            PdfReader::new()
+        };
+        let loaded = reader.load(path).await?;
+
+        let mut info = inspect_document(path, &loaded.document)?;
+        info.summary.repair = loaded.repair;
+
+        Ok(info)
+    }
+}
+
+/// Collect information about a loaded document.
+///
+/// # Arguments
+///
+/// * `path` - Path the document was loaded from
+/// * `doc` - Loaded PDF document
+///
+/// # Errors
+///
+/// Returns an error if page information cannot be extracted.
+pub fn inspect_document(path: &Path, doc: &Document) -> Result<DocumentInfo> {
+    let mut summary = ValidationResult::from_document(path.to_path_buf(), doc)?;
+    summary.is_encrypted = doc.is_encrypted() || doc.encryption_state.is_some();
+
+    let mut fonts = BTreeSet::new();
+    let mut pages = Vec::new();
+
+    for (number, page_id) in doc.get_pages() {
+        let (width, height) = inherited_attribute(doc, page_id, b"MediaBox")
+            .and_then(|obj| rect(doc, obj))
+            .map_or((612.0, 792.0), |[x0, y0, x1, y1]| (x1 - x0, y1 - y0));
+
+        let rotation = inherited_attribute(doc, page_id, b"Rotate")
+            .and_then(|obj| obj.as_i64().ok())
+            .unwrap_or(0);
+
+        pages.push(PageInfo {
+            number,
+            width,
+            height,
+            rotation,
+        });
+
+        if let Ok(page_fonts) = doc.get_page_fonts(page_id) {
+            for (name, font) in page_fonts {
+                let base_font = font
+                    .get(b"BaseFont")
+                    .and_then(Object::as_name)
+                    .unwrap_or(&name);
+                fonts.insert(String::from_utf8_lossy(base_font).into_owned());
+            }
+        }
+    }
+
+    Ok(DocumentInfo {
+        summary,
+        metadata: MetadataManager::new().get_metadata(doc),
+        pages,
+        has_outline: BookmarkManager::new().has_bookmarks(doc),
+        fonts: fonts.into_iter().collect(),
+        attachments: attachment_names(doc),
+        form_fields: form_field_names(doc),
+    })
+}
+
+/// Resolve a dictionary entry that may be stored inline or by reference.
+fn get_dict<'a>(doc: &'a Document, dict: &'a Dictionary, key: &[u8]) -> Option<&'a Dictionary> {
+    let obj = dict.get(key).ok()?;
+    doc.dereference(obj).ok()?.1.as_dict().ok()
+}
+
+/// Resolve an array entry that may be stored inline or by reference.
+fn get_array<'a>(doc: &'a Document, dict: &'a Dictionary, key: &[u8]) -> Option<&'a Vec<Object>> {
+    let obj = dict.get(key).ok()?;
+    doc.dereference(obj).ok()?.1.as_array().ok()
+}
+
+/// Names of the files in the document's EmbeddedFiles name tree.
+fn attachment_names(doc: &Document) -> Vec<String> {
+    let Ok(catalog) = doc.catalog() else {
+        return Vec::new();
+    };
+    let Some(tree) =
+        get_dict(doc, catalog, b"Names").and_then(|names| get_dict(doc, names, b"EmbeddedFiles"))
+    else {
+        return Vec::new();
+    };
+
+    name_tree_entries(doc, tree)
+        .into_iter()
+        .filter_map(|(name, _)| lopdf::decode_text_string(&Object::string_literal(name)).ok())
+        .collect()
+}
+
+/// Fully qualified names of the terminal fields in the AcroForm.
+fn form_field_names(doc: &Document) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(catalog) = doc.catalog()
+        && let Some(acro_form) = get_dict(doc, catalog, b"AcroForm")
+        && let Some(fields) = get_array(doc, acro_form, b"Fields")
+    {
+        let mut seen = HashSet::new();
+        for field in fields {
+            collect_form_fields(doc, field, "", &mut names, &mut seen);
+        }
+    }
+
+    names
+}
+
+/// Collect field names below a form field node.
+fn collect_form_fields(
+    doc: &Document,
+    field: &Object,
+    parent_name: &str,
+    names: &mut Vec<String>,
+    seen: &mut HashSet<ObjectId>,
+) {
+    if let Ok(id) = field.as_reference()
+        && !seen.insert(id)
+    {
+        return;
+    }
+
+    let Some(dict) = doc
+        .dereference(field)
+        .ok()
+        .and_then(|(_, obj)| obj.as_dict().ok())
+    else {
+        return;
+    };
+
+    let partial = dict
+        .get(b"T")
+        .ok()
+        .and_then(|t| lopdf::decode_text_string(t).ok());
+    let name = match (&partial, parent_name.is_empty()) {
+        (Some(partial), true) => partial.clone(),
+        (Some(partial), false) => format!("{parent_name}.{partial}"),
+        (None, _) => parent_name.to_string(),
+    };
+
+    // Kids without a /T entry are widget annotations of this field
+    let child_fields: Vec<&Object> = get_array(doc, dict, b"Kids")
+        .map(|kids| {
+            kids.iter()
+                .filter(|kid| {
+                    doc.dereference(kid)
+                        .ok()
+                        .and_then(|(_, obj)| obj.as_dict().ok())
+                        .is_some_and(|kid| kid.has(b"T"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if child_fields.is_empty() {
+        if !name.is_empty() {
+            names.push(name);
+        }
+        return;
+    }
+
+    for kid in child_fields {
+        collect_form_fields(doc, kid, &name, names, seen);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{StringFormat, dictionary};
+    use std::path::PathBuf;
+
+    fn create_test_document() -> Document {
+        let mut doc = Document::with_version("1.5");
+
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+
+        let page1 = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => font_id },
+            },
+        });
+        let page2 = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 842.into(), 595.into()],
+            "Rotate" => 90,
+        });
+
+        doc.objects.insert(
+            pages_id,
+            dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page1.into(), page2.into()],
+                "Count" => 2,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }
+            .into(),
+        );
+
+        let field = doc.add_object(dictionary! {
+            "T" => Object::String(b"name".to_vec(), StringFormat::Literal),
+            "FT" => "Tx",
+        });
+        let parent_field = doc.add_object(dictionary! {
+            "T" => Object::String(b"person".to_vec(), StringFormat::Literal),
+            "Kids" => vec![field.into()],
+        });
+
+        let filespec = doc.add_object(dictionary! { "Type" => "Filespec" });
+
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "AcroForm" => dictionary! { "Fields" => vec![parent_field.into()] },
+            "Names" => dictionary! {
+                "EmbeddedFiles" => dictionary! {
+                    "Names" => vec![
+                        Object::String(b"data.csv".to_vec(), StringFormat::Literal),
+                        filespec.into(),
+                    ],
+                },
+            },
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        doc
+    }
+
+    #[test]
+    fn test_inspect_document_pages() {
+        let doc = create_test_document();
+        let info = inspect_document(Path::new("test.pdf"), &doc).unwrap();
+
+        assert_eq!(info.pages.len(), 2);
+        assert_eq!(info.pages[0].width, 595.0);
+        assert_eq!(info.pages[0].height, 842.0);
+        assert_eq!(info.pages[0].rotation, 0);
+        assert_eq!(info.pages[1].width, 842.0);
+        assert_eq!(info.pages[1].rotation, 90);
+    }
+
+    #[test]
+    fn test_inspect_document_resources() {
+        let doc = create_test_document();
+        let info = inspect_document(Path::new("test.pdf"), &doc).unwrap();
+
+        assert_eq!(info.fonts, vec!["Helvetica".to_string()]);
+        assert_eq!(info.attachments, vec!["data.csv".to_string()]);
+        assert_eq!(info.form_fields, vec!["person.name".to_string()]);
+        assert!(!info.has_outline);
+        assert!(!info.summary.is_encrypted);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_file() {
+        let path = PathBuf::from("tests/fixtures/basic.pdf");
+        if !path.exists() {
+            return;
+        }
+
+        let info = Inspector::new().inspect(&path).await.unwrap();
+        assert_eq!(info.summary.page_count, 1);
+        assert_eq!(info.pages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_missing_file() {
+        let result = Inspector::new()
+            .inspect(Path::new("/nonexistent/file.pdf"))
+            .await;
+        assert!(matches!(result, Err(PdfCatError::FileNotFound { .. })));
+    }
+}
@@ -0,0 +1,566 @@
+//! JPEG and PNG images as PDF inputs.
+//!
+//! An image input becomes a one-page document with the image filling the
+//! page. The page is sized from the image's resolution, or at 72 dpi (one
+//! point per pixel) when the image doesn't give one; `--page-size` fits it
+//! to paper like any other page.
+//!
+//! JPEG data is embedded as it is, with `/DCTDecode`, so it is not
+//! re-encoded. PNG images are decoded and embedded with `/FlateDecode`,
+//! their alpha channel, if any, becoming a soft mask (`/SMask`).
+
+use lopdf::{Document, Object, Stream, dictionary};
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::error::{PdfCatError, Result};
+use crate::merge::layout::rect_object;
+use crate::utils::{is_pdf_header, read_file_header};
+
+/// Resolution assumed for images that don't give one, in dots per inch.
+const DEFAULT_DPI: f32 = 72.0;
+
+/// Image format of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// JPEG (`.jpg`, `.jpeg`).
+    Jpeg,
+    /// PNG (`.png`).
+    Png,
+}
+
+impl ImageFormat {
+    /// Get the image format of a file from its extension.
+    ///
+    /// # Returns
+    ///
+    /// The format, or None if the file is not a JPEG or PNG image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::io::image::ImageFormat;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(ImageFormat::from_path(Path::new("scan.JPG")), Some(ImageFormat::Jpeg));
+    /// assert_eq!(ImageFormat::from_path(Path::new("report.pdf")), None);
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    /// Get the image format of data from its signature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pdfcat::io::image::ImageFormat;
+    ///
+    /// assert_eq!(ImageFormat::from_bytes(b"\xFF\xD8\xFF\xE0"), Some(ImageFormat::Jpeg));
+    /// assert_eq!(ImageFormat::from_bytes(b"%PDF-1.7"), None);
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\xFF\xD8\xFF") {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1A\n") {
+            Some(Self::Png)
+        } else {
+            None
+        }
+    }
+
+    /// Get the image format of a file from its first bytes.
+    ///
+    /// A file that starts like a PDF is not an image, whatever its
+    /// extension. The extension decides for files that start like neither,
+    /// or can't be read, so loading them reports why.
+    ///
+    /// # Returns
+    ///
+    /// The format, or None if the file is to be loaded as a PDF.
+    pub fn detect(path: &Path) -> Option<Self> {
+        match read_file_header(path) {
+            Some(header) if is_pdf_header(&header) => None,
+            Some(header) => Self::from_bytes(&header).or_else(|| Self::from_path(path)),
+            None => Self::from_path(path),
+        }
+    }
+}
+
+/// An image ready to be embedded.
+struct Image {
+    /// Width in pixels.
+    width: u32,
+
+    /// Height in pixels.
+    height: u32,
+
+    /// Horizontal and vertical resolution in dots per inch.
+    dpi: (f32, f32),
+
+    /// Image XObject, without its width and height.
+    stream: Stream,
+
+    /// Soft mask XObject with the image's alpha channel, if any.
+    mask: Option<Stream>,
+}
+
+/// Load an image file as a one-page document.
+///
+/// # Arguments
+///
+/// * `path` - Path to the image file
+/// * `format` - Format of the image
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid image of
+/// the format.
+///
+/// # Examples
+///
+/// ```no_run
+/// use pdfcat::io::image::{ImageFormat, load_image};
+/// use std::path::Path;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let doc = load_image(Path::new("scan.jpg"), ImageFormat::Jpeg).await?;
+/// assert_eq!(doc.get_pages().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn load_image(path: &Path, format: ImageFormat) -> Result<Document> {
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|e| PdfCatError::FileNotAccessible {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+    let image = match format {
+        ImageFormat::Jpeg => read_jpeg(data),
+        ImageFormat::Png => read_png(&data),
+    }
+    .map_err(|reason| PdfCatError::failed_to_load_pdf(path.to_path_buf(), reason))?;
+
+    Ok(image_document(image))
+}
+
+/// Build a one-page document showing an image.
+fn image_document(image: Image) -> Document {
+    // Transparency needs PDF 1.4
+    let mut doc = Document::with_version(if image.mask.is_some() { "1.4" } else { "1.3" });
+
+    let mut stream = image.stream;
+    stream.dict.set("Type", "XObject");
+    stream.dict.set("Subtype", "Image");
+    stream.dict.set("Width", image.width);
+    stream.dict.set("Height", image.height);
+    if let Some(mut mask) = image.mask {
+        mask.dict.set("Type", "XObject");
+        mask.dict.set("Subtype", "Image");
+        mask.dict.set("Width", image.width);
+        mask.dict.set("Height", image.height);
+        stream.dict.set("SMask", doc.add_object(mask));
+    }
+    let image_id = doc.add_object(stream);
+
+    let width = image.width as f32 * 72.0 / image.dpi.0;
+    let height = image.height as f32 * 72.0 / image.dpi.1;
+    let content_id = doc.add_object(Stream::new(
+        dictionary! {},
+        format!("q {width} 0 0 {height} 0 0 cm /Im0 Do Q").into_bytes(),
+    ));
+
+    let pages_id = doc.new_object_id();
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => rect_object([0.0, 0.0, width, height]),
+        "Resources" => dictionary! {
+            "XObject" => dictionary! { "Im0" => image_id },
+        },
+        "Contents" => content_id,
+    });
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![Object::Reference(page_id)],
+            "Count" => 1,
+        }),
+    );
+
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc
+}
+
+/// Read the header of a JPEG image, keeping its data as it is.
+fn read_jpeg(data: Vec<u8>) -> std::result::Result<Image, String> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err("Not a JPEG image".to_string());
+    }
+
+    let mut frame = None;
+    let mut dpi = None;
+    let mut adobe = false;
+
+    // Walk the marker segments up to the start of the scan
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err("Invalid JPEG marker".to_string());
+        }
+        let marker = data[pos + 1];
+        match marker {
+            // Fill bytes, and markers without a segment
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            0x01 | 0xD0..=0xD7 => {
+                pos += 2;
+                continue;
+            }
+            // Start of scan
+            0xDA => break,
+            _ => {}
+        }
+
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let segment = data
+            .get(pos + 4..pos + 2 + length)
+            .ok_or("Truncated JPEG segment")?;
+        match marker {
+            // Start of frame, except for huffman tables (C4), the reserved
+            // JPG extension (C8) and arithmetic coding conditioning (CC)
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) && segment.len() >= 6 => {
+                let precision = segment[0];
+                let height = u16::from_be_bytes([segment[1], segment[2]]);
+                let width = u16::from_be_bytes([segment[3], segment[4]]);
+                frame = Some((precision, width, height, segment[5]));
+            }
+            // JFIF header, with the resolution in dots per inch (1) or per
+            // centimeter (2)
+            0xE0 if segment.starts_with(b"JFIF\0") && segment.len() >= 12 => {
+                let x = u16::from_be_bytes([segment[8], segment[9]]) as f32;
+                let y = u16::from_be_bytes([segment[10], segment[11]]) as f32;
+                dpi = match segment[7] {
+                    1 => Some((x, y)),
+                    2 => Some((x * 2.54, y * 2.54)),
+                    _ => None,
+                };
+            }
+            0xEE if segment.starts_with(b"Adobe") => adobe = true,
+            _ => {}
+        }
+        pos += 2 + length;
+    }
+
+    let (precision, width, height, components) = frame.ok_or("JPEG image has no frame header")?;
+    if precision != 8 {
+        return Err(format!("{precision}-bit JPEG images are not supported"));
+    }
+    if width == 0 || height == 0 {
+        return Err("JPEG image has no size in its frame header".to_string());
+    }
+
+    let mut dict = dictionary! {
+        "BitsPerComponent" => 8,
+        "Filter" => "DCTDecode",
+    };
+    match components {
+        1 => dict.set("ColorSpace", "DeviceGray"),
+        3 => dict.set("ColorSpace", "DeviceRGB"),
+        4 => {
+            dict.set("ColorSpace", "DeviceCMYK");
+            // Adobe applications write CMYK JPEG data inverted
+            if adobe {
+                dict.set(
+                    "Decode",
+                    [1, 0, 1, 0, 1, 0, 1, 0].map(Object::Integer).to_vec(),
+                );
+            }
+        }
+        _ => {
+            return Err(format!(
+                "JPEG images with {components} components are not supported"
+            ));
+        }
+    }
+
+    Ok(Image {
+        width: u32::from(width),
+        height: u32::from(height),
+        dpi: valid_dpi(dpi),
+        stream: Stream::new(dict, data).with_compression(false),
+        mask: None,
+    })
+}
+
+/// Decode a PNG image, splitting off its alpha channel.
+fn read_png(data: &[u8]) -> std::result::Result<Image, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    // Palettes, transparent colors and 1, 2, 4 or 16-bit samples all
+    // become 8-bit gray or RGB, with or without alpha
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Invalid PNG image: {e}"))?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut pixels)
+        .map_err(|e| format!("Invalid PNG image: {e}"))?;
+    pixels.truncate(frame.buffer_size());
+
+    // PNG gives pixels per meter; 300 dpi is stored as 11811, so the
+    // resolution is rounded to get whole inches back
+    let per_inch = |per_meter: u32| (per_meter as f32 * 0.0254 * 100.0).round() / 100.0;
+    let dpi = reader
+        .info()
+        .pixel_dims
+        .filter(|dims| dims.unit == png::Unit::Meter)
+        .map(|dims| (per_inch(dims.xppu), per_inch(dims.yppu)));
+
+    let (color_space, colors, has_alpha) = match frame.color_type {
+        png::ColorType::Grayscale => ("DeviceGray", 1, false),
+        png::ColorType::GrayscaleAlpha => ("DeviceGray", 1, true),
+        png::ColorType::Rgb => ("DeviceRGB", 3, false),
+        png::ColorType::Rgba => ("DeviceRGB", 3, true),
+        png::ColorType::Indexed => return Err("Unexpected PNG palette".to_string()),
+    };
+
+    let (color, alpha) = if has_alpha {
+        let mut color = Vec::with_capacity(pixels.len() / (colors + 1) * colors);
+        let mut alpha = Vec::with_capacity(pixels.len() / (colors + 1));
+        for pixel in pixels.chunks_exact(colors + 1) {
+            color.extend_from_slice(&pixel[..colors]);
+            alpha.push(pixel[colors]);
+        }
+        (color, Some(alpha))
+    } else {
+        (pixels, None)
+    };
+
+    let image_stream = |color_space: &str, samples: Vec<u8>| {
+        let mut stream = Stream::new(
+            dictionary! {
+                "ColorSpace" => color_space,
+                "BitsPerComponent" => 8,
+            },
+            samples,
+        );
+        // Leaves the samples as they are if they don't compress
+        let _ = stream.compress();
+        stream
+    };
+
+    Ok(Image {
+        width: frame.width,
+        height: frame.height,
+        dpi: valid_dpi(dpi),
+        stream: image_stream(color_space, color),
+        // A fully opaque alpha channel needs no mask
+        mask: alpha
+            .filter(|alpha| alpha.iter().any(|&value| value < 255))
+            .map(|alpha| image_stream("DeviceGray", alpha)),
+    })
+}
+
+/// The resolution of an image, or the default one if it gives none.
+fn valid_dpi(dpi: Option<(f32, f32)>) -> (f32, f32) {
+    match dpi {
+        Some((x, y)) if x >= 1.0 && y >= 1.0 => (x, y),
+        _ => (DEFAULT_DPI, DEFAULT_DPI),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Header segments of a JPEG image, without scan data.
+    fn jpeg_header(width: u16, height: u16, components: u8, dpi: u16) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        // JFIF header in dots per inch
+        data.extend([0xFF, 0xE0, 0x00, 0x10]);
+        data.extend(b"JFIF\0");
+        data.extend([1, 1, 1]);
+        data.extend(dpi.to_be_bytes());
+        data.extend(dpi.to_be_bytes());
+        data.extend([0, 0]);
+        // Baseline frame header
+        data.extend([0xFF, 0xC0]);
+        data.extend((8 + 3 * u16::from(components)).to_be_bytes());
+        data.push(8);
+        data.extend(height.to_be_bytes());
+        data.extend(width.to_be_bytes());
+        data.push(components);
+        for id in 1..=components {
+            data.extend([id, 0x11, 0]);
+        }
+        data.extend([0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        data
+    }
+
+    fn encode_png(width: u32, height: u32, color_type: png::ColorType, pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: 5906, // 150 dpi
+            yppu: 5906,
+            unit: png::Unit::Meter,
+        }));
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels).unwrap();
+        writer.finish().unwrap();
+        data
+    }
+
+    fn page_size(doc: &Document) -> (f32, f32) {
+        let page_id = doc.get_pages()[&1];
+        let media_box = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        (
+            media_box[2].as_float().unwrap(),
+            media_box[3].as_float().unwrap(),
+        )
+    }
+
+    fn image(doc: &Document) -> &Stream {
+        let page = doc.get_dictionary(doc.get_pages()[&1]).unwrap();
+        let image_id = page
+            .get(b"Resources")
+            .and_then(Object::as_dict)
+            .and_then(|resources| resources.get(b"XObject"))
+            .and_then(Object::as_dict)
+            .and_then(|xobjects| xobjects.get(b"Im0"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        doc.get_object(image_id).unwrap().as_stream().unwrap()
+    }
+
+    #[test]
+    fn test_read_jpeg() {
+        let data = jpeg_header(300, 150, 3, 300);
+        let doc = image_document(read_jpeg(data.clone()).unwrap());
+
+        // 300 pixels at 300 dpi is an inch
+        assert_eq!(page_size(&doc), (72.0, 36.0));
+        let image = image(&doc);
+        assert_eq!(
+            image.dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"DCTDecode"
+        );
+        assert_eq!(
+            image.dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+            b"DeviceRGB"
+        );
+        assert_eq!(image.content, data);
+        assert_eq!(doc.version, "1.3");
+
+        assert!(read_jpeg(b"\x89PNG".to_vec()).is_err());
+        assert!(read_jpeg(jpeg_header(10, 10, 2, 72)).is_err());
+    }
+
+    #[test]
+    fn test_read_png_with_alpha() {
+        // A 150 x 1 image of red and transparent blue pixels
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 0].repeat(75);
+        let doc =
+            image_document(read_png(&encode_png(150, 1, png::ColorType::Rgba, &pixels)).unwrap());
+
+        // 150 pixels at 150 dpi is an inch
+        let (width, height) = page_size(&doc);
+        assert!((width - 72.0).abs() < 0.01 && (height - 0.48).abs() < 0.01);
+
+        let image = image(&doc);
+        assert_eq!(image.dict.get(b"Width").unwrap().as_i64().unwrap(), 150);
+        assert_eq!(
+            image.dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"FlateDecode"
+        );
+        assert_eq!(
+            image.decompressed_content().unwrap(),
+            [255, 0, 0, 0, 0, 255].repeat(75)
+        );
+
+        let mask_id = image.dict.get(b"SMask").unwrap().as_reference().unwrap();
+        let mask = doc.get_object(mask_id).unwrap().as_stream().unwrap();
+        assert_eq!(mask.decompressed_content().unwrap(), [255, 0].repeat(75));
+        assert_eq!(doc.version, "1.4");
+    }
+
+    #[test]
+    fn test_read_png_opaque() {
+        let pixels = [10, 255, 30, 255];
+        let image = read_png(&encode_png(1, 2, png::ColorType::GrayscaleAlpha, &pixels)).unwrap();
+        assert!(image.mask.is_none());
+        assert_eq!(image.dpi.0.round(), 150.0);
+
+        assert!(read_png(b"not a png").is_err());
+    }
+
+    #[test]
+    fn test_detect() {
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, data: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, data).unwrap();
+            path
+        };
+
+        // The first bytes decide over the extension
+        let jpeg = jpeg_header(1, 1, 1, 72);
+        assert_eq!(
+            ImageFormat::detect(&write("photo.png", &jpeg)),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::detect(&write("scan.jpg", b"%PDF-1.4\n")), None);
+        assert_eq!(
+            ImageFormat::detect(&write("scan", &jpeg)),
+            Some(ImageFormat::Jpeg)
+        );
+
+        // Files that are neither go by their extension
+        assert_eq!(
+            ImageFormat::detect(&write("broken.png", b"")),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::detect(&dir.path().join("missing.jpg")),
+            Some(ImageFormat::Jpeg)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_image() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("scan.jpg");
+        std::fs::write(&path, jpeg_header(612, 792, 1, 72)).unwrap();
+
+        let doc = load_image(&path, ImageFormat::Jpeg).await.unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+        assert_eq!(page_size(&doc), (612.0, 792.0));
+
+        let missing = dir.path().join("missing.png");
+        assert!(load_image(&missing, ImageFormat::Png).await.is_err());
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! This module handles all file I/O operations including:
 //! - Loading PDF documents from disk
+//! - Loading JPEG and PNG images as one-page documents
 //! - Writing merged PDFs to disk
 //! - Parallel PDF loading
+//! - Repair of damaged PDF files
 //! - Memory-efficient file handling
 //!
 //! # Examples
@@ -22,10 +24,13 @@
 //! # }
 //! ```
 
+pub mod image;
 pub mod reader;
+pub mod repair;
 pub mod writer;
 
 pub use reader::{LoadResult, LoadStatistics, LoadedPdf, PdfReader};
+pub use repair::RepairReport;
 pub use writer::PdfWriter;
 
 use crate::error::Result;
@@ -5,6 +5,7 @@
 //! - Memory-efficient document handling
 //! - Detailed load statistics
 //! - Error recovery
+//! - Opt-in repair of damaged files
 //!
 //! # Examples
 //!
@@ -24,7 +25,11 @@ use lopdf::Document;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use crate::config::PdfVersion;
 use crate::error::{PdfCatError, Result};
+use crate::io::image::{self, ImageFormat};
+use crate::io::repair::{self, RepairReport};
+use crate::merge::VersionManager;
 
 /// A loaded PDF document with metadata.
 #[derive(Debug)]
@@ -43,14 +48,26 @@ pub struct LoadedPdf {
 
     /// File size in bytes.
     pub file_size: u64,
+
+    /// PDF version the document declares, in its header or catalog.
+    pub version: Option<PdfVersion>,
+
+    /// What was recovered, if the document had to be repaired.
+    pub repair: Option<RepairReport>,
 }
 
 impl LoadedPdf {
     /// Create a new LoadedPdf from a document.
-    fn new(document: Document, path: PathBuf, load_time: Duration) -> Result<Self> {
+    fn new(
+        document: Document,
+        path: PathBuf,
+        load_time: Duration,
+        repair: Option<RepairReport>,
+    ) -> Result<Self> {
         let page_count = document.get_pages().len();
 
         let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let version = VersionManager::new().version(&document);
 
         Ok(Self {
             document,
@@ -58,6 +75,8 @@ impl LoadedPdf {
             page_count,
             load_time,
             file_size,
+            version,
+            repair,
         })
     }
 }
@@ -137,17 +156,37 @@ impl LoadStatistics {
 pub struct PdfReader {
     /// Whether to verify PDF structure after loading.
     verify: bool,
+
+    /// Whether to attempt repairing files that fail to load.
+    repair: bool,
 }
 
 impl PdfReader {
     /// Create a new PDF reader with default settings.
     pub fn new() -> Self {
-        Self { verify: true }
+        Self {
+            verify: true,
+            repair: false,
+        }
     }
 
     /// Create a reader that skips verification (faster but less safe).
     pub fn without_verification() -> Self {
-        Self { verify: false }
+        Self {
+            verify: false,
+            repair: false,
+        }
+    }
+
+    /// Create a reader that repairs damaged files instead of rejecting them.
+    ///
+    /// Files with a corrupted xref or a missing page tree are reconstructed
+    /// and the recovery is recorded in [`LoadedPdf::repair`].
+    pub fn with_repair() -> Self {
+        Self {
+            verify: true,
+            repair: true,
+        }
     }
 
     /// Load a single PDF document.
@@ -162,7 +201,7 @@ impl PdfReader {
     /// - File cannot be read
     /// - File is not a valid PDF
     /// - PDF is encrypted
-    /// - PDF structure is corrupted
+    /// - PDF structure is corrupted (and cannot be repaired, in repair mode)
     ///
     /// # Examples
     ///
@@ -182,31 +221,47 @@ impl PdfReader {
 
         let start = Instant::now();
 
-        // Load the document
-        let doc = Document::load(&path_buf).await.map_err(|e| {
-            let err_msg = e.to_string();
-            if err_msg.contains("encrypt") || err_msg.contains("password") {
-                PdfCatError::encrypted_pdf(path_buf.clone())
-            } else {
-                PdfCatError::failed_to_load_pdf(path_buf.clone(), err_msg)
+        // Load the document; an image becomes a document of one page
+        let image_format = ImageFormat::detect(&path_buf);
+        let load_result = match image_format {
+            Some(format) => image::load_image(&path_buf, format).await,
+            None => Document::load(&path_buf).await.map_err(|e| {
+                let err_msg = e.to_string();
+                if err_msg.contains("encrypt") || err_msg.contains("password") {
+                    PdfCatError::encrypted_pdf(path_buf.clone())
+                } else {
+                    PdfCatError::failed_to_load_pdf(path_buf.clone(), err_msg)
+                }
+            }),
+        };
+
+        let (mut doc, mut report) = match load_result {
+            Ok(doc) => (doc, None),
+            Err(PdfCatError::FailedToLoadPdf { .. })
+                if self.repair && path_buf.is_file() && image_format.is_none() =>
+            {
+                let (doc, report) = repair::repair_file(&path_buf).await?;
+                (doc, Some(report))
             }
-        })?;
+            Err(err) => return Err(err),
+        };
 
         // Verify the document has pages
-        if verify {
-            let pages = doc.get_pages();
-            if pages.is_empty() {
+        if verify && doc.get_pages().is_empty() {
+            if !self.repair {
                 return Err(PdfCatError::corrupted_pdf(
                     path_buf.clone(),
                     "PDF has no pages",
                 ));
             }
+
+            report = Some(repair::repair_page_tree(&path_buf, &mut doc)?);
         }
 
         let load_time = start.elapsed();
 
         // Load in a blocking task to avoid blocking the async runtime
-        let result = LoadedPdf::new(doc, path_buf, load_time)?;
+        let result = LoadedPdf::new(doc, path_buf, load_time, report)?;
 
         Ok(result)
     }
@@ -279,15 +334,13 @@ impl PdfReader {
         // Create a stream of load tasks
         let tasks = paths.iter().map(|path| {
             let path = path.clone();
-            let reader = Self {
-                verify: self.verify,
-            };
+            let reader = self.clone();
             async move { reader.load(&path).await }
         });
 
-        // Process tasks with limited concurrency
+        // Process tasks with limited concurrency, keeping input order
         stream::iter(tasks)
-            .buffer_unordered(workers)
+            .buffered(workers)
             .collect::<Vec<_>>()
             .await
     }
@@ -385,9 +438,7 @@ impl PdfReader {
 
         let tasks = paths.iter().enumerate().map(|(idx, path)| {
             let path = path.clone();
-            let reader = Self {
-                verify: self.verify,
-            };
+            let reader = self.clone();
             async move {
                 let result = reader.load(&path).await;
                 (idx, result)
@@ -560,6 +611,33 @@ mod tests {
         assert_eq!(format_file_size(1024 * 1024 * 1024), "1.00 GB");
     }
 
+    #[tokio::test]
+    async fn test_load_with_repair() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf_path = temp_dir.path().join("damaged.pdf");
+
+        // Drop everything after the last object: xref, trailer and startxref
+        let content = std::fs::read("tests/fixtures/basic.pdf").unwrap();
+        let end = content.windows(6).rposition(|w| w == b"endobj").unwrap() + 6;
+        std::fs::write(&pdf_path, &content[..end]).unwrap();
+
+        let result = PdfReader::new().load(&pdf_path).await;
+        assert!(matches!(result, Err(PdfCatError::FailedToLoadPdf { .. })));
+
+        let loaded = PdfReader::with_repair().load(&pdf_path).await.unwrap();
+        assert_eq!(loaded.page_count, 1);
+        assert!(loaded.repair.is_some_and(|report| report.xref_rebuilt));
+    }
+
+    #[tokio::test]
+    async fn test_load_intact_pdf_with_repair() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf_path = create_test_pdf(&temp_dir, "test.pdf");
+
+        let loaded = PdfReader::with_repair().load(&pdf_path).await.unwrap();
+        assert!(loaded.repair.is_none());
+    }
+
     #[tokio::test]
     async fn test_reader_without_verification() {
         let temp_dir = TempDir::new().unwrap();
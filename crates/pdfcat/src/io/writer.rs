@@ -59,7 +59,8 @@ impl Default for WriteOptions {
 }
 
 /// Statistics about a write operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct WriteStatistics {
     /// Time taken to write the file.
     pub write_time: Duration,
@@ -260,6 +261,32 @@ impl PdfWriter {
         Ok(stats)
     }
 
+    /// Serialize a PDF document to memory.
+    ///
+    /// Applies the same compression and optimization as [`PdfWriter::save`],
+    /// so the length of the result matches the size of the written file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be serialized.
+    pub fn to_bytes(&self, doc: &Document) -> Result<Vec<u8>> {
+        let mut doc = doc.clone();
+
+        if self.options.compress {
+            doc.compress();
+        }
+
+        if self.options.optimize {
+            doc.renumber_objects();
+        }
+
+        let mut buffer = Vec::new();
+        doc.save_to(&mut buffer)
+            .map_err(|e| PdfCatError::other(format!("Failed to serialize PDF: {e}")))?;
+
+        Ok(buffer)
+    }
+
     /// Check if a file can be written to the given path.
     ///
     /// Performs pre-flight checks without actually writing.
@@ -430,6 +457,21 @@ mod tests {
         assert!(stats.optimized);
     }
 
+    #[tokio::test]
+    async fn test_to_bytes_matches_file_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("output.pdf");
+
+        let doc = create_test_document();
+        let writer = PdfWriter::new();
+
+        let bytes = writer.to_bytes(&doc).unwrap();
+        let stats = writer.save_with_stats(&doc, &output_path).await.unwrap();
+
+        assert!(bytes.starts_with(b"%PDF-"));
+        assert_eq!(bytes.len() as u64, stats.file_size);
+    }
+
     #[tokio::test]
     async fn test_non_atomic_write() {
         let temp_dir = TempDir::new().unwrap();
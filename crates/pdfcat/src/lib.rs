@@ -5,8 +5,11 @@
 //!
 //! - High-quality PDF merging
 //! - Page extraction and manipulation
+//! - JPEG and PNG images as pages
 //! - Bookmark creation
 //! - Metadata management
+//! - Document inspection
+//! - Splitting documents into parts
 //! - Parallel processing
 //! - Comprehensive error handling
 //!
@@ -16,7 +19,7 @@
 //!
 //! ```no_run
 //! use pdfcat::merge;
-//! use pdfcat::config::{Config, CompressionLevel, Metadata, OverwriteMode};
+//! use pdfcat::config::{Config, CompressionLevel, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit, PageLabels, PageRangePolicy, ViewerSettings};
 //! use std::path::PathBuf;
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -31,9 +34,33 @@
 //!     compression: CompressionLevel::Standard,
 //!     metadata: Metadata::default(),
 //!     continue_on_error: false,
+//!     repair: false,
 //!     jobs: None,
 //!     page_range: None,
+//!     page_range_policy: PageRangePolicy::Lenient,
 //!     rotation: None,
+//!     merge_mode: MergeMode::Concatenate,
+//!     reverse_inputs: Vec::new(),
+//!     duplex: false,
+//!     blank_pages: Vec::new(),
+//!     separators: false,
+//!     layout: None,
+//!     box_edits: Vec::new(),
+//!     page_size: None,
+//!     page_fit: PageFit::Fit,
+//!     stamps: Vec::new(),
+//!     bates: None,
+//!     bates_log: None,
+//!     watermarks: Vec::new(),
+//!     page_labels: PageLabels::Keep,
+//!     attachments: Vec::new(),
+//!     group_layers: false,
+//!     remove_annotations: None,
+//!     flatten: false,
+//!     sanitize: false,
+//!     viewer: ViewerSettings::default(),
+//!     pdf_version: None,
+//!     output_format: OutputFormat::Text,
 //! };
 //!
 //! let (document, stats) = merge::merge_pdfs(&config).await?;
@@ -71,9 +98,11 @@
 
 pub mod config;
 pub mod error;
+pub mod inspect;
 pub mod io;
 pub mod merge;
 pub mod output;
+pub mod split;
 pub mod utils;
 pub mod validation;
 
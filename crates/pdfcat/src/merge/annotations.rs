@@ -0,0 +1,604 @@
+//! Annotation removal and flattening.
+//!
+//! Annotations sit on top of a page's content: comments and highlights,
+//! links, stamps and the widgets of form fields. Each page lists its own
+//! in `/Annots`, so both operations work page by page.
+//!
+//! Flattening draws an annotation's normal appearance stream into the page
+//! content, placed as viewers place it, and then removes the annotation.
+//! Form fields whose widgets are all removed are dropped from the
+//! AcroForm.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+
+use crate::config::AnnotationFilter;
+use crate::error::Result;
+use crate::merge::layout::{add_page_resource, append_content, rect};
+
+/// Annotation flag for annotations that are neither shown nor printed.
+const HIDDEN: i64 = 1 << 1;
+
+/// Annotation flag for annotations that are printed but not shown.
+const NO_VIEW: i64 = 1 << 5;
+
+/// Subtypes of the annotations flattened.
+const FLATTENED: &[&[u8]] = &[b"Widget", b"Stamp"];
+
+/// Form field trees nested deeper than this are taken to be cyclic.
+const MAX_DEPTH: usize = 32;
+
+/// A 2D rectangle as [x0, y0, x1, y1].
+type Rect = [f32; 4];
+
+/// Editor for the annotations of pages.
+#[derive(Debug, Clone)]
+pub struct AnnotationEditor;
+
+impl AnnotationEditor {
+    /// Create a new annotation editor.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw the form fields and stamps of every page into its content.
+    ///
+    /// Annotations without an appearance stream can't be drawn and are
+    /// kept; hidden ones are removed without being drawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::AnnotationEditor;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// AnnotationEditor::new().flatten(&mut doc)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flatten(&self, doc: &mut Document) -> Result<()> {
+        let mut removed = HashSet::new();
+        let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        for page_id in page_ids {
+            self.flatten_page(doc, page_id, &mut removed)?;
+        }
+
+        prune_fields(doc, &removed);
+        Ok(())
+    }
+
+    /// Remove the annotations a filter matches from every page.
+    ///
+    /// Pop-up notes go with the annotations they belong to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    pub fn remove(&self, doc: &mut Document, filter: &AnnotationFilter) -> Result<()> {
+        let mut removed = HashSet::new();
+        let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        for page_id in page_ids {
+            self.filter_page(doc, page_id, filter, &mut removed);
+        }
+
+        prune_fields(doc, &removed);
+        Ok(())
+    }
+
+    /// Flatten the form fields and stamps of a single page.
+    fn flatten_page(
+        &self,
+        doc: &mut Document,
+        page_id: ObjectId,
+        removed: &mut HashSet<ObjectId>,
+    ) -> Result<()> {
+        let mut kept = Vec::new();
+        let mut drawn = Vec::new();
+        for entry in annotations(doc, page_id) {
+            let Some(annot) = annotation(doc, &entry) else {
+                kept.push(entry);
+                continue;
+            };
+            if !subtype(annot).is_some_and(|subtype| FLATTENED.contains(&subtype)) {
+                kept.push(entry);
+                continue;
+            }
+
+            let flags = annot.get(b"F").and_then(Object::as_i64).unwrap_or(0);
+            if flags & (HIDDEN | NO_VIEW) == 0 {
+                let placed = appearance(doc, annot)
+                    .zip(annot.get(b"Rect").ok().and_then(|obj| rect(doc, obj)));
+                let Some((form_id, area)) = placed else {
+                    kept.push(entry);
+                    continue;
+                };
+                drawn.push((form_id, area));
+            }
+
+            if let Object::Reference(id) = entry {
+                removed.insert(id);
+            }
+        }
+
+        let mut content = String::new();
+        for (form_id, area) in drawn {
+            let Some(matrix) = appearance_matrix(doc, form_id, area) else {
+                continue;
+            };
+            if let Ok(Object::Stream(form)) = doc.get_object_mut(form_id) {
+                form.dict.set("Type", "XObject");
+                form.dict.set("Subtype", "Form");
+            }
+
+            let name = format!("PdfCatAnnot{}", form_id.0);
+            add_page_resource(doc, page_id, "XObject", &name, form_id)?;
+            content.push_str(&format!(
+                "q {} cm /{name} Do Q\n",
+                matrix.map(|value| value.to_string()).join(" ")
+            ));
+        }
+        if !content.is_empty() {
+            append_content(doc, page_id, &content)?;
+        }
+
+        set_annotations(doc, page_id, kept);
+        Ok(())
+    }
+
+    /// Remove the annotations a filter matches from a single page.
+    fn filter_page(
+        &self,
+        doc: &mut Document,
+        page_id: ObjectId,
+        filter: &AnnotationFilter,
+        removed: &mut HashSet<ObjectId>,
+    ) {
+        let entries = annotations(doc, page_id);
+        let is_removed = |entry: &Object, removed: &HashSet<ObjectId>| {
+            let Some(annot) = annotation(doc, entry) else {
+                return false;
+            };
+            let subtype = subtype(annot).unwrap_or_default();
+            filter.matches(subtype)
+                || (subtype == b"Popup"
+                    && annot
+                        .get(b"Parent")
+                        .and_then(Object::as_reference)
+                        .is_ok_and(|parent| removed.contains(&parent)))
+        };
+
+        // Pop-ups last, once the annotations they belong to are known
+        let (popups, others): (Vec<Object>, Vec<Object>) =
+            entries.iter().cloned().partition(|entry| {
+                annotation(doc, entry).and_then(subtype) == Some(b"Popup".as_slice())
+            });
+        for entry in others.iter().chain(&popups) {
+            if is_removed(entry, removed)
+                && let Object::Reference(id) = entry
+            {
+                removed.insert(*id);
+            }
+        }
+
+        let kept: Vec<Object> = entries
+            .into_iter()
+            .filter(|entry| match entry {
+                Object::Reference(id) => !removed.contains(id),
+                _ => !is_removed(entry, removed),
+            })
+            .collect();
+
+        // Kept annotations no longer point to removed pop-ups
+        for entry in &kept {
+            if let Object::Reference(id) = entry
+                && let Ok(annot) = doc.get_dictionary_mut(*id)
+                && annot
+                    .get(b"Popup")
+                    .and_then(Object::as_reference)
+                    .is_ok_and(|popup| removed.contains(&popup))
+            {
+                annot.remove(b"Popup");
+            }
+        }
+
+        set_annotations(doc, page_id, kept);
+    }
+}
+
+impl Default for AnnotationEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entries of a page's `/Annots` array.
+fn annotations(doc: &Document, page_id: ObjectId) -> Vec<Object> {
+    doc.get_dictionary(page_id)
+        .and_then(|page| page.get_deref(b"Annots", doc))
+        .and_then(Object::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Replace a page's `/Annots` array, removing it if empty.
+fn set_annotations(doc: &mut Document, page_id: ObjectId, annots: Vec<Object>) {
+    if let Ok(page) = doc.get_dictionary_mut(page_id) {
+        if annots.is_empty() {
+            page.remove(b"Annots");
+        } else {
+            page.set("Annots", annots);
+        }
+    }
+}
+
+/// The dictionary of an `/Annots` entry.
+fn annotation<'a>(doc: &'a Document, entry: &'a Object) -> Option<&'a Dictionary> {
+    doc.dereference(entry).ok()?.1.as_dict().ok()
+}
+
+/// The subtype of an annotation.
+fn subtype(annot: &Dictionary) -> Option<&[u8]> {
+    annot.get(b"Subtype").and_then(Object::as_name).ok()
+}
+
+/// The normal appearance stream of an annotation, in its current state
+/// for annotations with several.
+fn appearance(doc: &Document, annot: &Dictionary) -> Option<ObjectId> {
+    let normal = annot
+        .get_deref(b"AP", doc)
+        .and_then(Object::as_dict)
+        .ok()?
+        .get(b"N")
+        .ok()?;
+
+    let normal = match doc.dereference(normal).ok()? {
+        (Some(id), Object::Stream(_)) => return Some(id),
+        (_, Object::Dictionary(states)) => states,
+        _ => return None,
+    };
+    let state = annot.get(b"AS").and_then(Object::as_name).ok()?;
+    match doc.dereference(normal.get(state).ok()?).ok()? {
+        (Some(id), Object::Stream(_)) => Some(id),
+        _ => None,
+    }
+}
+
+/// The `cm` matrix that draws an appearance stream in an annotation's
+/// rectangle.
+///
+/// The form's bounding box, transformed by its own matrix, is scaled and
+/// moved to fill the rectangle, as the PDF specification describes.
+fn appearance_matrix(doc: &Document, form_id: ObjectId, area: Rect) -> Option<[f32; 6]> {
+    let form = doc.get_object(form_id).and_then(Object::as_stream).ok()?;
+    let bbox = rect(doc, form.dict.get(b"BBox").ok()?)?;
+    let m: Vec<f32> = form
+        .dict
+        .get(b"Matrix")
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|values| values.iter().map(|v| v.as_float().ok()).collect())
+        .filter(|values: &Vec<f32>| values.len() == 6)
+        .unwrap_or_else(|| vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    let corners = [
+        (bbox[0], bbox[1]),
+        (bbox[2], bbox[1]),
+        (bbox[0], bbox[3]),
+        (bbox[2], bbox[3]),
+    ]
+    .map(|(x, y)| (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]));
+    let min_x = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+    let min_y = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|c| c.0)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let max_y = corners
+        .iter()
+        .map(|c| c.1)
+        .fold(f32::NEG_INFINITY, f32::max);
+    if max_x <= min_x || max_y <= min_y {
+        return None;
+    }
+
+    let scale_x = (area[2] - area[0]) / (max_x - min_x);
+    let scale_y = (area[3] - area[1]) / (max_y - min_y);
+    Some([
+        scale_x,
+        0.0,
+        0.0,
+        scale_y,
+        area[0] - scale_x * min_x,
+        area[1] - scale_y * min_y,
+    ])
+}
+
+/// Drop form fields whose widgets were all removed from the AcroForm,
+/// and the AcroForm itself when no fields are left.
+fn prune_fields(doc: &mut Document, removed: &HashSet<ObjectId>) {
+    if removed.is_empty() {
+        return;
+    }
+
+    let acro_form_id = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|obj| obj.as_reference().ok());
+    let Some(fields) = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get_deref(b"AcroForm", doc).ok())
+        .and_then(|obj| obj.as_dict().ok())
+        .and_then(|form| form.get_deref(b"Fields", doc).ok())
+        .and_then(|obj| obj.as_array().ok())
+        .cloned()
+    else {
+        return;
+    };
+
+    let mut kept = Vec::new();
+    for field in fields {
+        if keep_field(doc, &field, removed, 0) {
+            kept.push(field);
+        }
+    }
+
+    if kept.is_empty() {
+        if let Ok(catalog) = doc.catalog_mut() {
+            catalog.remove(b"AcroForm");
+        }
+        return;
+    }
+
+    let form = match acro_form_id {
+        Some(id) => doc.get_dictionary_mut(id).ok(),
+        None => doc
+            .catalog_mut()
+            .ok()
+            .and_then(|catalog| catalog.get_mut(b"AcroForm").ok())
+            .and_then(|obj| obj.as_dict_mut().ok()),
+    };
+    if let Some(form) = form {
+        form.set("Fields", kept);
+        // An XFA form would bring the removed fields back
+        form.remove(b"XFA");
+    }
+}
+
+/// Check whether a form field keeps a widget, dropping its removed kids.
+fn keep_field(
+    doc: &mut Document,
+    field: &Object,
+    removed: &HashSet<ObjectId>,
+    depth: usize,
+) -> bool {
+    let Ok(id) = field.as_reference() else {
+        return true;
+    };
+    if removed.contains(&id) {
+        return false;
+    }
+
+    let Some(kids) = doc
+        .get_dictionary(id)
+        .and_then(|dict| dict.get(b"Kids"))
+        .and_then(Object::as_array)
+        .ok()
+        .cloned()
+    else {
+        return true;
+    };
+    if depth > MAX_DEPTH {
+        return true;
+    }
+
+    let mut kept = Vec::new();
+    for kid in kids {
+        if keep_field(doc, &kid, removed, depth + 1) {
+            kept.push(kid);
+        }
+    }
+    if kept.is_empty() {
+        return false;
+    }
+
+    if let Ok(dict) = doc.get_dictionary_mut(id) {
+        dict.set("Kids", kept);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{Stream, dictionary};
+
+    /// A one-page document with a form field, a stamp, a note with a
+    /// pop-up and a link.
+    fn create_doc() -> (Document, ObjectId) {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_id = doc.new_object_id();
+
+        let on_id = doc.add_object(Stream::new(
+            dictionary! { "BBox" => vec![0.into(), 0.into(), 10.into(), 10.into()] },
+            b"0 0 10 10 re f".to_vec(),
+        ));
+        let widget_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Widget",
+            "FT" => "Btn",
+            "T" => Object::string_literal("agree"),
+            "Rect" => vec![100.into(), 200.into(), 120.into(), 220.into()],
+            "AP" => dictionary! { "N" => dictionary! { "Yes" => on_id } },
+            "AS" => "Yes",
+            "P" => page_id,
+        });
+        let stamp_form_id = doc.add_object(Stream::new(
+            dictionary! { "BBox" => vec![0.into(), 0.into(), 50.into(), 20.into()] },
+            b"(APPROVED) Tj".to_vec(),
+        ));
+        let stamp_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Stamp",
+            "Rect" => vec![300.into(), 700.into(), 400.into(), 740.into()],
+            "AP" => dictionary! { "N" => stamp_form_id },
+        });
+        let note_id = doc.new_object_id();
+        let popup_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Popup",
+            "Parent" => note_id,
+            "Rect" => vec![0.into(), 0.into(), 100.into(), 100.into()],
+        });
+        doc.objects.insert(
+            note_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Text",
+                "Contents" => Object::string_literal("Fix this"),
+                "Rect" => vec![10.into(), 10.into(), 30.into(), 30.into()],
+                "Popup" => popup_id,
+            }),
+        );
+        let link_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![10.into(), 50.into(), 90.into(), 60.into()],
+        });
+
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), b"BT ET".to_vec()));
+        doc.objects.insert(
+            page_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                "Contents" => content_id,
+                "Annots" => vec![
+                    widget_id.into(),
+                    stamp_id.into(),
+                    popup_id.into(),
+                    note_id.into(),
+                    link_id.into(),
+                ],
+            }),
+        );
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "AcroForm" => dictionary! { "Fields" => vec![widget_id.into()] },
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        (doc, page_id)
+    }
+
+    fn subtypes(doc: &Document, page_id: ObjectId) -> Vec<String> {
+        annotations(doc, page_id)
+            .iter()
+            .filter_map(|entry| annotation(doc, entry).and_then(subtype))
+            .map(|subtype| String::from_utf8_lossy(subtype).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_flatten() {
+        let (mut doc, page_id) = create_doc();
+        AnnotationEditor::new().flatten(&mut doc).unwrap();
+
+        assert_eq!(subtypes(&doc, page_id), vec!["Popup", "Text", "Link"]);
+        assert!(!doc.catalog().unwrap().has(b"AcroForm"));
+
+        // The checkbox is scaled from 10 to 20 points, the stamp from 50
+        // to 100 points wide
+        let content = doc.get_page_content(page_id).unwrap();
+        let content = String::from_utf8_lossy(&content);
+        assert!(content.contains("q 2 0 0 2 100 200 cm /PdfCatAnnot"));
+        assert!(content.contains("q 2 0 0 2 300 700 cm /PdfCatAnnot"));
+    }
+
+    #[test]
+    fn test_flatten_hidden_and_missing_appearance() {
+        let (mut doc, page_id) = create_doc();
+        let annots = annotations(&doc, page_id);
+        let widget_id = annots[0].as_reference().unwrap();
+        let stamp_id = annots[1].as_reference().unwrap();
+        doc.get_dictionary_mut(widget_id).unwrap().set("F", HIDDEN);
+        doc.get_dictionary_mut(stamp_id).unwrap().remove(b"AP");
+
+        AnnotationEditor::new().flatten(&mut doc).unwrap();
+
+        // The hidden field is removed without being drawn; the stamp can't
+        // be drawn and is kept
+        assert_eq!(
+            subtypes(&doc, page_id),
+            vec!["Stamp", "Popup", "Text", "Link"]
+        );
+        let content = doc.get_page_content(page_id).unwrap();
+        assert!(!String::from_utf8_lossy(&content).contains("PdfCatAnnot"));
+    }
+
+    #[test]
+    fn test_remove_comments() {
+        let (mut doc, page_id) = create_doc();
+        let filter: AnnotationFilter = "comments".parse().unwrap();
+        AnnotationEditor::new().remove(&mut doc, &filter).unwrap();
+
+        assert_eq!(subtypes(&doc, page_id), vec!["Widget", "Link"]);
+        assert!(doc.catalog().unwrap().has(b"AcroForm"));
+    }
+
+    #[test]
+    fn test_remove_popups_with_parent() {
+        let (mut doc, page_id) = create_doc();
+        let filter: AnnotationFilter = "Text".parse().unwrap();
+        AnnotationEditor::new().remove(&mut doc, &filter).unwrap();
+
+        assert_eq!(subtypes(&doc, page_id), vec!["Widget", "Stamp", "Link"]);
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let (mut doc, page_id) = create_doc();
+        AnnotationEditor::new()
+            .remove(&mut doc, &AnnotationFilter::All)
+            .unwrap();
+
+        assert!(!doc.get_dictionary(page_id).unwrap().has(b"Annots"));
+        assert!(!doc.catalog().unwrap().has(b"AcroForm"));
+    }
+
+    #[test]
+    fn test_prune_fields_keeps_other_kids() {
+        let (mut doc, _) = create_doc();
+        let kept_id = doc.add_object(dictionary! { "Subtype" => "Widget" });
+        let removed_id = doc.add_object(dictionary! { "Subtype" => "Widget" });
+        let field_id = doc.add_object(dictionary! {
+            "Kids" => vec![kept_id.into(), removed_id.into()],
+        });
+        doc.catalog_mut().unwrap().set(
+            "AcroForm",
+            dictionary! { "Fields" => vec![field_id.into()] },
+        );
+
+        prune_fields(&mut doc, &HashSet::from([removed_id]));
+
+        let field = doc.get_dictionary(field_id).unwrap();
+        let kids = field.get(b"Kids").unwrap().as_array().unwrap();
+        assert_eq!(kids, &vec![Object::Reference(kept_id)]);
+    }
+}
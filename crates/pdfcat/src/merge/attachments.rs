@@ -0,0 +1,340 @@
+//! Embedded file attachments.
+//!
+//! Attachments are listed in the catalog's `/Names /EmbeddedFiles` name
+//! tree, which maps each file name to a file specification holding the
+//! file's contents as an embedded file stream. Names in a tree must be
+//! unique, so files with the same name from different inputs are renamed
+//! "name (2).ext", "name (3).ext" and so on.
+
+use lopdf::{Document, Object, Stream, dictionary};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::error::{PdfCatError, Result};
+use crate::merge::metadata::format_pdf_date;
+use crate::merge::trees::name_tree_entries;
+
+/// An entry of the embedded files name tree: the file name as stored in
+/// the PDF and its file specification.
+pub type EmbeddedFile = (Vec<u8>, Object);
+
+/// Manager for embedded file attachments.
+#[derive(Debug, Clone)]
+pub struct AttachmentManager;
+
+impl AttachmentManager {
+    /// Create a new attachment manager.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// List the files embedded in a document, in name tree order.
+    pub fn embedded_files(&self, doc: &Document) -> Vec<EmbeddedFile> {
+        let tree = doc
+            .catalog()
+            .and_then(|catalog| catalog.get_deref(b"Names", doc))
+            .and_then(Object::as_dict)
+            .and_then(|names| names.get_deref(b"EmbeddedFiles", doc))
+            .and_then(Object::as_dict);
+
+        tree.map(|tree| name_tree_entries(doc, tree))
+            .unwrap_or_default()
+    }
+
+    /// Embed a file in a document.
+    ///
+    /// The file gets a MIME type from its extension and, if given, its
+    /// modification date. It is not listed as an attachment until the
+    /// returned entry is passed to [`AttachmentManager::set_embedded_files`].
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to embed the file in
+    /// * `name` - File name shown by viewers
+    /// * `data` - Contents of the file
+    /// * `modified` - When the file was last modified
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::AttachmentManager;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let manager = AttachmentManager::new();
+    /// let mut files = manager.embedded_files(&doc);
+    /// files.push(manager.attach(&mut doc, "figures.csv", b"x,y\n1,2\n".to_vec(), None));
+    /// manager.set_embedded_files(&mut doc, files)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attach(
+        &self,
+        doc: &mut Document,
+        name: &str,
+        data: Vec<u8>,
+        modified: Option<SystemTime>,
+    ) -> EmbeddedFile {
+        let mut params = dictionary! {
+            "Size" => data.len() as i64,
+        };
+        if let Some(modified) = modified {
+            params.set("ModDate", Object::string_literal(format_pdf_date(modified)));
+        }
+
+        let stream_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "EmbeddedFile",
+                "Subtype" => Object::Name(mime_type(name).as_bytes().to_vec()),
+                "Params" => params,
+            },
+            data,
+        ));
+        let spec_id = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => lopdf::text_string(name),
+            "UF" => lopdf::text_string(name),
+            "EF" => dictionary! {
+                "F" => stream_id,
+                "UF" => stream_id,
+            },
+        });
+
+        (text_bytes(name), Object::Reference(spec_id))
+    }
+
+    /// Replace the embedded files name tree of a document.
+    ///
+    /// Duplicate names are made unique and the entries are sorted by name,
+    /// as name trees require. Other name trees in `/Names` are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    pub fn set_embedded_files(&self, doc: &mut Document, files: Vec<EmbeddedFile>) -> Result<()> {
+        let mut taken = HashSet::new();
+        let mut entries: Vec<EmbeddedFile> = files
+            .into_iter()
+            .map(|(name, spec)| (unique_name(&name, &mut taken), spec))
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let names: Vec<Object> = entries
+            .into_iter()
+            .flat_map(|(name, spec)| [Object::String(name, lopdf::StringFormat::Literal), spec])
+            .collect();
+        let tree = dictionary! {
+            "Names" => names,
+        };
+
+        let catalog = doc
+            .catalog()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        if let Ok(names_id) = catalog.get(b"Names").and_then(Object::as_reference)
+            && let Ok(names) = doc.get_dictionary_mut(names_id)
+        {
+            names.set("EmbeddedFiles", tree);
+            return Ok(());
+        }
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        match catalog.get_mut(b"Names").and_then(Object::as_dict_mut) {
+            Ok(names) => names.set("EmbeddedFiles", tree),
+            Err(_) => catalog.set("Names", dictionary! { "EmbeddedFiles" => tree }),
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AttachmentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Make a name unique among the names already taken by adding a number
+/// before its extension, and take it.
+fn unique_name(name: &[u8], taken: &mut HashSet<Vec<u8>>) -> Vec<u8> {
+    if taken.insert(name.to_vec()) {
+        return name.to_vec();
+    }
+
+    let text = lopdf::decode_text_string(&Object::string_literal(name))
+        .unwrap_or_else(|_| String::from_utf8_lossy(name).into_owned());
+    let (stem, extension) = match text.rfind('.') {
+        Some(dot) if dot > 0 => text.split_at(dot),
+        _ => (text.as_str(), ""),
+    };
+
+    (2..)
+        .map(|n| text_bytes(&format!("{stem} ({n}){extension}")))
+        .find(|candidate| taken.insert(candidate.clone()))
+        .unwrap_or_default()
+}
+
+/// Bytes of a PDF text string.
+fn text_bytes(text: &str) -> Vec<u8> {
+    match lopdf::text_string(text) {
+        Object::String(bytes, _) => bytes,
+        _ => Vec::new(),
+    }
+}
+
+/// MIME type of a file from its extension.
+fn mime_type(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "xml" => "application/xml",
+        "json" => "application/json",
+        "zip" => "application/zip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_doc() -> Document {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => Vec::<Object>::new(),
+            "Count" => 0,
+        });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    fn names(files: &[EmbeddedFile]) -> Vec<String> {
+        files
+            .iter()
+            .map(|(name, _)| String::from_utf8_lossy(name).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_attach() {
+        let manager = AttachmentManager::new();
+        let mut doc = create_doc();
+        let modified = SystemTime::UNIX_EPOCH;
+
+        let file = manager.attach(&mut doc, "data.xlsx", b"cells".to_vec(), Some(modified));
+        manager.set_embedded_files(&mut doc, vec![file]).unwrap();
+
+        let files = manager.embedded_files(&doc);
+        assert_eq!(names(&files), vec!["data.xlsx"]);
+
+        let (_, spec) = doc.dereference(&files[0].1).unwrap();
+        let ef = spec
+            .as_dict()
+            .unwrap()
+            .get(b"EF")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        let stream_id = ef.get(b"F").unwrap().as_reference().unwrap();
+        let stream = doc.get_object(stream_id).unwrap().as_stream().unwrap();
+        assert_eq!(stream.content, b"cells");
+        assert_eq!(
+            stream.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+            b"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        );
+        let params = stream.dict.get(b"Params").unwrap().as_dict().unwrap();
+        assert_eq!(params.get(b"Size").unwrap().as_i64().unwrap(), 5);
+        assert!(params.has(b"ModDate"));
+    }
+
+    #[test]
+    fn test_set_embedded_files_renames_duplicates() {
+        let manager = AttachmentManager::new();
+        let mut doc = create_doc();
+
+        let files = vec![
+            manager.attach(&mut doc, "notes.txt", Vec::new(), None),
+            manager.attach(&mut doc, "a.csv", Vec::new(), None),
+            manager.attach(&mut doc, "notes.txt", Vec::new(), None),
+            manager.attach(&mut doc, "notes", Vec::new(), None),
+            manager.attach(&mut doc, "notes", Vec::new(), None),
+        ];
+        manager.set_embedded_files(&mut doc, files).unwrap();
+
+        assert_eq!(
+            names(&manager.embedded_files(&doc)),
+            vec!["a.csv", "notes", "notes (2)", "notes (2).txt", "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn test_set_embedded_files_keeps_other_names() {
+        let manager = AttachmentManager::new();
+        let mut doc = create_doc();
+        let names_id = doc.add_object(dictionary! {
+            "Dests" => dictionary! { "Names" => Vec::<Object>::new() },
+        });
+        doc.catalog_mut().unwrap().set("Names", names_id);
+
+        let file = manager.attach(&mut doc, "a.txt", Vec::new(), None);
+        manager.set_embedded_files(&mut doc, vec![file]).unwrap();
+
+        let names = doc.get_dictionary(names_id).unwrap();
+        assert!(names.has(b"Dests"));
+        assert_eq!(manager.embedded_files(&doc).len(), 1);
+    }
+
+    #[test]
+    fn test_embedded_files_with_kids() {
+        let manager = AttachmentManager::new();
+        let mut doc = create_doc();
+        let first = manager.attach(&mut doc, "a.txt", Vec::new(), None);
+        let second = manager.attach(&mut doc, "b.txt", Vec::new(), None);
+
+        let kid_id = doc.add_object(dictionary! {
+            "Names" => vec![Object::string_literal("b.txt"), second.1],
+        });
+        doc.catalog_mut().unwrap().set(
+            "Names",
+            dictionary! {
+                "EmbeddedFiles" => dictionary! {
+                    "Names" => vec![Object::string_literal("a.txt"), first.1],
+                    "Kids" => vec![Object::Reference(kid_id)],
+                },
+            },
+        );
+
+        assert_eq!(names(&manager.embedded_files(&doc)), vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_mime_type() {
+        assert_eq!(mime_type("report.PDF"), "application/pdf");
+        assert_eq!(mime_type("data.csv"), "text/csv");
+        assert_eq!(mime_type("archive"), "application/octet-stream");
+    }
+}
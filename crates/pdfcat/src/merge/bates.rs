@@ -0,0 +1,224 @@
+//! Bates numbering logs.
+//!
+//! A Bates log maps each run of consecutively numbered pages to the input
+//! file the pages came from and their page numbers in that file. It is
+//! written as CSV, one row per run, or as a JSON array.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::{Bates, PageRange};
+use crate::error::{PdfCatError, Result};
+
+/// A run of output pages with consecutive Bates numbers from one source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatesRange {
+    /// Bates number of the first page.
+    pub first: String,
+
+    /// Bates number of the last page.
+    pub last: String,
+
+    /// Input file the pages came from (None for inserted blank pages).
+    pub file: Option<PathBuf>,
+
+    /// Page numbers in the input file, in output order. Separator pages
+    /// have no page in the input and are not listed.
+    pub pages: Vec<u32>,
+}
+
+/// Where an output page came from: the index of its input and its page
+/// number there, if it has one.
+pub type PageSource = Option<(usize, Option<u32>)>;
+
+/// Group output pages into runs that come from the same input.
+///
+/// # Arguments
+///
+/// * `bates` - Bates numbering of the output
+/// * `sources` - Source of each output page, in output order
+/// * `files` - Path of each input
+pub(crate) fn bates_ranges(
+    bates: &Bates,
+    sources: &[PageSource],
+    files: &[PathBuf],
+) -> Vec<BatesRange> {
+    let mut ranges: Vec<BatesRange> = Vec::new();
+    let mut current_input = None;
+
+    for (index, source) in sources.iter().enumerate() {
+        let number = bates.number(index);
+        let input = source.map(|(input, _)| input);
+
+        match ranges.last_mut() {
+            Some(range) if input == current_input => range.last = number,
+            _ => ranges.push(BatesRange {
+                first: number.clone(),
+                last: number,
+                file: input.and_then(|input| files.get(input).cloned()),
+                pages: Vec::new(),
+            }),
+        }
+
+        if let (Some(range), Some((_, Some(page)))) = (ranges.last_mut(), source) {
+            range.pages.push(*page);
+        }
+        current_input = input;
+    }
+
+    ranges
+}
+
+/// Write a Bates log as CSV.
+///
+/// Columns are the first and last Bates number, the source file and its
+/// pages as a page range such as "1-4,7".
+pub fn log_csv(ranges: &[BatesRange]) -> String {
+    let mut csv = String::from("first,last,file,pages\n");
+    for range in ranges {
+        let file = range
+            .file
+            .as_deref()
+            .map(|file| file.display().to_string())
+            .unwrap_or_default();
+        let pages = if range.pages.is_empty() {
+            String::new()
+        } else {
+            PageRange::from_pages(&range.pages).to_string()
+        };
+
+        let fields = [range.first.as_str(), &range.last, &file, &pages].map(csv_field);
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Write a Bates log as a JSON array.
+///
+/// # Errors
+///
+/// Returns an error if serialization fails.
+pub fn log_json(ranges: &[BatesRange]) -> Result<String> {
+    serde_json::to_string_pretty(ranges)
+        .map_err(|e| PdfCatError::other(format!("Failed to serialize Bates log: {e}")))
+}
+
+/// Write a Bates log to a file, as JSON if its extension is `.json` and as
+/// CSV otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use pdfcat::merge::bates::write_log;
+/// # use pdfcat::merge::Merger;
+/// # use pdfcat::config::Config;
+/// # use std::path::Path;
+/// # async fn example(config: Config) -> Result<(), Box<dyn std::error::Error>> {
+/// let result = Merger::new().merge(&config).await?;
+/// write_log(Path::new("production.csv"), &result.bates_log).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn write_log(path: &Path, ranges: &[BatesRange]) -> Result<()> {
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let contents = if is_json {
+        log_json(ranges)?
+    } else {
+        log_csv(ranges)
+    };
+
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|e| PdfCatError::FailedToWrite {
+            path: path.to_path_buf(),
+            source: e,
+        })
+}
+
+/// Quote a CSV field if it contains a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<PathBuf> {
+        vec![PathBuf::from("a.pdf"), PathBuf::from("b, c.pdf")]
+    }
+
+    #[test]
+    fn test_bates_ranges() {
+        let bates = Bates::new("X");
+        let sources = [
+            Some((0, None)),
+            Some((0, Some(3))),
+            Some((0, Some(4))),
+            None,
+            Some((1, Some(1))),
+            Some((1, Some(2))),
+        ];
+
+        let ranges = bates_ranges(&bates, &sources, &files());
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].first, "X000001");
+        assert_eq!(ranges[0].last, "X000003");
+        assert_eq!(ranges[0].pages, vec![3, 4]);
+        assert_eq!(ranges[1].file, None);
+        assert_eq!(ranges[1].first, ranges[1].last);
+        assert_eq!(ranges[2].file, Some(PathBuf::from("b, c.pdf")));
+        assert_eq!(ranges[2].last, "X000006");
+    }
+
+    #[test]
+    fn test_bates_ranges_collated() {
+        // Interleaved inputs give one run per page
+        let sources = [Some((0, Some(1))), Some((1, Some(1))), Some((0, Some(2)))];
+        let ranges = bates_ranges(&Bates::new(""), &sources, &files());
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[2].pages, vec![2]);
+    }
+
+    #[test]
+    fn test_log_csv() {
+        let sources = [
+            Some((0, Some(1))),
+            Some((0, Some(2))),
+            None,
+            Some((1, Some(5))),
+        ];
+        let ranges = bates_ranges(&Bates::new("ACME"), &sources, &files());
+
+        assert_eq!(
+            log_csv(&ranges),
+            "first,last,file,pages\n\
+             ACME000001,ACME000002,a.pdf,1-2\n\
+             ACME000003,ACME000003,,\n\
+             ACME000004,ACME000004,\"b, c.pdf\",5\n"
+        );
+    }
+
+    #[test]
+    fn test_log_json() {
+        let sources = [Some((0, Some(1)))];
+        let ranges = bates_ranges(&Bates::new("ACME"), &sources, &files());
+
+        let json: serde_json::Value = serde_json::from_str(&log_json(&ranges).unwrap()).unwrap();
+        assert_eq!(json[0]["first"], "ACME000001");
+        assert_eq!(json[0]["file"], "a.pdf");
+        assert_eq!(json[0]["pages"][0], 1);
+    }
+}
@@ -73,14 +73,9 @@ impl BookmarkManager {
                 break;
             }
 
-            let title = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown");
-
             let page_id = pages[page_idx].1;
 
-            outline_items.push((title.to_string(), page_id));
+            outline_items.push((file_title(path), page_id));
         }
 
         if outline_items.is_empty() {
@@ -93,6 +88,38 @@ impl BookmarkManager {
         Ok(())
     }
 
+    /// Add a bookmark for each merged file at a known page.
+    ///
+    /// Unlike [`add_bookmarks_for_files`](Self::add_bookmarks_for_files),
+    /// the destination of each bookmark is given explicitly, so it stays
+    /// correct when files contribute different numbers of pages or their
+    /// pages are interleaved.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to add bookmarks to
+    /// * `items` - Each merged file with the page its bookmark points to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if bookmark creation fails.
+    pub fn add_bookmarks_at_pages(
+        &self,
+        doc: &mut Document,
+        items: &[(&Path, ObjectId)],
+    ) -> Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let outline_items: Vec<(String, ObjectId)> = items
+            .iter()
+            .map(|(path, page_id)| (file_title(path), *page_id))
+            .collect();
+
+        self.create_outline_structure(doc, &outline_items)
+    }
+
     /// Create the PDF outline structure.
     fn create_outline_structure(
         &self,
@@ -190,6 +217,14 @@ impl Default for BookmarkManager {
     }
 }
 
+/// Bookmark title for a merged file: its file name.
+fn file_title(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,6 +312,27 @@ mod tests {
         assert!(manager.has_bookmarks(&doc));
     }
 
+    #[test]
+    fn test_add_bookmarks_at_pages() {
+        let mut doc = create_test_document_with_pages(6);
+        let manager = BookmarkManager::new();
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+
+        let items = [
+            (Path::new("front.pdf"), pages[0]),
+            (Path::new("back.pdf"), pages[1]),
+        ];
+        manager.add_bookmarks_at_pages(&mut doc, &items).unwrap();
+
+        let toc = doc.get_toc().unwrap();
+        let targets: Vec<(&str, usize)> = toc
+            .toc
+            .iter()
+            .map(|entry| (entry.title.as_str(), entry.page))
+            .collect();
+        assert_eq!(targets, vec![("front.pdf", 1), ("back.pdf", 2)]);
+    }
+
     #[test]
     fn test_has_bookmarks() {
         let doc = create_test_document_with_pages(5);
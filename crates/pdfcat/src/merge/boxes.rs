@@ -0,0 +1,648 @@
+//! Page boundary box editing.
+//!
+//! This module changes the `CropBox`, `TrimBox` and `BleedBox` of pages:
+//! - Shrinking a box by margins, given as the page is displayed
+//! - Setting a box to an absolute rectangle
+//! - Shrinking a box to the bounds of the page's visible content
+//!
+//! Boxes are always kept inside the `MediaBox`. A `TrimBox` or `BleedBox`
+//! that a page doesn't have starts out as its `CropBox`, which in turn
+//! defaults to the `MediaBox`.
+
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+use crate::config::{BoxChange, BoxEdit, BoxKind};
+use crate::error::{PdfCatError, Result};
+use crate::merge::layout::{Rect, inherited_attribute, page_rotation, rect, rect_object};
+
+/// A transformation matrix as `[a, b, c, d, e, f]`.
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Maximum nesting of Form XObjects followed when measuring content.
+const MAX_FORM_DEPTH: usize = 8;
+
+/// Editor for page boundary boxes.
+#[derive(Debug, Clone)]
+pub struct BoxEditor;
+
+impl BoxEditor {
+    /// Create a new box editor.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Apply a box edit to the pages of a document.
+    ///
+    /// Page numbers in the edit's page range refer to the pages of `doc`.
+    /// Pages beyond the end of the document are ignored, and pages listed
+    /// more than once are only edited once.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to modify
+    /// * `edit` - Box edit to apply
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edit would leave a page box with no area.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::boxes::BoxEditor;
+    /// # use pdfcat::config::{BoxEdit, BoxKind};
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let editor = BoxEditor::new();
+    /// editor.apply(&mut doc, &BoxEdit::parse(BoxKind::Crop, "auto")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply(&self, doc: &mut Document, edit: &BoxEdit) -> Result<()> {
+        let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let page_count = page_ids.len() as u32;
+
+        let mut numbers = match &edit.pages {
+            Some(range) => range.to_pages(page_count),
+            None => (1..=page_count).collect(),
+        };
+        let mut seen = std::collections::HashSet::new();
+        numbers.retain(|&number| seen.insert(number));
+
+        for number in numbers {
+            let page_id = page_ids[number as usize - 1];
+            let Some(new_box) = self.edited_box(doc, page_id, edit) else {
+                continue;
+            };
+
+            if new_box[2] <= new_box[0] || new_box[3] <= new_box[1] {
+                return Err(PdfCatError::merge_failed(format!(
+                    "{} of page {number} would have no area",
+                    edit.kind.key()
+                )));
+            }
+
+            let page = doc.get_dictionary_mut(page_id).map_err(|err| {
+                PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}"))
+            })?;
+            page.set(edit.kind.key(), rect_object(new_box));
+        }
+
+        Ok(())
+    }
+
+    /// Work out the new box of a page, clamped to its `MediaBox`.
+    ///
+    /// Returns `None` if the box should be left alone, which happens when
+    /// auto-cropping a page whose content bounds can't be determined.
+    fn edited_box(&self, doc: &Document, page_id: ObjectId, edit: &BoxEdit) -> Option<Rect> {
+        let page_rect =
+            |key: &[u8]| inherited_attribute(doc, page_id, key).and_then(|obj| rect(doc, obj));
+
+        let media_box = page_rect(b"MediaBox").unwrap_or([0.0, 0.0, 612.0, 792.0]);
+        let crop_box = page_rect(b"CropBox")
+            .map(|crop| intersect(crop, media_box))
+            .unwrap_or(media_box);
+        let current = match edit.kind {
+            BoxKind::Crop => crop_box,
+            BoxKind::Trim | BoxKind::Bleed => page_rect(edit.kind.key().as_bytes())
+                .map(|current| intersect(current, media_box))
+                .unwrap_or(crop_box),
+        };
+
+        let new_box = match edit.change {
+            BoxChange::Margins(margins) => {
+                let [top, right, bottom, left] =
+                    user_space_margins(margins, page_rotation(doc, page_id));
+                [
+                    current[0] + left,
+                    current[1] + bottom,
+                    current[2] - right,
+                    current[3] - top,
+                ]
+            }
+            BoxChange::Rect(rect) => rect,
+            BoxChange::Auto => intersect(content_bounds(doc, page_id)?, current),
+        };
+
+        Some(intersect(new_box, media_box))
+    }
+}
+
+impl Default for BoxEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turn `[top, right, bottom, left]` margins, as a page with the given
+/// `/Rotate` is displayed, into margins of its unrotated user space.
+fn user_space_margins(margins: [f32; 4], rotation: i64) -> [f32; 4] {
+    // Rotating clockwise by a quarter turn moves each side to the next
+    // one clockwise: top to right, right to bottom, and so on.
+    let turns = (rotation / 90) as usize;
+    std::array::from_fn(|side| margins[(side + turns) % 4])
+}
+
+/// Intersection of two rectangles. The result has no area if they don't
+/// overlap.
+fn intersect(a: Rect, b: Rect) -> Rect {
+    [
+        a[0].max(b[0]),
+        a[1].max(b[1]),
+        a[2].min(b[2]),
+        a[3].min(b[3]),
+    ]
+}
+
+/// Bounds of the visible content of a page in its user space.
+///
+/// Painted paths, images, Form XObjects and text are measured. Text
+/// extents are estimated from the font size and the number of characters
+/// shown, and invisible text (such as an OCR layer) is ignored. Clipping
+/// paths are not taken into account.
+///
+/// Returns `None` if the page paints nothing, or paints something without
+/// bounds such as a shading fill.
+pub(crate) fn content_bounds(doc: &Document, page_id: ObjectId) -> Option<Rect> {
+    let content = doc.get_page_content(page_id).ok()?;
+    let resources =
+        inherited_attribute(doc, page_id, b"Resources").and_then(|obj| obj.as_dict().ok());
+
+    let mut scanner = ContentScanner {
+        doc,
+        bounds: None,
+        unbounded: false,
+    };
+    scanner.scan(&content, resources, IDENTITY, 0);
+
+    if scanner.unbounded {
+        None
+    } else {
+        scanner.bounds
+    }
+}
+
+/// Graphics and text state tracked while measuring content.
+#[derive(Debug, Clone, Copy)]
+struct ScanState {
+    ctm: Matrix,
+    font_size: f32,
+    horizontal_scaling: f32,
+    leading: f32,
+    invisible_text: bool,
+}
+
+/// Walks content streams and accumulates the bounds of what they paint.
+struct ContentScanner<'a> {
+    doc: &'a Document,
+    bounds: Option<Rect>,
+    unbounded: bool,
+}
+
+impl ContentScanner<'_> {
+    fn scan(&mut self, content: &[u8], resources: Option<&Dictionary>, ctm: Matrix, depth: usize) {
+        let Ok(content) = Content::decode(content) else {
+            self.unbounded = true;
+            return;
+        };
+
+        let mut state = ScanState {
+            ctm,
+            font_size: 0.0,
+            horizontal_scaling: 1.0,
+            leading: 0.0,
+            invisible_text: false,
+        };
+        let mut stack = Vec::new();
+        let mut path: Vec<(f32, f32)> = Vec::new();
+        let mut text_matrix = IDENTITY;
+        let mut line_matrix = IDENTITY;
+
+        for operation in &content.operations {
+            let operands: Vec<f32> = operation
+                .operands
+                .iter()
+                .filter_map(|obj| obj.as_float().ok())
+                .collect();
+
+            match operation.operator.as_str() {
+                "q" => stack.push(state),
+                "Q" => state = stack.pop().unwrap_or(state),
+                "cm" => {
+                    if let Ok(matrix) = <Matrix>::try_from(operands.as_slice()) {
+                        state.ctm = multiply(matrix, state.ctm);
+                    }
+                }
+
+                "m" | "l" | "c" | "v" | "y" => {
+                    for point in operands.chunks_exact(2) {
+                        path.push(apply(state.ctm, point[0], point[1]));
+                    }
+                }
+                "re" => {
+                    if let &[x, y, width, height] = operands.as_slice() {
+                        for (px, py) in [
+                            (x, y),
+                            (x + width, y),
+                            (x, y + height),
+                            (x + width, y + height),
+                        ] {
+                            path.push(apply(state.ctm, px, py));
+                        }
+                    }
+                }
+                "S" | "s" | "f" | "F" | "f*" | "B" | "B*" | "b" | "b*" => {
+                    for (x, y) in path.drain(..) {
+                        self.include_point(x, y);
+                    }
+                }
+                "n" => path.clear(),
+                "sh" => self.unbounded = true,
+
+                "Do" => {
+                    if let Some(name) = operation
+                        .operands
+                        .first()
+                        .and_then(|obj| obj.as_name().ok())
+                    {
+                        self.scan_xobject(name, resources, state.ctm, depth);
+                    }
+                }
+                "BI" => self.include_rect(state.ctm, [0.0, 0.0, 1.0, 1.0]),
+
+                "BT" => {
+                    text_matrix = IDENTITY;
+                    line_matrix = IDENTITY;
+                }
+                "Tf" => {
+                    if let Some(&size) = operands.last() {
+                        state.font_size = size;
+                    }
+                }
+                "Tz" => {
+                    if let Some(&scaling) = operands.first() {
+                        state.horizontal_scaling = scaling / 100.0;
+                    }
+                }
+                "TL" => {
+                    if let Some(&leading) = operands.first() {
+                        state.leading = leading;
+                    }
+                }
+                "Tr" => {
+                    if let Some(&mode) = operands.first() {
+                        state.invisible_text = mode == 3.0;
+                    }
+                }
+                "Td" | "TD" => {
+                    if let &[tx, ty] = operands.as_slice() {
+                        if operation.operator == "TD" {
+                            state.leading = -ty;
+                        }
+                        line_matrix = multiply([1.0, 0.0, 0.0, 1.0, tx, ty], line_matrix);
+                        text_matrix = line_matrix;
+                    }
+                }
+                "Tm" => {
+                    if let Ok(matrix) = <Matrix>::try_from(operands.as_slice()) {
+                        line_matrix = matrix;
+                        text_matrix = matrix;
+                    }
+                }
+                "T*" | "'" | "\"" => {
+                    line_matrix = multiply([1.0, 0.0, 0.0, 1.0, 0.0, -state.leading], line_matrix);
+                    text_matrix = line_matrix;
+                    if operation.operator != "T*" {
+                        let strings = operation.operands.last().into_iter();
+                        self.show_text(strings, &state, &mut text_matrix);
+                    }
+                }
+                "Tj" => {
+                    self.show_text(operation.operands.iter(), &state, &mut text_matrix);
+                }
+                "TJ" => {
+                    if let Some(Ok(items)) = operation.operands.first().map(Object::as_array) {
+                        self.show_text(items.iter(), &state, &mut text_matrix);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Measure a named XObject drawn with the given CTM.
+    fn scan_xobject(
+        &mut self,
+        name: &[u8],
+        resources: Option<&Dictionary>,
+        ctm: Matrix,
+        depth: usize,
+    ) {
+        let doc = self.doc;
+        let Some(stream) = resources
+            .and_then(|resources| resources.get(b"XObject").ok())
+            .and_then(|obj| doc.dereference(obj).ok())
+            .and_then(|(_, obj)| obj.as_dict().ok())
+            .and_then(|xobjects| xobjects.get(name).ok())
+            .and_then(|obj| doc.dereference(obj).ok())
+            .and_then(|(_, obj)| obj.as_stream().ok())
+        else {
+            return;
+        };
+
+        match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+            Ok(b"Image") => self.include_rect(ctm, [0.0, 0.0, 1.0, 1.0]),
+            Ok(b"Form") if depth < MAX_FORM_DEPTH => {
+                let matrix = stream
+                    .dict
+                    .get(b"Matrix")
+                    .and_then(Object::as_array)
+                    .ok()
+                    .and_then(|values| {
+                        let values: Vec<f32> = values
+                            .iter()
+                            .filter_map(|obj| obj.as_float().ok())
+                            .collect();
+                        <Matrix>::try_from(values.as_slice()).ok()
+                    })
+                    .unwrap_or(IDENTITY);
+                let form_resources = stream
+                    .dict
+                    .get(b"Resources")
+                    .ok()
+                    .and_then(|obj| doc.dereference(obj).ok())
+                    .and_then(|(_, obj)| obj.as_dict().ok())
+                    .or(resources);
+
+                match stream.get_plain_content() {
+                    Ok(content) => {
+                        self.scan(&content, form_resources, multiply(matrix, ctm), depth + 1)
+                    }
+                    Err(_) => self.unbounded = true,
+                }
+            }
+            Ok(b"Form") => self.unbounded = true,
+            _ => {}
+        }
+    }
+
+    /// Measure text shown with `Tj`, `TJ`, `'` or `"`, and advance the
+    /// text matrix past it.
+    fn show_text<'o>(
+        &mut self,
+        items: impl Iterator<Item = &'o Object>,
+        state: &ScanState,
+        text_matrix: &mut Matrix,
+    ) {
+        let size = state.font_size;
+        let mut width = 0.0;
+        for item in items {
+            match item {
+                Object::String(bytes, _) => width += bytes.len() as f32 * 0.6 * size,
+                other => {
+                    if let Ok(adjustment) = other.as_float() {
+                        width -= adjustment / 1000.0 * size;
+                    }
+                }
+            }
+        }
+        width *= state.horizontal_scaling;
+
+        if !state.invisible_text && width != 0.0 {
+            let matrix = multiply(*text_matrix, state.ctm);
+            self.include_rect(matrix, [0.0, -0.25 * size, width, size]);
+        }
+        *text_matrix = multiply([1.0, 0.0, 0.0, 1.0, width, 0.0], *text_matrix);
+    }
+
+    fn include_rect(&mut self, matrix: Matrix, rect: Rect) {
+        for (x, y) in [
+            (rect[0], rect[1]),
+            (rect[2], rect[1]),
+            (rect[0], rect[3]),
+            (rect[2], rect[3]),
+        ] {
+            let (x, y) = apply(matrix, x, y);
+            self.include_point(x, y);
+        }
+    }
+
+    fn include_point(&mut self, x: f32, y: f32) {
+        let bounds = self.bounds.get_or_insert([x, y, x, y]);
+        *bounds = [
+            bounds[0].min(x),
+            bounds[1].min(y),
+            bounds[2].max(x),
+            bounds[3].max(y),
+        ];
+    }
+}
+
+/// The matrix that applies `first`, then `second`.
+fn multiply(first: Matrix, second: Matrix) -> Matrix {
+    [
+        first[0] * second[0] + first[1] * second[2],
+        first[0] * second[1] + first[1] * second[3],
+        first[2] * second[0] + first[3] * second[2],
+        first[2] * second[1] + first[3] * second[3],
+        first[4] * second[0] + first[5] * second[2] + second[4],
+        first[4] * second[1] + first[5] * second[3] + second[5],
+    ]
+}
+
+/// Apply a matrix to a point.
+fn apply(matrix: Matrix, x: f32, y: f32) -> (f32, f32) {
+    (
+        matrix[0] * x + matrix[2] * y + matrix[4],
+        matrix[1] * x + matrix[3] * y + matrix[5],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PageRange;
+    use crate::merge::layout::page_box;
+    use lopdf::{Stream, dictionary};
+
+    fn create_doc(pages: &[(i64, &str)]) -> Document {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut kids = Vec::new();
+        for &(rotate, content) in pages {
+            let content_id =
+                doc.add_object(Stream::new(Dictionary::new(), content.as_bytes().to_vec()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 600.into(), 800.into()],
+                "Rotate" => rotate,
+                "Contents" => content_id,
+            });
+            kids.push(Object::Reference(page_id));
+        }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        doc
+    }
+
+    fn page_rect(doc: &Document, number: usize, key: &[u8]) -> Option<Rect> {
+        let page_id = doc.get_pages()[&(number as u32)];
+        let page = doc.get_dictionary(page_id).unwrap();
+        page.get(key).ok().and_then(|obj| rect(doc, obj))
+    }
+
+    fn edit(kind: BoxKind, change: BoxChange, pages: Option<&str>) -> BoxEdit {
+        BoxEdit {
+            kind,
+            change,
+            pages: pages.map(|pages| PageRange::parse(pages).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_margins_follow_rotation() {
+        let mut doc = create_doc(&[(0, ""), (90, "")]);
+        BoxEditor::new()
+            .apply(
+                &mut doc,
+                &edit(
+                    BoxKind::Crop,
+                    BoxChange::Margins([10.0, 20.0, 30.0, 40.0]),
+                    None,
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            page_rect(&doc, 1, b"CropBox"),
+            Some([40.0, 30.0, 580.0, 790.0])
+        );
+        // Displayed top is the left of the unrotated page, and so on
+        assert_eq!(
+            page_rect(&doc, 2, b"CropBox"),
+            Some([10.0, 40.0, 570.0, 780.0])
+        );
+    }
+
+    #[test]
+    fn test_trim_defaults_to_crop_box() {
+        let mut doc = create_doc(&[(0, ""), (0, "")]);
+        let editor = BoxEditor::new();
+        editor
+            .apply(
+                &mut doc,
+                &edit(BoxKind::Crop, BoxChange::Margins([10.0; 4]), None),
+            )
+            .unwrap();
+        editor
+            .apply(
+                &mut doc,
+                &edit(BoxKind::Trim, BoxChange::Margins([5.0; 4]), Some("2,2")),
+            )
+            .unwrap();
+
+        assert_eq!(page_rect(&doc, 1, b"TrimBox"), None);
+        assert_eq!(
+            page_rect(&doc, 2, b"TrimBox"),
+            Some([15.0, 15.0, 585.0, 785.0])
+        );
+    }
+
+    #[test]
+    fn test_rect_is_clamped_to_media_box() {
+        let mut doc = create_doc(&[(0, "")]);
+        BoxEditor::new()
+            .apply(
+                &mut doc,
+                &edit(
+                    BoxKind::Bleed,
+                    BoxChange::Rect([-10.0, 100.0, 700.0, 200.0]),
+                    None,
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(
+            page_rect(&doc, 1, b"BleedBox"),
+            Some([0.0, 100.0, 600.0, 200.0])
+        );
+    }
+
+    #[test]
+    fn test_margins_leaving_no_area() {
+        let mut doc = create_doc(&[(0, "")]);
+        let result = BoxEditor::new().apply(
+            &mut doc,
+            &edit(BoxKind::Crop, BoxChange::Margins([400.0; 4]), None),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_auto_crop() {
+        let mut doc = create_doc(&[
+            (
+                0,
+                "q 2 0 0 2 100 100 cm 0 0 m 50 25 l S Q 0 0 800 800 re W n",
+            ),
+            (0, ""),
+            (0, "BT /F1 10 Tf 3 Tr 10 10 Td (Hidden) Tj ET"),
+        ]);
+        BoxEditor::new()
+            .apply(&mut doc, &edit(BoxKind::Crop, BoxChange::Auto, None))
+            .unwrap();
+
+        assert_eq!(
+            page_rect(&doc, 1, b"CropBox"),
+            Some([100.0, 100.0, 200.0, 150.0])
+        );
+        // Blank pages and invisible text are left alone
+        assert_eq!(page_rect(&doc, 2, b"CropBox"), None);
+        assert_eq!(page_rect(&doc, 3, b"CropBox"), None);
+        assert_eq!(
+            page_box(&doc, doc.get_pages()[&3]),
+            [0.0, 0.0, 600.0, 800.0]
+        );
+    }
+
+    #[test]
+    fn test_content_bounds_text() {
+        let doc = create_doc(&[(0, "BT /F1 10 Tf 1 0 0 1 50 60 Tm (ab) Tj ET")]);
+        let page_id = doc.get_pages()[&1];
+
+        // Two characters, estimated at 0.6 em each
+        assert_eq!(
+            content_bounds(&doc, page_id),
+            Some([50.0, 57.5, 62.0, 70.0])
+        );
+    }
+
+    #[test]
+    fn test_user_space_margins() {
+        let margins = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(user_space_margins(margins, 0), margins);
+        assert_eq!(user_space_margins(margins, 90), [2.0, 3.0, 4.0, 1.0]);
+        assert_eq!(user_space_margins(margins, 270), [4.0, 1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,148 @@
+//! Text set in the standard Helvetica font.
+//!
+//! Helvetica is one of the standard Type1 fonts every PDF viewer provides,
+//! so pages that use it need no embedded font program. This module holds
+//! the font dictionary, the character widths needed to measure and center
+//! text, and WinAnsiEncoding string encoding.
+
+use lopdf::{Dictionary, dictionary};
+
+/// Font dictionary for Helvetica with WinAnsiEncoding.
+pub(crate) fn helvetica() -> Dictionary {
+    dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    }
+}
+
+/// Width of WinAnsiEncoding text set in Helvetica at `size` points.
+pub(crate) fn text_width(encoded: &[u8], size: f32) -> f32 {
+    let units: u32 = encoded.iter().map(|&byte| helvetica_width(byte)).sum();
+    units as f32 * size / 1000.0
+}
+
+/// Write WinAnsiEncoding text as a PDF string literal, parentheses
+/// included.
+pub(crate) fn string_literal(encoded: &[u8]) -> String {
+    let mut literal = String::with_capacity(encoded.len() + 2);
+    literal.push('(');
+    for &byte in encoded {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            0x20..=0x7e => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    literal.push(')');
+    literal
+}
+
+/// Characters WinAnsiEncoding places at 0x80 to 0x9F, where Latin-1 has
+/// control characters. The five unused codes are `None`.
+#[rustfmt::skip]
+const WIN_ANSI_EXTRA: [Option<char>; 32] = [
+    // 0x80 to 0x8F
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    // 0x90 to 0x9F
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ'),
+];
+
+/// Encode text in WinAnsiEncoding, replacing characters it lacks with "?".
+///
+/// Printable ASCII and Latin-1 characters are kept, as are the typographic
+/// quotes, dashes and other characters WinAnsiEncoding adds at 0x80 to
+/// 0x9F; control characters become spaces.
+pub(crate) fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7e | 0xa0..=0xff => c as u8,
+            0..=0x1f | 0x7f => b' ',
+            _ => WIN_ANSI_EXTRA
+                .iter()
+                .position(|&extra| extra == Some(c))
+                .map_or(b'?', |index| 0x80 + index as u8),
+        })
+        .collect()
+}
+
+/// Width of a WinAnsiEncoding character in Helvetica, in thousandths of
+/// the font size.
+fn helvetica_width(byte: u8) -> u32 {
+    #[rustfmt::skip]
+    const ASCII: [u16; 95] = [
+        // ' ' to '/'
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+        // '0' to '?'
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+        // '@' to 'O'
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+        // 'P' to '_'
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+        // '`' to 'o'
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+        // 'p' to '~'
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    ];
+
+    #[rustfmt::skip]
+    const UPPER: [u16; 128] = [
+        // 0x80 to 0x8F: '€' to unused
+        556, 556, 222, 556, 333, 1000, 556, 556, 333, 1000, 667, 333, 1000, 556, 611, 556,
+        // 0x90 to 0x9F: unused to 'Ÿ'
+        556, 222, 222, 333, 333, 350, 556, 1000, 333, 1000, 500, 333, 944, 556, 500, 667,
+        // 0xA0 to 0xAF: no-break space to macron
+        278, 333, 556, 556, 556, 556, 260, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+        // 0xB0 to 0xBF: '°' to '¿'
+        400, 584, 333, 333, 333, 556, 537, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+        // 0xC0 to 0xCF: 'À' to 'Ï'
+        667, 667, 667, 667, 667, 667, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+        // 0xD0 to 0xDF: 'Ð' to 'ß'
+        722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+        // 0xE0 to 0xEF: 'à' to 'ï'
+        556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278, 278,
+        // 0xF0 to 0xFF: 'ð' to 'ÿ'
+        556, 556, 556, 556, 556, 556, 556, 584, 611, 556, 556, 556, 556, 500, 556, 500,
+    ];
+
+    match byte {
+        0x20..=0x7e => u32::from(ASCII[usize::from(byte - 0x20)]),
+        0x80..=0xff => u32::from(UPPER[usize::from(byte - 0x80)]),
+        _ => 556,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_win_ansi() {
+        assert_eq!(encode_win_ansi("Café\tü—"), b"Caf\xe9 \xfc\x97".to_vec());
+        assert_eq!(
+            encode_win_ansi("Client’s “copy” – 5 €™ ✓"),
+            b"Client\x92s \x93copy\x94 \x96 5 \x80\x99 ?".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(string_literal(b"(a\\b)\xe9"), "(\\(a\\\\b\\)\\351)");
+    }
+
+    #[test]
+    fn test_text_width() {
+        // "Wi" is 944 + 222 units wide
+        assert!((text_width(b"Wi", 10.0) - 11.66).abs() < 0.001);
+
+        // An em dash is as wide as the font size, a bullet about a third
+        assert!((text_width(b"\x97", 10.0) - 10.0).abs() < 0.001);
+        assert!((text_width(b"\x95", 10.0) - 3.5).abs() < 0.001);
+    }
+}
@@ -0,0 +1,508 @@
+//! Page labels of the merged document.
+//!
+//! Page labels are the page numbers a viewer shows, such as "iv" or "A-1",
+//! in place of a page's position in the document. They are stored in the
+//! catalog as a number tree that maps the index of the first page of each
+//! range to the range's numbering style, prefix and first number.
+//!
+//! Labels are worked out page by page: each input's number tree is
+//! expanded to one label per page, the labels follow the pages into the
+//! output, and the result is compacted back into ranges.
+
+use lopdf::{Dictionary, Document, Object, StringFormat, dictionary};
+use std::path::Path;
+
+use crate::config::PageLabels;
+use crate::error::{PdfCatError, Result};
+use crate::merge::bates::PageSource;
+use crate::merge::trees::number_tree_entries;
+
+/// Label of one page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageLabel {
+    /// Numbering style (D, R, r, A or a), or None for a prefix alone.
+    style: Option<Vec<u8>>,
+
+    /// Text shown before the number, as stored in the PDF.
+    prefix: Vec<u8>,
+
+    /// Number of the page.
+    number: i64,
+}
+
+impl PageLabel {
+    /// A plain decimal page number.
+    fn decimal(number: i64) -> Self {
+        Self {
+            style: Some(b"D".to_vec()),
+            prefix: Vec::new(),
+            number,
+        }
+    }
+
+    /// Label of the page after this one in the same range.
+    fn next(&self) -> Self {
+        Self {
+            number: self.number + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Read a label range from its dictionary.
+    fn from_dict(dict: &Dictionary) -> Self {
+        Self {
+            style: dict
+                .get(b"S")
+                .and_then(Object::as_name)
+                .ok()
+                .map(<[u8]>::to_vec),
+            prefix: dict
+                .get(b"P")
+                .and_then(Object::as_str)
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default(),
+            number: dict.get(b"St").and_then(Object::as_i64).unwrap_or(1),
+        }
+    }
+
+    /// Dictionary of a label range starting with this label.
+    fn to_dict(&self) -> Dictionary {
+        let mut dict = Dictionary::new();
+        if let Some(ref style) = self.style {
+            dict.set("S", Object::Name(style.clone()));
+        }
+        if !self.prefix.is_empty() {
+            dict.set(
+                "P",
+                Object::String(self.prefix.clone(), StringFormat::Literal),
+            );
+        }
+        if self.number != 1 {
+            dict.set("St", self.number);
+        }
+        dict
+    }
+}
+
+/// Labeler that writes the page labels of a merged document.
+#[derive(Debug, Clone)]
+pub struct PageLabeler;
+
+impl PageLabeler {
+    /// Create a new page labeler.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Set the page labels of a merged document.
+    ///
+    /// With [`PageLabels::Keep`], pages keep the labels they had in their
+    /// input. Pages of inputs without labels and separator pages are
+    /// labeled with their position in the output, and inserted blank pages
+    /// continue the label of the page before them. If no input has labels,
+    /// the document gets none.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Merged document
+    /// * `mode` - How labels are made
+    /// * `sources` - Source of each output page, in output order
+    /// * `inputs` - Path and original document of each input
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    pub fn apply(
+        &self,
+        doc: &mut Document,
+        mode: &PageLabels,
+        sources: &[PageSource],
+        inputs: &[(&Path, &Document)],
+    ) -> Result<()> {
+        let labels = match mode {
+            PageLabels::Keep => keep_labels(sources, inputs),
+            PageLabels::Generate(template) => Some(generate_labels(template, sources, inputs)),
+            PageLabels::Remove => None,
+        };
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        match labels {
+            Some(labels) => catalog.set("PageLabels", number_tree(&labels)),
+            None => {
+                catalog.remove(b"PageLabels");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PageLabeler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Labels of the output pages taken from the inputs, or None if no input
+/// has labels.
+fn keep_labels(sources: &[PageSource], inputs: &[(&Path, &Document)]) -> Option<Vec<PageLabel>> {
+    let input_labels: Vec<Option<Vec<PageLabel>>> =
+        inputs.iter().map(|(_, doc)| read_labels(doc)).collect();
+    if input_labels.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let mut labels: Vec<PageLabel> = Vec::with_capacity(sources.len());
+    for (index, source) in sources.iter().enumerate() {
+        let label = match *source {
+            Some((input, Some(page))) => input_labels
+                .get(input)
+                .and_then(|labels| labels.as_ref()?.get(page as usize - 1))
+                .cloned(),
+            Some((_, None)) => None,
+            None => labels.last().map(PageLabel::next),
+        };
+        labels.push(label.unwrap_or_else(|| PageLabel::decimal(index as i64 + 1)));
+    }
+
+    Some(labels)
+}
+
+/// Labels of the output pages made from a template such as "{stem}-{n}".
+///
+/// Each input's pages, separator included, are numbered from 1. Inserted
+/// blank pages continue the label of the page before them.
+fn generate_labels(
+    template: &str,
+    sources: &[PageSource],
+    inputs: &[(&Path, &Document)],
+) -> Vec<PageLabel> {
+    let prefixes: Vec<Vec<u8>> = inputs
+        .iter()
+        .map(|(path, _)| {
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default();
+            let prefix = template
+                .strip_suffix("{n}")
+                .unwrap_or(template)
+                .replace("{stem}", &stem);
+            match lopdf::text_string(&prefix) {
+                Object::String(bytes, _) => bytes,
+                _ => Vec::new(),
+            }
+        })
+        .collect();
+
+    let mut counts = vec![0; inputs.len()];
+    let mut labels: Vec<PageLabel> = Vec::with_capacity(sources.len());
+    for (index, source) in sources.iter().enumerate() {
+        let label = match *source {
+            Some((input, _)) if input < inputs.len() => {
+                counts[input] += 1;
+                PageLabel {
+                    prefix: prefixes[input].clone(),
+                    ..PageLabel::decimal(counts[input])
+                }
+            }
+            _ => labels
+                .last()
+                .map_or_else(|| PageLabel::decimal(index as i64 + 1), PageLabel::next),
+        };
+        labels.push(label);
+    }
+
+    labels
+}
+
+/// Labels of each page of a document, or None if it has no labels.
+///
+/// Pages before the first label range are labeled with their page number.
+fn read_labels(doc: &Document) -> Option<Vec<PageLabel>> {
+    let tree = doc
+        .catalog()
+        .ok()?
+        .get_deref(b"PageLabels", doc)
+        .ok()?
+        .as_dict()
+        .ok()?;
+
+    let mut ranges: Vec<(i64, PageLabel)> = number_tree_entries(doc, tree)
+        .into_iter()
+        .filter_map(|(start, range)| {
+            let (_, range) = doc.dereference(&range).ok()?;
+            Some((start, PageLabel::from_dict(range.as_dict().ok()?)))
+        })
+        .collect();
+    if ranges.is_empty() {
+        return None;
+    }
+    ranges.sort_by_key(|&(start, _)| start);
+
+    let page_count = doc.get_pages().len() as i64;
+    let labels = (0..page_count)
+        .map(
+            |index| match ranges.iter().rev().find(|&&(start, _)| start <= index) {
+                Some((start, label)) => PageLabel {
+                    number: label.number + (index - start),
+                    ..label.clone()
+                },
+                None => PageLabel::decimal(index + 1),
+            },
+        )
+        .collect();
+
+    Some(labels)
+}
+
+/// Number tree of page labels, with one range per run of pages that
+/// share a style and prefix and are numbered consecutively.
+fn number_tree(labels: &[PageLabel]) -> Dictionary {
+    let mut nums = Vec::new();
+    let mut previous: Option<&PageLabel> = None;
+
+    for (index, label) in labels.iter().enumerate() {
+        if previous.is_none_or(|previous| previous.next() != *label) {
+            nums.push(Object::Integer(index as i64));
+            nums.push(Object::Dictionary(label.to_dict()));
+        }
+        previous = Some(label);
+    }
+
+    dictionary! {
+        "Nums" => nums,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::Stream;
+
+    fn create_doc(page_count: usize, labels: Option<Object>) -> Document {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let kids: Vec<Object> = (0..page_count)
+            .map(|_| {
+                let content_id = doc.add_object(Stream::new(Dictionary::new(), Vec::new()));
+                Object::Reference(doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                    "Contents" => content_id,
+                }))
+            })
+            .collect();
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => page_count as i64,
+            }),
+        );
+        let mut catalog = dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        };
+        if let Some(labels) = labels {
+            catalog.set("PageLabels", labels);
+        }
+        let catalog_id = doc.add_object(catalog);
+        doc.trailer.set("Root", catalog_id);
+
+        doc
+    }
+
+    /// A book with roman front matter and arabic body pages.
+    fn create_book() -> Document {
+        create_doc(
+            5,
+            Some(Object::Dictionary(dictionary! {
+                "Nums" => vec![
+                    0.into(),
+                    Object::Dictionary(dictionary! { "S" => "r" }),
+                    2.into(),
+                    Object::Dictionary(dictionary! { "S" => "D" }),
+                ],
+            })),
+        )
+    }
+
+    fn label(style: &str, prefix: &str, number: i64) -> PageLabel {
+        PageLabel {
+            style: Some(style.as_bytes().to_vec()),
+            prefix: prefix.as_bytes().to_vec(),
+            number,
+        }
+    }
+
+    #[test]
+    fn test_read_labels() {
+        let labels = read_labels(&create_book()).unwrap();
+        assert_eq!(labels[1], label("r", "", 2));
+        assert_eq!(labels[2], label("D", "", 1));
+        assert_eq!(labels[4], label("D", "", 3));
+
+        assert!(read_labels(&create_doc(2, None)).is_none());
+    }
+
+    #[test]
+    fn test_read_labels_kids() {
+        let mut doc = create_doc(3, None);
+        let kid_id = doc.add_object(dictionary! {
+            "Limits" => vec![1.into(), 1.into()],
+            "Nums" => vec![
+                1.into(),
+                Object::Dictionary(dictionary! { "S" => "A", "P" => Object::string_literal("App-"), "St" => 3 }),
+            ],
+        });
+        doc.catalog_mut().unwrap().set(
+            "PageLabels",
+            dictionary! { "Kids" => vec![Object::Reference(kid_id)] },
+        );
+
+        let labels = read_labels(&doc).unwrap();
+        assert_eq!(labels[0], PageLabel::decimal(1));
+        assert_eq!(labels[1], label("A", "App-", 3));
+        assert_eq!(labels[2], label("A", "App-", 4));
+    }
+
+    #[test]
+    fn test_keep_labels_with_offsets() {
+        let book = create_book();
+        let plain = create_doc(2, None);
+        let inputs = [
+            (Path::new("plain.pdf"), &plain),
+            (Path::new("book.pdf"), &book),
+        ];
+        // Both pages of plain.pdf, a blank page, then pages 2-4 of book.pdf
+        let sources = [
+            Some((0, Some(1))),
+            Some((0, Some(2))),
+            None,
+            Some((1, Some(2))),
+            Some((1, Some(3))),
+            Some((1, Some(4))),
+        ];
+
+        let mut doc = create_doc(6, None);
+        PageLabeler::new()
+            .apply(&mut doc, &PageLabels::Keep, &sources, &inputs)
+            .unwrap();
+
+        let labels = read_labels(&doc).unwrap();
+        assert_eq!(labels[2], PageLabel::decimal(3));
+        assert_eq!(labels[3], label("r", "", 2));
+        assert_eq!(labels[4], label("D", "", 1));
+        assert_eq!(labels[5], label("D", "", 2));
+
+        // Decimal pages 1-3 form one range
+        let nums = doc
+            .catalog()
+            .unwrap()
+            .get_deref(b"PageLabels", &doc)
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Nums")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(nums.len(), 6);
+        assert_eq!(nums[2].as_i64().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_keep_labels_without_labeled_inputs() {
+        let plain = create_doc(1, None);
+        let mut doc = create_book();
+        PageLabeler::new()
+            .apply(
+                &mut doc,
+                &PageLabels::Keep,
+                &[Some((0, Some(1)))],
+                &[(Path::new("plain.pdf"), &plain)],
+            )
+            .unwrap();
+
+        assert!(doc.catalog().unwrap().get(b"PageLabels").is_err());
+    }
+
+    #[test]
+    fn test_generate_labels() {
+        let a = create_doc(2, None);
+        let b = create_book();
+        let inputs = [(Path::new("dir/a.pdf"), &a), (Path::new("b.pdf"), &b)];
+        // Collated pages with a blank page at the end
+        let sources = [
+            Some((0, Some(1))),
+            Some((1, Some(1))),
+            Some((0, Some(2))),
+            Some((1, Some(2))),
+            None,
+        ];
+
+        let mut doc = create_doc(5, None);
+        let mode = PageLabels::Generate("{stem}-{n}".to_string());
+        PageLabeler::new()
+            .apply(&mut doc, &mode, &sources, &inputs)
+            .unwrap();
+
+        let labels = read_labels(&doc).unwrap();
+        assert_eq!(labels[0], label("D", "a-", 1));
+        assert_eq!(labels[2], label("D", "a-", 2));
+        assert_eq!(labels[3], label("D", "b-", 2));
+        assert_eq!(labels[4], label("D", "b-", 3));
+    }
+
+    #[test]
+    fn test_remove_labels() {
+        let mut doc = create_book();
+        PageLabeler::new()
+            .apply(&mut doc, &PageLabels::Remove, &[], &[])
+            .unwrap();
+
+        assert!(read_labels(&doc).is_none());
+    }
+
+    #[test]
+    fn test_number_tree() {
+        let labels = [
+            label("r", "", 1),
+            label("r", "", 2),
+            label("D", "", 1),
+            label("D", "", 5),
+            label("D", "A-", 6),
+        ];
+
+        let tree = number_tree(&labels);
+        let nums = tree.get(b"Nums").unwrap().as_array().unwrap();
+        let starts: Vec<i64> = nums
+            .iter()
+            .step_by(2)
+            .map(|start| start.as_i64().unwrap())
+            .collect();
+        assert_eq!(starts, vec![0, 2, 3, 4]);
+
+        let first = nums[1].as_dict().unwrap();
+        assert!(first.get(b"St").is_err());
+        assert!(first.get(b"P").is_err());
+        assert_eq!(
+            nums[5]
+                .as_dict()
+                .unwrap()
+                .get(b"St")
+                .unwrap()
+                .as_i64()
+                .unwrap(),
+            5
+        );
+    }
+}
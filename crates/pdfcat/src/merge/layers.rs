@@ -0,0 +1,315 @@
+//! Optional content groups (layers).
+//!
+//! Drawings from CAD and GIS tools often put their content in optional
+//! content groups, which viewers list as layers that can be shown or
+//! hidden. The groups are listed in the catalog's `/OCProperties`, with a
+//! default configuration (`/D`) holding the order of the layers panel and
+//! which groups start on or off.
+//!
+//! Merging combines the groups of every input under one `/OCProperties`,
+//! optionally placing each input's layers under a heading with its file
+//! name. Alternate configurations (`/Configs`) are not kept, since each
+//! only covers the layers of its own input.
+
+use lopdf::{Dictionary, Document, Object, ObjectId, dictionary};
+use std::collections::HashSet;
+
+use crate::error::{PdfCatError, Result};
+
+/// The layers of one input.
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    /// Optional content groups.
+    ocgs: Vec<Object>,
+
+    /// Entries of the layers panel.
+    order: Vec<Object>,
+
+    /// Groups that start hidden.
+    off: HashSet<ObjectId>,
+
+    /// Groups the user can't show or hide.
+    locked: Vec<Object>,
+
+    /// Radio button groups, of which one group at most is shown.
+    rb_groups: Vec<Object>,
+
+    /// Usage application dictionaries, which show or hide groups for
+    /// viewing, printing or exporting.
+    auto_states: Vec<Object>,
+}
+
+/// Merger for the optional content groups of the inputs.
+#[derive(Debug, Clone)]
+pub struct LayerMerger;
+
+impl LayerMerger {
+    /// Create a new layer merger.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read the layers of an input.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Input document, with objects numbered as in the output
+    ///
+    /// # Returns
+    ///
+    /// The input's layers, or None if it has none.
+    pub fn read_layers(&self, doc: &Document) -> Option<Layers> {
+        let properties = doc
+            .catalog()
+            .ok()?
+            .get_deref(b"OCProperties", doc)
+            .ok()?
+            .as_dict()
+            .ok()?;
+
+        let ocgs = array(doc, properties, b"OCGs");
+        if ocgs.is_empty() {
+            return None;
+        }
+
+        let config = properties
+            .get_deref(b"D", doc)
+            .and_then(Object::as_dict)
+            .cloned()
+            .unwrap_or_default();
+
+        // With a base state of OFF, groups not listed as on start hidden
+        let base_off = config
+            .get_deref(b"BaseState", doc)
+            .and_then(Object::as_name)
+            .is_ok_and(|state| state == b"OFF");
+        let off = if base_off {
+            let on = references(&array(doc, &config, b"ON"));
+            references(&ocgs).difference(&on).copied().collect()
+        } else {
+            references(&array(doc, &config, b"OFF"))
+        };
+
+        let order = match config.get_deref(b"Order", doc).and_then(Object::as_array) {
+            Ok(order) => order.clone(),
+            Err(_) => ocgs.clone(),
+        };
+
+        Some(Layers {
+            order,
+            off,
+            locked: array(doc, &config, b"Locked"),
+            rb_groups: array(doc, &config, b"RBGroups"),
+            auto_states: array(doc, &config, b"AS"),
+            ocgs,
+        })
+    }
+
+    /// Combine the layers of the inputs into the optional content
+    /// properties of a document.
+    ///
+    /// Every group keeps the state it starts in; the combined
+    /// configuration lists the groups that start on under `/ON` and the
+    /// rest under `/OFF`.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Merged document
+    /// * `inputs` - File name and layers of each input with layers, in order
+    /// * `group` - Whether to list each input's layers under its file name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::LayerMerger;
+    /// # use lopdf::Document;
+    /// # fn example(mut merged: Document, site: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let merger = LayerMerger::new();
+    /// let mut layers = Vec::new();
+    /// layers.extend(merger.read_layers(&merged).map(|l| ("plan.pdf".to_string(), l)));
+    /// layers.extend(merger.read_layers(&site).map(|l| ("site.pdf".to_string(), l)));
+    /// merger.merge_layers(&mut merged, layers, true)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_layers(
+        &self,
+        doc: &mut Document,
+        inputs: Vec<(String, Layers)>,
+        group: bool,
+    ) -> Result<()> {
+        let mut ocgs = Vec::new();
+        let mut order = Vec::new();
+        let mut off = HashSet::new();
+        let mut locked = Vec::new();
+        let mut rb_groups = Vec::new();
+        let mut auto_states = Vec::new();
+
+        for (name, layers) in inputs {
+            if group {
+                let mut heading = vec![lopdf::text_string(&name)];
+                heading.extend(layers.order);
+                order.push(Object::Array(heading));
+            } else {
+                order.extend(layers.order);
+            }
+
+            ocgs.extend(layers.ocgs);
+            off.extend(layers.off);
+            locked.extend(layers.locked);
+            rb_groups.extend(layers.rb_groups);
+            auto_states.extend(layers.auto_states);
+        }
+
+        let (off, on): (Vec<Object>, Vec<Object>) = ocgs
+            .iter()
+            .cloned()
+            .partition(|ocg| ocg.as_reference().is_ok_and(|id| off.contains(&id)));
+
+        let mut config = dictionary! {
+            "Order" => order,
+            "ON" => on,
+            "OFF" => off,
+        };
+        for (key, entries) in [
+            ("Locked", locked),
+            ("RBGroups", rb_groups),
+            ("AS", auto_states),
+        ] {
+            if !entries.is_empty() {
+                config.set(key, entries);
+            }
+        }
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        catalog.set(
+            "OCProperties",
+            dictionary! {
+                "OCGs" => ocgs,
+                "D" => config,
+            },
+        );
+
+        Ok(())
+    }
+}
+
+impl Default for LayerMerger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An array in a dictionary, or an empty one if it has none.
+fn array(doc: &Document, dict: &Dictionary, key: &[u8]) -> Vec<Object> {
+    dict.get_deref(key, doc)
+        .and_then(Object::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// IDs of the objects referred to in an array.
+fn references(objects: &[Object]) -> HashSet<ObjectId> {
+    objects
+        .iter()
+        .filter_map(|object| object.as_reference().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A document with the layers named, the last of which starts hidden.
+    fn create_layered_doc(names: &[&str], base_off: bool) -> Document {
+        let mut doc = Document::with_version("1.7");
+        let ocgs: Vec<Object> = names
+            .iter()
+            .map(|name| {
+                Object::Reference(doc.add_object(dictionary! {
+                    "Type" => "OCG",
+                    "Name" => lopdf::text_string(name),
+                }))
+            })
+            .collect();
+
+        let (shown, hidden) = ocgs.split_at(ocgs.len() - 1);
+        let config = if base_off {
+            dictionary! { "BaseState" => "OFF", "ON" => shown.to_vec() }
+        } else {
+            dictionary! { "OFF" => hidden.to_vec(), "Order" => ocgs.clone() }
+        };
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "OCProperties" => dictionary! { "OCGs" => ocgs, "D" => config },
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    fn config(doc: &Document) -> &Dictionary {
+        doc.catalog()
+            .unwrap()
+            .get(b"OCProperties")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"D")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_read_layers() {
+        let merger = LayerMerger::new();
+        let layers = merger
+            .read_layers(&create_layered_doc(&["Walls", "Notes"], true))
+            .unwrap();
+        assert_eq!(layers.ocgs.len(), 2);
+        assert_eq!(layers.off, references(&layers.ocgs[1..]));
+        // Without an order, the panel lists every group
+        assert_eq!(layers.order, layers.ocgs);
+
+        assert!(merger.read_layers(&Document::with_version("1.7")).is_none());
+    }
+
+    #[test]
+    fn test_merge_layers() {
+        let merger = LayerMerger::new();
+        let mut merged = create_layered_doc(&["Walls", "Notes"], false);
+        let mut input = create_layered_doc(&["Roads", "Rivers", "Labels"], true);
+        input.renumber_objects_with(merged.max_id + 1);
+
+        let layers = vec![
+            ("plan.pdf".to_string(), merger.read_layers(&merged).unwrap()),
+            ("site.pdf".to_string(), merger.read_layers(&input).unwrap()),
+        ];
+        merger.merge_layers(&mut merged, layers, false).unwrap();
+
+        let config = config(&merged);
+        assert_eq!(config.get(b"Order").unwrap().as_array().unwrap().len(), 5);
+        assert_eq!(config.get(b"ON").unwrap().as_array().unwrap().len(), 3);
+        assert_eq!(config.get(b"OFF").unwrap().as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_layers_grouped() {
+        let merger = LayerMerger::new();
+        let mut merged = create_layered_doc(&["Walls", "Notes"], false);
+        let layers = vec![("plan.pdf".to_string(), merger.read_layers(&merged).unwrap())];
+        merger.merge_layers(&mut merged, layers, true).unwrap();
+
+        let order = config(&merged).get(b"Order").unwrap().as_array().unwrap();
+        assert_eq!(order.len(), 1);
+        let heading = order[0].as_array().unwrap();
+        assert_eq!(lopdf::decode_text_string(&heading[0]).unwrap(), "plan.pdf");
+        assert_eq!(heading.len(), 3);
+    }
+}
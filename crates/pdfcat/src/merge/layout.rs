@@ -0,0 +1,742 @@
+//! Page imposition (N-up and booklet layouts).
+//!
+//! This module places several source pages onto each output sheet:
+//! - Each source page is wrapped as a Form XObject
+//! - Each sheet is a new page whose content stream draws the forms with
+//!   scaling transforms, one per grid cell
+//! - Booklets use a two-up layout with pages in saddle-stitch order
+//!
+//! Annotations and form fields of the source pages are not carried over
+//! to the sheets. Outline and link destinations that pointed at a source
+//! page are redirected to the sheet it was placed on.
+
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, dictionary};
+use std::collections::{HashMap, HashSet};
+
+use crate::config::Layout;
+use crate::error::{PdfCatError, Result};
+use crate::merge::pages::PageExtractor;
+
+/// A rectangle as `[x0, y0, x1, y1]` in PDF user space units.
+pub type Rect = [f32; 4];
+
+/// Imposer that lays out pages onto larger sheets.
+#[derive(Debug, Clone)]
+pub struct Imposer;
+
+impl Imposer {
+    /// Create a new imposer.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Replace the pages of a document with imposed sheets.
+    ///
+    /// The sheet size matches the first page, in whichever orientation
+    /// lets the pages be drawn largest. Pages are scaled uniformly to fit
+    /// their cell and centered in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to impose
+    /// * `layout` - Layout to apply
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page cannot be read or the page tree cannot
+    /// be updated.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::layout::Imposer;
+    /// # use pdfcat::config::Layout;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let imposer = Imposer::new();
+    /// imposer.impose(&mut doc, Layout::Grid { columns: 2, rows: 1 })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn impose(&self, doc: &mut Document, layout: Layout) -> Result<()> {
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let Some(&first) = pages.first() else {
+            return Ok(());
+        };
+
+        let (columns, rows) = match layout {
+            Layout::Grid { columns, rows } => (columns, rows),
+            Layout::Booklet => (2, 1),
+        };
+
+        let slots: Vec<Option<ObjectId>> = match layout {
+            Layout::Grid { .. } => pages.iter().copied().map(Some).collect(),
+            Layout::Booklet => booklet_order(pages.len())
+                .into_iter()
+                .map(|index| index.map(|index| pages[index]))
+                .collect(),
+        };
+
+        let (width, height) = displayed_size(page_box(doc, first), page_rotation(doc, first));
+        let (sheet_width, sheet_height) = sheet_size(width, height, columns, rows);
+        let cell_width = sheet_width / columns as f32;
+        let cell_height = sheet_height / rows as f32;
+
+        let pages_id = pages_root(doc)?;
+        let mut forms = HashMap::new();
+        let mut sheets = Vec::new();
+        let mut moved = HashMap::new();
+
+        for sheet_slots in slots.chunks(layout.pages_per_sheet().max(1) as usize) {
+            let sheet_id = doc.new_object_id();
+            let mut content = String::new();
+            let mut xobjects = Dictionary::new();
+
+            for (cell, slot) in sheet_slots.iter().enumerate() {
+                let Some(page_id) = *slot else {
+                    continue;
+                };
+
+                let form_id = match forms.get(&page_id) {
+                    Some(&form_id) => form_id,
+                    None => {
+                        let form_id = page_to_form(doc, page_id)?;
+                        forms.insert(page_id, form_id);
+                        form_id
+                    }
+                };
+
+                let column = cell as u32 % columns;
+                let row = cell as u32 / columns;
+                let cell_rect = [
+                    column as f32 * cell_width,
+                    sheet_height - (row + 1) as f32 * cell_height,
+                    (column + 1) as f32 * cell_width,
+                    sheet_height - row as f32 * cell_height,
+                ];
+                let matrix = placement_matrix(
+                    page_box(doc, page_id),
+                    page_rotation(doc, page_id),
+                    cell_rect,
+                );
+
+                let name = format!("P{cell}");
+                content.push_str(&format!(
+                    "q {} cm /{name} Do Q\n",
+                    matrix.map(|value| value.to_string()).join(" ")
+                ));
+                xobjects.set(name, Object::Reference(form_id));
+                moved.entry(page_id).or_insert(sheet_id);
+            }
+
+            let media_box = rect_object([0.0, 0.0, sheet_width, sheet_height]);
+            let contents_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+            let sheet = dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => media_box.clone(),
+                "CropBox" => media_box,
+                "Rotate" => 0,
+                "Resources" => dictionary! { "XObject" => xobjects },
+                "Contents" => contents_id,
+            };
+            doc.objects.insert(sheet_id, Object::Dictionary(sheet));
+            sheets.push(sheet_id);
+        }
+
+        PageExtractor::new().update_page_tree(doc, &sheets)?;
+        redirect_destinations(doc, &moved);
+
+        // The forms hold copies of the source pages' content, so the pages
+        // and their content streams are no longer needed
+        for &page_id in &pages {
+            for stream in content_streams(doc, page_id)? {
+                if let Ok(id) = stream.as_reference() {
+                    doc.objects.remove(&id);
+                }
+            }
+            doc.objects.remove(&page_id);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Imposer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Order pages for a saddle-stitched booklet.
+///
+/// Returns 0-indexed page numbers in sheet order (front left, front right,
+/// back left, back right for each sheet). The page count is padded to a
+/// multiple of four with blanks, shown as `None`.
+pub fn booklet_order(page_count: usize) -> Vec<Option<usize>> {
+    let padded = page_count.div_ceil(4) * 4;
+    let page = |index: usize| (index < page_count).then_some(index);
+
+    (0..padded / 4)
+        .flat_map(|sheet| {
+            [
+                page(padded - 1 - 2 * sheet),
+                page(2 * sheet),
+                page(2 * sheet + 1),
+                page(padded - 2 - 2 * sheet),
+            ]
+        })
+        .collect()
+}
+
+/// Choose the sheet orientation that lets pages be drawn largest.
+///
+/// The sheet has the dimensions of a `width` x `height` page, either in
+/// portrait or landscape orientation. Portrait wins ties.
+fn sheet_size(width: f32, height: f32, columns: u32, rows: u32) -> (f32, f32) {
+    let short = width.min(height);
+    let long = width.max(height);
+
+    let scale = |sheet_width: f32, sheet_height: f32| {
+        (sheet_width / columns as f32 / width).min(sheet_height / rows as f32 / height)
+    };
+
+    if scale(long, short) > scale(short, long) {
+        (long, short)
+    } else {
+        (short, long)
+    }
+}
+
+/// The visible box of a page: its CropBox, falling back to its MediaBox.
+///
+/// Inherited boxes are honored. Pages without a usable box are treated
+/// as US Letter.
+pub(crate) fn page_box(doc: &Document, page_id: ObjectId) -> Rect {
+    [b"CropBox".as_slice(), b"MediaBox"]
+        .into_iter()
+        .find_map(|key| inherited_attribute(doc, page_id, key).and_then(|obj| rect(doc, obj)))
+        .unwrap_or([0.0, 0.0, 612.0, 792.0])
+}
+
+/// The page's `/Rotate` value normalized to 0, 90, 180 or 270.
+pub(crate) fn page_rotation(doc: &Document, page_id: ObjectId) -> i64 {
+    let rotation = inherited_attribute(doc, page_id, b"Rotate")
+        .and_then(|obj| obj.as_i64().ok())
+        .unwrap_or(0);
+
+    (rotation.rem_euclid(360) / 90) * 90
+}
+
+/// Width and height of a box as displayed after applying `/Rotate`.
+pub(crate) fn displayed_size(bbox: Rect, rotation: i64) -> (f32, f32) {
+    let width = bbox[2] - bbox[0];
+    let height = bbox[3] - bbox[1];
+
+    if rotation % 180 == 0 {
+        (width, height)
+    } else {
+        (height, width)
+    }
+}
+
+/// Transform that draws a page's content, as seen in a viewer, centered
+/// and scaled to fit inside `cell`.
+///
+/// # Arguments
+///
+/// * `bbox` - The page's visible box in its own user space
+/// * `rotation` - The page's `/Rotate` value (0, 90, 180 or 270)
+/// * `cell` - Target rectangle on the sheet
+///
+/// # Returns
+///
+/// The `[a b c d e f]` operands of a `cm` operator.
+pub(crate) fn placement_matrix(bbox: Rect, rotation: i64, cell: Rect) -> [f32; 6] {
+    scaled_placement_matrix(bbox, rotation, cell, f32::INFINITY)
+}
+
+/// Like [`placement_matrix`], but never scales the page by more than
+/// `max_scale`.
+pub(crate) fn scaled_placement_matrix(
+    bbox: Rect,
+    rotation: i64,
+    cell: Rect,
+    max_scale: f32,
+) -> [f32; 6] {
+    let width = bbox[2] - bbox[0];
+    let height = bbox[3] - bbox[1];
+    let (shown_width, shown_height) = displayed_size(bbox, rotation);
+
+    // Rotation clockwise by the page's /Rotate, mapping the box (moved to
+    // the origin) onto [0, shown_width] x [0, shown_height].
+    let (a, b, c, d, e, f) = match rotation {
+        90 => (0.0, -1.0, 1.0, 0.0, 0.0, width),
+        180 => (-1.0, 0.0, 0.0, -1.0, width, height),
+        270 => (0.0, 1.0, -1.0, 0.0, height, 0.0),
+        _ => (1.0, 0.0, 0.0, 1.0, 0.0, 0.0),
+    };
+
+    let cell_width = cell[2] - cell[0];
+    let cell_height = cell[3] - cell[1];
+    let scale = (cell_width / shown_width)
+        .min(cell_height / shown_height)
+        .min(max_scale);
+    let offset_x = cell[0] + (cell_width - scale * shown_width) / 2.0;
+    let offset_y = cell[1] + (cell_height - scale * shown_height) / 2.0;
+
+    // Translation of the box origin after rotation
+    let origin_x = -(a * bbox[0] + c * bbox[1]) + e;
+    let origin_y = -(b * bbox[0] + d * bbox[1]) + f;
+
+    [
+        scale * a,
+        scale * b,
+        scale * c,
+        scale * d,
+        scale * origin_x + offset_x,
+        scale * origin_y + offset_y,
+    ]
+}
+
+/// References to a page's content streams, in drawing order.
+pub(crate) fn content_streams(doc: &Document, page_id: ObjectId) -> Result<Vec<Object>> {
+    let contents = doc
+        .get_dictionary(page_id)
+        .map_err(|err| PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}")))?
+        .get(b"Contents")
+        .ok()
+        .cloned();
+
+    Ok(match contents {
+        Some(Object::Reference(id)) => match doc.get_object(id) {
+            Ok(Object::Array(items)) => items.clone(),
+            _ => vec![Object::Reference(id)],
+        },
+        Some(Object::Array(items)) => items,
+        _ => Vec::new(),
+    })
+}
+
+/// Draw `content` on top of a page.
+///
+/// The page's existing content is wrapped in `q`/`Q`, so graphics state it
+/// leaves behind doesn't affect `content`.
+pub(crate) fn append_content(doc: &mut Document, page_id: ObjectId, content: &str) -> Result<()> {
+    let mut streams = content_streams(doc, page_id)?;
+    let prefix_id = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+    let suffix = format!("\nQ\n{content}");
+    let suffix_id = doc.add_object(Stream::new(Dictionary::new(), suffix.into_bytes()));
+    streams.insert(0, Object::Reference(prefix_id));
+    streams.push(Object::Reference(suffix_id));
+
+    set_content_streams(doc, page_id, streams)
+}
+
+/// Draw `content` underneath a page's existing content.
+///
+/// `content` must restore the graphics state it changes.
+pub(crate) fn prepend_content(doc: &mut Document, page_id: ObjectId, content: &str) -> Result<()> {
+    let mut streams = content_streams(doc, page_id)?;
+    let content = format!("{content}\n");
+    let prefix_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+    streams.insert(0, Object::Reference(prefix_id));
+
+    set_content_streams(doc, page_id, streams)
+}
+
+fn set_content_streams(doc: &mut Document, page_id: ObjectId, streams: Vec<Object>) -> Result<()> {
+    let page = doc.get_dictionary_mut(page_id).map_err(|err| {
+        PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}"))
+    })?;
+    page.set("Contents", Object::Array(streams));
+
+    Ok(())
+}
+
+/// Add a named resource, such as a font, to a page.
+///
+/// The page gets its own copy of its resources, inherited ones included,
+/// so other pages sharing them are not affected.
+///
+/// # Arguments
+///
+/// * `category` - Resource category, such as "Font" or "ExtGState"
+/// * `name` - Name the page's content uses for the resource
+/// * `id` - The resource object
+pub(crate) fn add_page_resource(
+    doc: &mut Document,
+    page_id: ObjectId,
+    category: &str,
+    name: &str,
+    id: ObjectId,
+) -> Result<()> {
+    let mut resources = inherited_attribute(doc, page_id, b"Resources")
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut entries = resources
+        .get(category.as_bytes())
+        .ok()
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+    entries.set(name, id);
+    resources.set(category, entries);
+
+    let page = doc.get_dictionary_mut(page_id).map_err(|err| {
+        PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}"))
+    })?;
+    page.set("Resources", resources);
+
+    Ok(())
+}
+
+/// Transform from a page's user space to its displayed coordinates, with
+/// the origin at the bottom left corner of the page as displayed.
+pub(crate) fn displayed_matrix(doc: &Document, page_id: ObjectId) -> [f32; 6] {
+    let bbox = page_box(doc, page_id);
+    let rotation = page_rotation(doc, page_id);
+    let (width, height) = displayed_size(bbox, rotation);
+
+    placement_matrix(bbox, rotation, [0.0, 0.0, width, height])
+}
+
+/// Inverse of a `cm` matrix.
+///
+/// Placement matrices without scaling are rotations by multiples of 90
+/// degrees plus a translation, so they are always invertible.
+pub(crate) fn invert_matrix(m: [f32; 6]) -> [f32; 6] {
+    let det = m[0] * m[3] - m[1] * m[2];
+    let a = m[3] / det;
+    let b = -m[1] / det;
+    let c = -m[2] / det;
+    let d = m[0] / det;
+
+    // Adding zero turns -0 into 0, which reads better in content streams
+    [a, b, c, d, -(a * m[4] + c * m[5]), -(b * m[4] + d * m[5])].map(|value| value + 0.0)
+}
+
+/// Wrap a page's content and resources as a Form XObject.
+pub(crate) fn page_to_form(doc: &mut Document, page_id: ObjectId) -> Result<ObjectId> {
+    // A stream may end without whitespace, so streams are joined with a
+    // line break to keep its last token from running into the next one's
+    // first
+    let content = content_streams(doc, page_id)?
+        .iter()
+        .filter_map(|stream| {
+            let stream = doc
+                .dereference(stream)
+                .ok()
+                .and_then(|(_, obj)| obj.as_stream().ok())?;
+            Some(
+                stream
+                    .decompressed_content()
+                    .unwrap_or_else(|_| stream.content.clone()),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(b"\n".as_slice());
+
+    let mut form = dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Form",
+        "BBox" => rect_object(page_box(doc, page_id)),
+    };
+
+    for key in [b"Resources".as_slice(), b"Group"] {
+        if let Some(value) = inherited_entry(doc, page_id, key) {
+            form.set(key, value.clone());
+        }
+    }
+
+    Ok(doc.add_object(Stream::new(form, content)))
+}
+
+/// Point destinations at moved pages to their new pages instead.
+///
+/// Explicit destinations are arrays starting with a page reference and a
+/// fit type name. Redirected destinations show the whole new page.
+pub(crate) fn redirect_destinations(doc: &mut Document, moved: &HashMap<ObjectId, ObjectId>) {
+    fn visit(obj: &mut Object, moved: &HashMap<ObjectId, ObjectId>) {
+        match obj {
+            Object::Array(items) => {
+                if let [Object::Reference(page_id), Object::Name(_), ..] = items.as_slice()
+                    && let Some(&new_id) = moved.get(page_id)
+                {
+                    *items = vec![Object::Reference(new_id), Object::Name(b"Fit".to_vec())];
+                    return;
+                }
+                items.iter_mut().for_each(|item| visit(item, moved));
+            }
+            Object::Dictionary(dict) => {
+                dict.iter_mut().for_each(|(_, value)| visit(value, moved));
+            }
+            Object::Stream(stream) => {
+                stream
+                    .dict
+                    .iter_mut()
+                    .for_each(|(_, value)| visit(value, moved));
+            }
+            _ => {}
+        }
+    }
+
+    if moved.is_empty() {
+        return;
+    }
+
+    for obj in doc.objects.values_mut() {
+        visit(obj, moved);
+    }
+}
+
+/// ID of the root page tree node.
+fn pages_root(doc: &Document) -> Result<ObjectId> {
+    doc.catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(|err| PdfCatError::merge_failed(format!("Failed to get pages reference: {err}")))
+}
+
+/// Look up a possibly inherited page attribute, resolving references.
+pub(crate) fn inherited_attribute<'a>(
+    doc: &'a Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let value = inherited_entry(doc, page_id, key)?;
+    doc.dereference(value).ok().map(|(_, obj)| obj)
+}
+
+/// Look up a possibly inherited page attribute as stored, without
+/// resolving references.
+pub(crate) fn inherited_entry<'a>(
+    doc: &'a Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let mut seen = HashSet::new();
+    let mut node = doc.get_dictionary(page_id).ok()?;
+
+    loop {
+        if let Ok(value) = node.get(key) {
+            return Some(value);
+        }
+
+        // A node seen before means a cycle in a corrupted tree
+        let parent_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+        if !seen.insert(parent_id) {
+            return None;
+        }
+        node = doc.get_dictionary(parent_id).ok()?;
+    }
+}
+
+/// Read a rectangle array, normalizing it so that x0 <= x1 and y0 <= y1.
+pub(crate) fn rect(doc: &Document, obj: &Object) -> Option<Rect> {
+    let arr = obj.as_array().ok()?;
+    if arr.len() < 4 {
+        return None;
+    }
+
+    let mut coords = [0.0f32; 4];
+    for (coord, value) in coords.iter_mut().zip(arr) {
+        *coord = doc.dereference(value).ok()?.1.as_float().ok()?;
+    }
+
+    let normalized = [
+        coords[0].min(coords[2]),
+        coords[1].min(coords[3]),
+        coords[0].max(coords[2]),
+        coords[1].max(coords[3]),
+    ];
+
+    (normalized[2] > normalized[0] && normalized[3] > normalized[1]).then_some(normalized)
+}
+
+/// Build a rectangle array object.
+pub(crate) fn rect_object(rect: Rect) -> Object {
+    Object::Array(rect.iter().map(|&value| Object::Real(value)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::test_utils;
+
+    fn create_doc(sizes: &[(i64, i64, i64)]) -> Document {
+        test_utils::create_doc(sizes, b"0 0 m 10 10 l S")
+    }
+
+    fn transform(matrix: [f32; 6], x: f32, y: f32) -> (f32, f32) {
+        (
+            matrix[0] * x + matrix[2] * y + matrix[4],
+            matrix[1] * x + matrix[3] * y + matrix[5],
+        )
+    }
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 0.01 && (actual.1 - expected.1).abs() < 0.01,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_booklet_order() {
+        assert_eq!(
+            booklet_order(8),
+            vec![
+                Some(7),
+                Some(0),
+                Some(1),
+                Some(6),
+                Some(5),
+                Some(2),
+                Some(3),
+                Some(4)
+            ]
+        );
+        assert_eq!(booklet_order(2), vec![None, Some(0), Some(1), None]);
+        assert!(booklet_order(0).is_empty());
+    }
+
+    #[test]
+    fn test_sheet_size_orientation() {
+        // Two portrait pages side by side fit best on a landscape sheet
+        assert_eq!(sheet_size(595.0, 842.0, 2, 1), (842.0, 595.0));
+        // Four stay portrait
+        assert_eq!(sheet_size(595.0, 842.0, 2, 2), (595.0, 842.0));
+    }
+
+    #[test]
+    fn test_placement_matrix_unrotated() {
+        let matrix = placement_matrix([0.0, 0.0, 100.0, 200.0], 0, [0.0, 0.0, 50.0, 50.0]);
+
+        // Scaled by 1/4 and centered horizontally
+        assert_close(transform(matrix, 0.0, 0.0), (12.5, 0.0));
+        assert_close(transform(matrix, 100.0, 200.0), (37.5, 50.0));
+    }
+
+    #[test]
+    fn test_placement_matrix_rotated() {
+        let bbox = [10.0, 20.0, 110.0, 220.0];
+        let cell = [0.0, 0.0, 200.0, 100.0];
+
+        // Rotated 90 degrees clockwise: the top-left corner ends up at the
+        // top-right, and the bottom-left corner at the top-left.
+        let matrix = placement_matrix(bbox, 90, cell);
+        assert_close(transform(matrix, 10.0, 220.0), (200.0, 100.0));
+        assert_close(transform(matrix, 10.0, 20.0), (0.0, 100.0));
+
+        let matrix = placement_matrix(bbox, 180, cell);
+        assert_close(transform(matrix, 10.0, 20.0), (125.0, 100.0));
+
+        let matrix = placement_matrix(bbox, 270, cell);
+        assert_close(transform(matrix, 10.0, 20.0), (200.0, 0.0));
+    }
+
+    #[test]
+    fn test_impose_two_up() {
+        let mut doc = create_doc(&[(595, 842, 0); 5]);
+        Imposer::new()
+            .impose(
+                &mut doc,
+                Layout::Grid {
+                    columns: 2,
+                    rows: 1,
+                },
+            )
+            .unwrap();
+
+        let sheets: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(sheets.len(), 3);
+        assert_eq!(page_box(&doc, sheets[0]), [0.0, 0.0, 842.0, 595.0]);
+
+        let content = String::from_utf8(doc.get_page_content(sheets[0]).unwrap()).unwrap();
+        assert_eq!(content.matches(" Do ").count(), 2);
+        let content = String::from_utf8(doc.get_page_content(sheets[2]).unwrap()).unwrap();
+        assert_eq!(content.matches(" Do ").count(), 1);
+    }
+
+    #[test]
+    fn test_impose_removes_source_pages() {
+        let mut doc = create_doc(&[(595, 842, 0); 2]);
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let contents = doc.get_page_contents(pages[0]);
+        Imposer::new().impose(&mut doc, Layout::Booklet).unwrap();
+
+        assert!(!doc.objects.contains_key(&pages[0]));
+        assert!(!doc.objects.contains_key(&pages[1]));
+        assert!(!doc.objects.contains_key(&contents[0]));
+    }
+
+    #[test]
+    fn test_page_to_form_joins_streams() {
+        let mut doc = create_doc(&[(595, 842, 0)]);
+        let page_id = doc.page_iter().next().unwrap();
+        let first = doc.add_object(Stream::new(Dictionary::new(), b"q 1 0 0 1 5 5 cm".to_vec()));
+        let second = doc.add_object(Stream::new(
+            Dictionary::new(),
+            b"0 0 m 10 10 l S Q".to_vec(),
+        ));
+        doc.get_dictionary_mut(page_id)
+            .unwrap()
+            .set("Contents", vec![first.into(), second.into()]);
+
+        let form_id = page_to_form(&mut doc, page_id).unwrap();
+        let form = doc.get_object(form_id).unwrap().as_stream().unwrap();
+        assert_eq!(form.content, b"q 1 0 0 1 5 5 cm\n0 0 m 10 10 l S Q");
+    }
+
+    #[test]
+    fn test_impose_booklet() {
+        let mut doc = create_doc(&[(595, 842, 0); 3]);
+        Imposer::new().impose(&mut doc, Layout::Booklet).unwrap();
+
+        // 3 pages are padded to 4: one sheet, front and back
+        assert_eq!(doc.get_pages().len(), 2);
+    }
+
+    #[test]
+    fn test_redirect_destinations() {
+        let mut doc = create_doc(&[(100, 100, 0)]);
+        let page_id = *doc.get_pages().get(&1).unwrap();
+        let new_id = (999, 0);
+        let dest_id = doc.add_object(vec![
+            Object::Reference(page_id),
+            Object::Name(b"XYZ".to_vec()),
+            Object::Null,
+            Object::Null,
+            Object::Null,
+        ]);
+
+        redirect_destinations(&mut doc, &HashMap::from([(page_id, new_id)]));
+
+        let dest = doc.get_object(dest_id).unwrap().as_array().unwrap();
+        assert_eq!(dest[0], Object::Reference(new_id));
+        assert_eq!(dest.len(), 2);
+    }
+
+    #[test]
+    fn test_invert_matrix() {
+        let matrix = placement_matrix([0.0, 0.0, 612.0, 792.0], 270, [0.0, 0.0, 792.0, 612.0]);
+        let inverse = invert_matrix(matrix);
+
+        // Applying both transforms gives back the original point
+        let (x, y) = (100.0, 50.0);
+        let (u, v) = (
+            inverse[0] * x + inverse[2] * y + inverse[4],
+            inverse[1] * x + inverse[3] * y + inverse[5],
+        );
+        let (x2, y2) = (
+            matrix[0] * u + matrix[2] * v + matrix[4],
+            matrix[1] * u + matrix[3] * v + matrix[5],
+        );
+        assert_eq!((x2, y2), (x, y));
+    }
+}
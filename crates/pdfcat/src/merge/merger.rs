@@ -5,15 +5,35 @@
 
 use lopdf::{Document, Object, ObjectId};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-use crate::config::Config;
+use crate::config::{
+    BoxKind, Config, MergeMode, PageLabels, PageMode, PdfVersion, Stamp, WatermarkContent,
+};
 use crate::error::{PdfCatError, Result};
-use crate::io::{LoadedPdf, PdfReader};
+use crate::io::{LoadedPdf, PdfReader, RepairReport};
+use crate::merge::annotations::AnnotationEditor;
+use crate::merge::attachments::AttachmentManager;
+use crate::merge::bates::{BatesRange, PageSource, bates_ranges};
 use crate::merge::bookmarks::BookmarkManager;
+use crate::merge::boxes::BoxEditor;
+use crate::merge::labels::PageLabeler;
+use crate::merge::layers::LayerMerger;
+use crate::merge::layout::{Imposer, displayed_size, page_box, page_rotation};
 use crate::merge::metadata::MetadataManager;
+use crate::merge::normalize::PageNormalizer;
 use crate::merge::pages::PageExtractor;
+use crate::merge::portfolio::{PortfolioBuilder, PortfolioFile};
+use crate::merge::sanitize::{SanitizeReport, Sanitizer};
+use crate::merge::separator::{SeparatorGenerator, SeparatorInfo};
+use crate::merge::stamp::{StampContext, Stamper};
+use crate::merge::structure::StructureMerger;
+use crate::merge::version::{VersionFeature, VersionManager};
+use crate::merge::viewer::ViewerConfigurator;
+use crate::merge::watermark::Watermarker;
+use crate::validation::SkippedFile;
 
 /// Statistics about a merge operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,8 +57,14 @@ pub struct MergeStatistics {
     /// Number of bookmarks added.
     pub bookmarks_added: usize,
 
+    /// Number of blank pages inserted.
+    pub blank_pages_added: usize,
+
     /// Whether compression was applied.
     pub compressed: bool,
+
+    /// PDF version of the merged document.
+    pub pdf_version: PdfVersion,
 }
 
 impl MergeStatistics {
@@ -59,6 +85,41 @@ pub struct MergeResult {
 
     /// Paths of files that were merged.
     pub merged_files: Vec<PathBuf>,
+
+    /// Files that had to be repaired, with what was recovered.
+    pub repaired_files: Vec<(PathBuf, RepairReport)>,
+
+    /// Files skipped because they failed to load.
+    pub skipped_files: Vec<SkippedFile>,
+
+    /// Bates numbers of the output pages and where the pages came from
+    /// (empty without Bates numbering).
+    pub bates_log: Vec<BatesRange>,
+
+    /// What was removed from each input (empty without sanitizing).
+    pub sanitized_files: Vec<(PathBuf, SanitizeReport)>,
+
+    /// Features of the merged document that its PDF version doesn't
+    /// support (empty unless an older version was forced).
+    pub unsupported_features: Vec<VersionFeature>,
+}
+
+/// A merged document with what the merge did to it.
+struct MergedDocument {
+    /// The merged document.
+    document: Document,
+
+    /// Number of blank pages inserted.
+    blank_pages_added: usize,
+
+    /// Bates log of the output pages.
+    bates_log: Vec<BatesRange>,
+
+    /// What was removed from each input, in order.
+    sanitize_reports: Vec<SanitizeReport>,
+
+    /// Features the document's PDF version doesn't support.
+    unsupported_features: Vec<VersionFeature>,
 }
 
 /// PDF merger that combines multiple documents.
@@ -73,8 +134,53 @@ pub struct Merger {
     /// Bookmark manager for outline handling.
     bookmark_manager: BookmarkManager,
 
+    /// Generator for separator pages between inputs.
+    separator_generator: SeparatorGenerator,
+
+    /// Editor for crop, trim and bleed boxes.
+    box_editor: BoxEditor,
+
+    /// Normalizer for resizing pages to a common paper size.
+    normalizer: PageNormalizer,
+
+    /// Watermarker for text and PDF page watermarks.
+    watermarker: Watermarker,
+
+    /// Stamper for page numbers, headers and footers.
+    stamper: Stamper,
+
+    /// Labeler for the page numbers viewers show.
+    labeler: PageLabeler,
+
+    /// Imposer for N-up and booklet layouts.
+    imposer: Imposer,
+
     /// Metadata manager for document properties.
     metadata_manager: MetadataManager,
+
+    /// Manager for embedded file attachments.
+    attachment_manager: AttachmentManager,
+
+    /// Builder for PDF Portfolios.
+    portfolio_builder: PortfolioBuilder,
+
+    /// Merger for the structure trees of tagged inputs.
+    structure_merger: StructureMerger,
+
+    /// Merger for the layers of the inputs.
+    layer_merger: LayerMerger,
+
+    /// Editor for annotation removal and flattening.
+    annotation_editor: AnnotationEditor,
+
+    /// Sanitizer for active content and hidden data.
+    sanitizer: Sanitizer,
+
+    /// Configurator for how viewers show the output.
+    viewer_configurator: ViewerConfigurator,
+
+    /// Manager for the PDF version of the output.
+    version_manager: VersionManager,
 }
 
 impl Merger {
@@ -84,7 +190,22 @@ impl Merger {
             reader: PdfReader::new(),
             page_extractor: PageExtractor::new(),
             bookmark_manager: BookmarkManager::new(),
+            separator_generator: SeparatorGenerator::new(),
+            box_editor: BoxEditor::new(),
+            normalizer: PageNormalizer::new(),
+            watermarker: Watermarker::new(),
+            stamper: Stamper::new(),
+            labeler: PageLabeler::new(),
+            imposer: Imposer::new(),
             metadata_manager: MetadataManager::new(),
+            attachment_manager: AttachmentManager::new(),
+            portfolio_builder: PortfolioBuilder::new(),
+            structure_merger: StructureMerger::new(),
+            layer_merger: LayerMerger::new(),
+            annotation_editor: AnnotationEditor::new(),
+            sanitizer: Sanitizer::new(),
+            viewer_configurator: ViewerConfigurator::new(),
+            version_manager: VersionManager::new(),
         }
     }
 
@@ -122,20 +243,30 @@ impl Merger {
 
         // Load all input PDFs
         let load_start = Instant::now();
-        let (load_results, _load_stats) = self
-            .reader
+        let reader = if config.repair {
+            PdfReader::with_repair()
+        } else {
+            self.reader.clone()
+        };
+        let (load_results, _load_stats) = reader
             .load_all(&config.inputs, config.effective_jobs())
             .await;
         let load_time = load_start.elapsed();
 
         // Separate successful loads from failures
         let mut loaded_pdfs = Vec::new();
-        for result in load_results {
+        let mut reversed = Vec::new();
+        let mut skipped_files = Vec::new();
+        for (index, (path, result)) in config.inputs.iter().zip(load_results).enumerate() {
             match result {
-                Ok(loaded) => loaded_pdfs.push(loaded),
+                Ok(loaded) => {
+                    loaded_pdfs.push(loaded);
+                    reversed.push(config.reverse_inputs.contains(&(index + 1)));
+                }
                 Err(err) => {
                     if config.continue_on_error {
                         eprintln!("Warning: Skipping file due to error: {err}");
+                        skipped_files.push(SkippedFile::new(path.clone(), &err));
                     } else {
                         return Err(err);
                     }
@@ -148,10 +279,23 @@ impl Merger {
         }
 
         // Perform the merge
-        let document = self.merge_documents(&loaded_pdfs, config).await?;
+        let MergedDocument {
+            document,
+            blank_pages_added,
+            bates_log,
+            sanitize_reports,
+            unsupported_features,
+        } = self
+            .merge_documents(&loaded_pdfs, &reversed, config)
+            .await?;
 
         let merge_time = merge_start.elapsed();
 
+        let repaired_files = loaded_pdfs
+            .iter()
+            .filter_map(|p| p.repair.clone().map(|report| (p.path.clone(), report)))
+            .collect();
+
         // Calculate statistics
         let statistics = MergeStatistics {
             files_merged: loaded_pdfs.len(),
@@ -160,55 +304,99 @@ impl Merger {
             load_time,
             input_size: loaded_pdfs.iter().map(|p| p.file_size).sum(),
             bookmarks_added: 0, // Updated if bookmarks are added
+            blank_pages_added,
             compressed: config.compression != crate::config::CompressionLevel::None,
+            pdf_version: self.version_manager.version(&document).unwrap_or_default(),
         };
 
         let merged_files: Vec<PathBuf> = loaded_pdfs.into_iter().map(|p| p.path).collect();
+        let sanitized_files = merged_files.iter().cloned().zip(sanitize_reports).collect();
 
         Ok(MergeResult {
             document,
             statistics,
             merged_files,
+            repaired_files,
+            skipped_files,
+            bates_log,
+            sanitized_files,
+            unsupported_features,
         })
     }
 
     /// Merge loaded PDF documents.
+    ///
+    /// `reversed` has one entry per document and says whether its pages
+    /// are taken in reverse order.
     async fn merge_documents(
         &self,
         loaded_pdfs: &[LoadedPdf],
+        reversed: &[bool],
         config: &Config,
-    ) -> Result<Document> {
+    ) -> Result<MergedDocument> {
         if loaded_pdfs.is_empty() {
             return Err(PdfCatError::NoFilesToMerge);
         }
 
+        if config.merge_mode == MergeMode::Portfolio {
+            let mut portfolio = self.build_portfolio(loaded_pdfs, config).await?;
+            let unsupported_features = self.finish_document(&mut portfolio, config, None)?;
+            return Ok(MergedDocument {
+                document: portfolio,
+                blank_pages_added: 0,
+                bates_log: Vec::new(),
+                sanitize_reports: Vec::new(),
+                unsupported_features,
+            });
+        }
+
         // Start with the first document as base
-        let mut merged = loaded_pdfs[0].document.clone();
-        let mut max_id = merged.max_id;
+        let (mut merged, first_pages) =
+            self.prepare_document(&loaded_pdfs[0], reversed[0], config)?;
 
-        // Process first document for page ranges
-        if let Some(ref page_range) = config.page_range {
-            merged = self.page_extractor.extract_pages(&merged, page_range)?;
+        // What sanitizing removed from each input
+        let mut sanitize_reports = Vec::new();
+        if config.sanitize {
+            sanitize_reports.push(self.sanitizer.sanitize(&mut merged)?);
         }
 
-        // Apply rotation to first document if specified
-        if let Some(rotation) = config.rotation {
-            self.page_extractor
-                .rotate_all_pages(&mut merged, rotation)?;
-        }
+        let mut max_id = merged.max_id;
+        let mut attachments = self.attachment_manager.embedded_files(&merged);
+
+        // Structure trees of tagged inputs, with the parent tree keys of
+        // each input shifted past those of the inputs before it
+        let mut structure_trees = Vec::new();
+        structure_trees.extend(self.structure_merger.take_tree(&mut merged, 0));
+
+        // Layers of each input that has any, under the input's file name
+        let layer_name = |loaded: &LoadedPdf| {
+            loaded.path.file_name().map_or_else(
+                || loaded.path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+        };
+        let mut layers = Vec::new();
+        layers.extend(
+            self.layer_merger
+                .read_layers(&merged)
+                .map(|input_layers| (layer_name(&loaded_pdfs[0]), input_layers)),
+        );
+
+        // Pages contributed by each input, in order
+        let mut input_pages: Vec<Vec<ObjectId>> = vec![merged.get_pages().into_values().collect()];
+
+        // Input and original page number of each page, for the Bates log
+        let mut sources: HashMap<ObjectId, (usize, Option<u32>)> = input_pages[0]
+            .iter()
+            .zip(first_pages)
+            .map(|(&page_id, page)| (page_id, (0, Some(page))))
+            .collect();
 
         // Merge remaining documents
-        for loaded in &loaded_pdfs[1..] {
-            let mut doc = loaded.document.clone();
-
-            // Extract pages if page range specified
-            if let Some(ref page_range) = config.page_range {
-                doc = self.page_extractor.extract_pages(&doc, page_range)?;
-            }
-
-            // Apply rotation if specified
-            if let Some(rotation) = config.rotation {
-                self.page_extractor.rotate_all_pages(&mut doc, rotation)?;
+        for (index, (loaded, &reverse)) in loaded_pdfs.iter().zip(reversed).enumerate().skip(1) {
+            let (mut doc, original_pages) = self.prepare_document(loaded, reverse, config)?;
+            if config.sanitize {
+                sanitize_reports.push(self.sanitizer.sanitize(&mut doc)?);
             }
 
             // Renumber objects to avoid ID conflicts
@@ -218,48 +406,452 @@ impl Merger {
             // Get page references from the document
             let doc_pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
 
+            attachments.extend(self.attachment_manager.embedded_files(&doc));
+            let offset = structure_trees.last().map_or(0, |tree| tree.next_key());
+            structure_trees.extend(self.structure_merger.take_tree(&mut doc, offset));
+            layers.extend(
+                self.layer_merger
+                    .read_layers(&doc)
+                    .map(|input_layers| (layer_name(loaded), input_layers)),
+            );
+
             // Add all objects from doc to merged
             merged.objects.extend(doc.objects);
 
             // Update the page tree
-            self.add_pages_to_tree(&mut merged, &doc_pages)?;
+            if config.merge_mode == MergeMode::Concatenate {
+                self.add_pages_to_tree(&mut merged, &doc_pages)?;
+            }
+
+            sources.extend(
+                doc_pages
+                    .iter()
+                    .zip(original_pages)
+                    .map(|(&page_id, page)| (page_id, (index, Some(page)))),
+            );
+            input_pages.push(doc_pages);
+        }
+        merged.max_id = max_id;
+
+        // Combine the inputs' attachments with the files to attach
+        for path in &config.attachments {
+            let data = tokio::fs::read(path)
+                .await
+                .map_err(|e| PdfCatError::FileNotAccessible {
+                    path: path.clone(),
+                    source: e,
+                })?;
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            attachments.push(
+                self.attachment_manager
+                    .attach(&mut merged, &name, data, modified),
+            );
+        }
+        if !attachments.is_empty() {
+            self.attachment_manager
+                .set_embedded_files(&mut merged, attachments)?;
+        }
+
+        // Combine the structure trees; sheets of an N-up or booklet layout
+        // don't match any
+        if config.layout.is_some() {
+            self.structure_merger.remove_tree(&mut merged)?;
+        } else if !structure_trees.is_empty() {
+            self.structure_merger
+                .merge_trees(&mut merged, structure_trees)?;
+        }
+
+        // Combine the layers; the first input's properties are kept as
+        // they are when no other input has layers
+        if layers.len() > 1 || (config.group_layers && !layers.is_empty()) {
+            self.layer_merger
+                .merge_layers(&mut merged, layers, config.group_layers)?;
         }
 
-        // Add bookmarks if requested
+        // Put a separator page before each input; it counts as the input's
+        // first page, so bookmarks and duplex padding include it
+        if config.separators {
+            let separators: Vec<SeparatorInfo> = loaded_pdfs
+                .iter()
+                .zip(&input_pages)
+                .map(|(loaded, pages)| SeparatorInfo {
+                    path: &loaded.path,
+                    title: self.metadata_manager.get_metadata(&loaded.document).title,
+                    page_count: pages.len(),
+                    size: pages.first().map_or((612.0, 792.0), |&page_id| {
+                        displayed_size(page_box(&merged, page_id), page_rotation(&merged, page_id))
+                    }),
+                })
+                .collect();
+            let separator_ids = self
+                .separator_generator
+                .add_separators(&mut merged, &separators);
+
+            for (index, (pages, separator_id)) in
+                input_pages.iter_mut().zip(separator_ids).enumerate()
+            {
+                pages.insert(0, separator_id);
+                sources.insert(separator_id, (index, None));
+            }
+            self.page_extractor
+                .update_page_tree(&mut merged, &input_pages.concat())?;
+        }
+
+        if config.merge_mode == MergeMode::Collate {
+            let order = collate_pages(&input_pages);
+            self.page_extractor.update_page_tree(&mut merged, &order)?;
+        }
+
+        // Insert blank pages; inputs keep their first pages, so bookmarks
+        // still land on them
+        let mut blank_pages_added = 0;
+        if config.duplex || !config.blank_pages.is_empty() {
+            let pages: Vec<ObjectId> = merged.get_pages().into_values().collect();
+            let input_ends = if config.duplex {
+                input_ends(&input_pages)
+            } else {
+                Vec::new()
+            };
+            let order =
+                self.insert_blank_pages(&mut merged, &pages, &config.blank_pages, &input_ends)?;
+            blank_pages_added = order.len() - pages.len();
+            self.page_extractor.update_page_tree(&mut merged, &order)?;
+        }
+
+        // Add bookmarks if requested, pointing at each input's first page
         if config.bookmarks {
-            let file_paths = loaded_pdfs
+            let items: Vec<(&Path, ObjectId)> = loaded_pdfs
                 .iter()
-                .map(|p| p.path.as_path())
-                .collect::<Vec<_>>();
+                .zip(&input_pages)
+                .filter_map(|(loaded, pages)| Some((loaded.path.as_path(), *pages.first()?)))
+                .collect();
             self.bookmark_manager
-                .add_bookmarks_for_files(&mut merged, file_paths.as_slice())?;
+                .add_bookmarks_at_pages(&mut merged, &items)?;
+        }
+
+        // Edit page boxes, numbering pages as they appear in the output.
+        // The CropBox is what normalization scales to the paper, while
+        // normalization drops the TrimBox and BleedBox, so those are edited
+        // on the normalized pages.
+        let (crop_edits, print_edits): (Vec<_>, Vec<_>) = config
+            .box_edits
+            .iter()
+            .partition(|edit| edit.kind == BoxKind::Crop);
+        for edit in crop_edits {
+            self.box_editor.apply(&mut merged, edit)?;
+        }
+
+        // Bring all pages to the same paper size
+        if let Some(page_size) = config.page_size {
+            self.normalizer
+                .normalize(&mut merged, page_size, config.page_fit)?;
+        }
+
+        for edit in print_edits {
+            self.box_editor.apply(&mut merged, edit)?;
+        }
+
+        // Draw watermarks, under any stamps
+        for watermark in &config.watermarks {
+            match watermark.content {
+                WatermarkContent::Text(ref text) => {
+                    self.watermarker.add_text(&mut merged, watermark, text)?;
+                }
+                WatermarkContent::Page { ref path, page } => {
+                    let source = self.reader.load(path).await?;
+                    self.watermarker.add_page(
+                        &mut merged,
+                        watermark,
+                        path,
+                        &source.document,
+                        page,
+                    )?;
+                }
+            }
+        }
+
+        // Where each page came from, before pages are laid out onto sheets
+        let page_sources: Vec<PageSource> = merged
+            .get_pages()
+            .into_values()
+            .map(|page_id| sources.get(&page_id).copied())
+            .collect();
+
+        let bates_log = match config.bates {
+            Some(ref bates) => {
+                let files: Vec<PathBuf> = loaded_pdfs.iter().map(|p| p.path.clone()).collect();
+                bates_ranges(bates, &page_sources, &files)
+            }
+            None => Vec::new(),
+        };
+
+        // Label pages; sheets of an N-up or booklet layout get no labels
+        let page_labels = if config.layout.is_some() {
+            &PageLabels::Remove
+        } else {
+            &config.page_labels
+        };
+        let inputs: Vec<(&Path, &Document)> = loaded_pdfs
+            .iter()
+            .map(|loaded| (loaded.path.as_path(), &loaded.document))
+            .collect();
+        self.labeler
+            .apply(&mut merged, page_labels, &page_sources, &inputs)?;
+
+        // Stamp pages as they will be numbered, before they are laid out
+        // onto sheets. Bates numbers get a stamp of their own unless a
+        // stamp already shows them.
+        let mut stamps = config.stamps.clone();
+        if let Some(ref bates) = config.bates
+            && !stamps.iter().any(|stamp| stamp.text.contains("{bates}"))
+        {
+            stamps.push(Stamp {
+                font_size: bates.font_size,
+                ..Stamp::new("{bates}", bates.position)
+            });
+        }
+
+        if !stamps.is_empty() {
+            let mut context = StampContext::new();
+            context.bates = config.bates.clone();
+            for (loaded, pages) in loaded_pdfs.iter().zip(&input_pages) {
+                let file = loaded.path.file_name().map_or_else(
+                    || loaded.path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+                for &page_id in pages {
+                    context.files.insert(page_id, file.clone());
+                }
+            }
+            self.stamper.stamp(&mut merged, &stamps, &context)?;
+        }
+
+        // Lay out pages onto sheets; bookmarks follow their pages
+        if let Some(layout) = config.layout {
+            self.imposer.impose(&mut merged, layout)?;
+        }
+
+        let newest_version = loaded_pdfs.iter().filter_map(|loaded| loaded.version).max();
+        let unsupported_features = self.finish_document(&mut merged, config, newest_version)?;
+
+        Ok(MergedDocument {
+            document: merged,
+            blank_pages_added,
+            bates_log,
+            sanitize_reports,
+            unsupported_features,
+        })
+    }
+
+    /// Build a PDF Portfolio with each input, and each file to attach,
+    /// embedded unchanged.
+    async fn build_portfolio(
+        &self,
+        loaded_pdfs: &[LoadedPdf],
+        config: &Config,
+    ) -> Result<Document> {
+        let paths = loaded_pdfs
+            .iter()
+            .map(|loaded| (&loaded.path, Some(loaded.page_count)))
+            .chain(config.attachments.iter().map(|path| (path, None)));
+
+        let mut files = Vec::new();
+        for (path, page_count) in paths {
+            let data = tokio::fs::read(path)
+                .await
+                .map_err(|e| PdfCatError::FileNotAccessible {
+                    path: path.clone(),
+                    source: e,
+                })?;
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            files.push(PortfolioFile {
+                path,
+                data,
+                modified,
+                page_count,
+            });
         }
 
+        let title = config.metadata.title.as_deref().unwrap_or("Portfolio");
+        self.portfolio_builder.build(title, &files)
+    }
+
+    /// Set metadata and the PDF version, compress and renumber a finished
+    /// document.
+    ///
+    /// `newest_version` is the newest version of the inputs, if they
+    /// matter to the document.
+    ///
+    /// # Returns
+    ///
+    /// Features of the document that its PDF version doesn't support.
+    fn finish_document(
+        &self,
+        doc: &mut Document,
+        config: &Config,
+        newest_version: Option<PdfVersion>,
+    ) -> Result<Vec<VersionFeature>> {
         // Set metadata if specified
         if !config.metadata.is_empty() {
-            self.metadata_manager
-                .set_metadata(&mut merged, &config.metadata)?;
+            self.metadata_manager.set_metadata(doc, &config.metadata)?;
+        }
+
+        // Show the outline that was added, unless told otherwise
+        let mut viewer = config.viewer.clone();
+        if config.bookmarks && viewer.page_mode.is_none() {
+            viewer.page_mode = Some(PageMode::Outline);
+        }
+        if !viewer.is_empty() {
+            self.viewer_configurator.configure(doc, &viewer)?;
         }
 
+        // Declare the newest version of the inputs and of the features
+        // used, unless a version is given
+        let features = self.version_manager.features(doc);
+        let version = config.pdf_version.unwrap_or_else(|| {
+            [
+                self.version_manager.version(doc),
+                newest_version,
+                features.iter().map(|feature| feature.version).max(),
+            ]
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or_default()
+        });
+        self.version_manager.set_version(doc, version)?;
+        let unsupported_features = features
+            .into_iter()
+            .filter(|feature| feature.version > version)
+            .collect();
+
         // Apply compression based on config
         match config.compression {
             crate::config::CompressionLevel::None => {
                 // No compression
             }
             crate::config::CompressionLevel::Standard => {
-                merged.compress();
+                doc.compress();
             }
             crate::config::CompressionLevel::Maximum => {
-                merged.compress();
+                doc.compress();
                 // Additional optimizations for maximum compression
-                merged.prune_objects();
+                doc.prune_objects();
             }
         }
 
         // Always renumber for consistency
-        merged.renumber_objects();
+        doc.renumber_objects();
+
+        Ok(unsupported_features)
+    }
+
+    /// Build a page order with blank pages inserted.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document the blank pages are added to
+    /// * `pages` - Pages in output order
+    /// * `after` - Page numbers to insert a blank page after (0 = before the
+    ///   first page)
+    /// * `input_ends` - Page numbers where an input ends; a blank page is
+    ///   added there if the next input would otherwise start on an even page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a position is beyond the last page.
+    fn insert_blank_pages(
+        &self,
+        doc: &mut Document,
+        pages: &[ObjectId],
+        after: &[usize],
+        input_ends: &[usize],
+    ) -> Result<Vec<ObjectId>> {
+        if let Some(&position) = after.iter().find(|&&position| position > pages.len()) {
+            return Err(PdfCatError::invalid_config(format!(
+                "Cannot insert a blank page after page {position}: the merged document has {} pages",
+                pages.len()
+            )));
+        }
+
+        let mut order = Vec::new();
+        if pages.is_empty() {
+            return Ok(order);
+        }
+
+        for position in 0..=pages.len() {
+            if position > 0 {
+                order.push(pages[position - 1]);
+            }
+
+            // Blank pages match the page before them, or the first page
+            let like = pages[position.saturating_sub(1)];
+            let explicit = after.iter().filter(|&&p| p == position).count();
+            for _ in 0..explicit {
+                order.push(self.page_extractor.blank_page(doc, like)?);
+            }
+            if input_ends.contains(&position) && order.len() % 2 == 1 {
+                order.push(self.page_extractor.blank_page(doc, like)?);
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Apply page selection, rotation and reversal to a loaded document.
+    ///
+    /// Returns the prepared document and, for each of its pages, the page
+    /// number it had in the loaded document.
+    fn prepare_document(
+        &self,
+        loaded: &LoadedPdf,
+        reverse: bool,
+        config: &Config,
+    ) -> Result<(Document, Vec<u32>)> {
+        let mut doc = loaded.document.clone();
+        let page_count = doc.get_pages().len() as u32;
+
+        // Extract pages if page range specified
+        let mut pages: Vec<u32> = match config.page_range {
+            Some(ref page_range) => {
+                let pages =
+                    page_range.resolve(&loaded.path, page_count, config.page_range_policy)?;
+                doc = self.page_extractor.select_pages(&doc, &pages)?;
+                pages
+            }
+            None => (1..=page_count).collect(),
+        };
+
+        // Apply rotation if specified
+        if let Some(rotation) = config.rotation {
+            self.page_extractor.rotate_all_pages(&mut doc, rotation)?;
+        }
+
+        // Flatten first, so that removing stamps keeps the flattened ones
+        if config.flatten {
+            self.annotation_editor.flatten(&mut doc)?;
+        }
+        if let Some(ref filter) = config.remove_annotations {
+            self.annotation_editor.remove(&mut doc, filter)?;
+        }
+
+        if reverse {
+            let order: Vec<u32> = (1..=doc.get_pages().len() as u32).rev().collect();
+            doc = self.page_extractor.select_pages(&doc, &order)?;
+            pages.reverse();
+        }
 
-        Ok(merged)
+        Ok((doc, pages))
     }
 
     /// Add pages to the merged document's page tree.
@@ -317,6 +909,31 @@ impl Default for Merger {
     }
 }
 
+/// Page numbers at which each input but the last ends.
+fn input_ends(input_pages: &[Vec<ObjectId>]) -> Vec<usize> {
+    input_pages
+        .iter()
+        .scan(0, |end, pages| {
+            *end += pages.len();
+            Some(*end)
+        })
+        .take(input_pages.len().saturating_sub(1))
+        .collect()
+}
+
+/// Interleave the pages of several inputs.
+///
+/// Takes the first page of each input, then the second page of each
+/// input, and so on. Inputs that run out of pages are skipped.
+fn collate_pages(inputs: &[Vec<ObjectId>]) -> Vec<ObjectId> {
+    let longest = inputs.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..longest)
+        .flat_map(|index| inputs.iter().filter_map(move |pages| pages.get(index)))
+        .copied()
+        .collect()
+}
+
 /// Format file size as human-readable string.
 fn format_file_size(size: u64) -> String {
     const KB: u64 = 1024;
@@ -337,7 +954,10 @@ fn format_file_size(size: u64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{CompressionLevel, Metadata, OverwriteMode};
+    use crate::config::{
+        CompressionLevel, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit, PageLabels,
+        PageRangePolicy, ViewerSettings,
+    };
     use std::io::Write;
     use tempfile::TempDir;
 
@@ -363,9 +983,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         }
     }
 
@@ -437,6 +1081,108 @@ mod tests {
         assert!(!result.unwrap().statistics.compressed);
     }
 
+    #[test]
+    fn test_collate_pages() {
+        let front = vec![(1, 0), (3, 0), (5, 0)];
+        let back = vec![(2, 0), (4, 0)];
+        let extra = vec![(9, 0)];
+
+        assert_eq!(
+            collate_pages(&[front, back, extra]),
+            vec![(1, 0), (2, 0), (9, 0), (3, 0), (4, 0), (5, 0)]
+        );
+        assert!(collate_pages(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_collate_reversed() {
+        let temp_dir = TempDir::new().unwrap();
+        let front = create_test_pdf(&temp_dir, "front.pdf");
+        let back = create_test_pdf(&temp_dir, "back.pdf");
+        let output = temp_dir.path().join("output.pdf");
+
+        let mut config = create_test_config(vec![front, back], output);
+        config.merge_mode = MergeMode::Collate;
+        config.reverse_inputs = vec![2];
+        config.bookmarks = true;
+
+        let result = Merger::new().merge(&config).await.unwrap();
+        assert_eq!(result.statistics.total_pages, 2);
+
+        let toc = result.document.get_toc().unwrap();
+        let pages: Vec<usize> = toc.toc.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_input_ends() {
+        let inputs = vec![vec![(1, 0)], vec![(2, 0), (3, 0)], vec![(4, 0)]];
+        assert_eq!(input_ends(&inputs), vec![1, 3]);
+        assert!(input_ends(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_duplex_blank_pages() {
+        let temp_dir = TempDir::new().unwrap();
+        let inputs: Vec<PathBuf> = ["a.pdf", "b.pdf", "c.pdf"]
+            .iter()
+            .map(|name| create_test_pdf(&temp_dir, name))
+            .collect();
+        let output = temp_dir.path().join("output.pdf");
+
+        let mut config = create_test_config(inputs, output);
+        config.duplex = true;
+        config.bookmarks = true;
+
+        let result = Merger::new().merge(&config).await.unwrap();
+        assert_eq!(result.statistics.total_pages, 5);
+        assert_eq!(result.statistics.blank_pages_added, 2);
+
+        let toc = result.document.get_toc().unwrap();
+        let pages: Vec<usize> = toc.toc.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![1, 3, 5]);
+
+        // Explicit positions count the merged pages without blanks
+        config.duplex = false;
+        config.blank_pages = vec![0, 3, 3];
+        let result = Merger::new().merge(&config).await.unwrap();
+        assert_eq!(result.statistics.total_pages, 6);
+
+        let toc = result.document.get_toc().unwrap();
+        let pages: Vec<usize> = toc.toc.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![2, 3, 4]);
+
+        config.blank_pages = vec![4];
+        assert!(Merger::new().merge(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merge_with_separators() {
+        let temp_dir = TempDir::new().unwrap();
+        let inputs: Vec<PathBuf> = ["a.pdf", "b.pdf"]
+            .iter()
+            .map(|name| create_test_pdf(&temp_dir, name))
+            .collect();
+        let output = temp_dir.path().join("output.pdf");
+
+        let mut config = create_test_config(inputs, output);
+        config.separators = true;
+        config.bookmarks = true;
+        config.compression = crate::config::CompressionLevel::None;
+
+        let result = Merger::new().merge(&config).await.unwrap();
+        assert_eq!(result.statistics.total_pages, 4);
+
+        let pages: Vec<ObjectId> = result.document.get_pages().into_values().collect();
+        let separator = result.document.get_page_content(pages[2]).unwrap();
+        assert!(String::from_utf8_lossy(&separator).contains("(b.pdf) Tj"));
+
+        // Bookmarks point at the separators
+        let toc = result.document.get_toc().unwrap();
+        let numbers: Vec<usize> = toc.toc.iter().map(|entry| entry.page).collect();
+        assert_eq!(numbers, vec![1, 3]);
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(format_file_size(500), "500 bytes");
@@ -454,7 +1200,9 @@ mod tests {
             load_time: Duration::from_secs(1),
             input_size: 1024 * 1024,
             bookmarks_added: 3,
+            blank_pages_added: 1,
             compressed: true,
+            pdf_version: PdfVersion::new(1, 7),
         };
 
         assert_eq!(stats.format_input_size(), "1.00 MB");
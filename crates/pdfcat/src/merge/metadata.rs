@@ -177,12 +177,18 @@ impl MetadataManager {
         })
     }
 
-    /// Clear all metadata from a document.
+    /// Clear all metadata from a document: the Info dictionary and the
+    /// catalog's XMP metadata stream.
     pub fn clear_metadata(&self, doc: &mut Document) -> Result<()> {
         if let Ok(info_ref) = doc.trailer.get(b"Info").and_then(|i| i.as_reference()) {
             doc.objects.remove(&info_ref);
             doc.trailer.remove(b"Info");
         }
+        if let Ok(catalog) = doc.catalog_mut()
+            && let Some(Object::Reference(xmp_ref)) = catalog.remove(b"Metadata")
+        {
+            doc.objects.remove(&xmp_ref);
+        }
         Ok(())
     }
 
@@ -201,7 +207,7 @@ impl Default for MetadataManager {
 /// Format a SystemTime as a PDF date string.
 ///
 /// PDF date format: D:YYYYMMDDHHmmSSOHH'mm
-fn format_pdf_date(time: SystemTime) -> String {
+pub(crate) fn format_pdf_date(time: SystemTime) -> String {
     use std::time::UNIX_EPOCH;
 
     let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
@@ -322,9 +328,17 @@ mod tests {
         manager.set_metadata(&mut doc, &metadata).unwrap();
         assert!(manager.has_metadata(&doc));
 
+        let xmp_id = doc.add_object(lopdf::Stream::new(
+            dictionary! { "Type" => "Metadata", "Subtype" => "XML" },
+            b"<x:xmpmeta/>".to_vec(),
+        ));
+        doc.catalog_mut().unwrap().set("Metadata", xmp_id);
+
         let result = manager.clear_metadata(&mut doc);
         assert!(result.is_ok());
         assert!(!manager.has_metadata(&doc));
+        assert!(!doc.catalog().unwrap().has(b"Metadata"));
+        assert!(doc.get_object(xmp_id).is_err());
     }
 
     #[test]
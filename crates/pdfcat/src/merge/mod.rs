@@ -28,9 +28,33 @@
 //! #   compression: pdfcat::config::CompressionLevel::Standard,
 //! #   metadata: pdfcat::config::Metadata::default(),
 //! #   continue_on_error: false,
+//! #   repair: false,
 //! #   jobs: None,
 //! #   page_range: None,
+//! #   page_range_policy: pdfcat::config::PageRangePolicy::Lenient,
 //! #   rotation: None,
+//! #   merge_mode: pdfcat::config::MergeMode::Concatenate,
+//! #   reverse_inputs: Vec::new(),
+//! #   duplex: false,
+//! #   blank_pages: Vec::new(),
+//! #   separators: false,
+//! #   layout: None,
+//! #   box_edits: Vec::new(),
+//! #   page_size: None,
+//! #   page_fit: pdfcat::config::PageFit::Fit,
+//! #   stamps: Vec::new(),
+//! #   bates: None,
+//! #   bates_log: None,
+//! #   watermarks: Vec::new(),
+//! #   page_labels: pdfcat::config::PageLabels::Keep,
+//! #   attachments: Vec::new(),
+//! #   group_layers: false,
+//! #   remove_annotations: None,
+//! #   flatten: false,
+//! #   sanitize: false,
+//! #   viewer: pdfcat::config::ViewerSettings::default(),
+//! #   pdf_version: None,
+//! #   output_format: pdfcat::config::OutputFormat::Text,
 //! };
 //!
 //! let merger = Merger::new();
@@ -40,15 +64,50 @@
 //! # }
 //! ```
 
+pub mod annotations;
+pub mod attachments;
+pub mod bates;
 pub mod bookmarks;
+pub mod boxes;
+mod font;
+pub mod labels;
+pub mod layers;
+pub mod layout;
 pub mod merger;
 pub mod metadata;
+pub mod normalize;
 pub mod pages;
+pub mod portfolio;
+pub mod sanitize;
+pub mod separator;
+pub mod stamp;
+pub mod structure;
+#[cfg(test)]
+mod test_utils;
+pub(crate) mod trees;
+pub mod version;
+pub mod viewer;
+pub mod watermark;
 
+pub use annotations::AnnotationEditor;
+pub use attachments::AttachmentManager;
 pub use bookmarks::BookmarkManager;
+pub use boxes::BoxEditor;
+pub use labels::PageLabeler;
+pub use layers::LayerMerger;
+pub use layout::Imposer;
 pub use merger::{MergeResult, MergeStatistics, Merger};
 pub use metadata::MetadataManager;
+pub use normalize::PageNormalizer;
 pub use pages::{PageExtractor, PageRotation};
+pub use portfolio::PortfolioBuilder;
+pub use sanitize::{SanitizeReport, Sanitizer};
+pub use separator::SeparatorGenerator;
+pub use stamp::Stamper;
+pub use structure::StructureMerger;
+pub use version::{VersionFeature, VersionManager};
+pub use viewer::ViewerConfigurator;
+pub use watermark::Watermarker;
 
 use crate::config::Config;
 use crate::error::Result;
@@ -91,7 +150,10 @@ pub async fn merge_pdfs(config: &Config) -> Result<(Document, MergeStatistics)>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{CompressionLevel, Metadata, OverwriteMode};
+    use crate::config::{
+        CompressionLevel, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit, PageLabels,
+        PageRangePolicy, ViewerSettings,
+    };
     use std::path::PathBuf;
 
     #[expect(unused)]
@@ -107,9 +169,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         }
     }
 
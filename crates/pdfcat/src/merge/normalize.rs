@@ -0,0 +1,291 @@
+//! Page size normalization.
+//!
+//! This module brings every page of a document to a common paper size:
+//! - Each page's content is wrapped in a transform that scales and centers
+//!   it on the new page, clipped to the page's old visible box
+//! - `/Rotate` is folded into the transform, so pages display as before
+//! - `MediaBox` and `CropBox` are replaced; `BleedBox`, `TrimBox` and
+//!   `ArtBox` no longer apply and are removed
+//! - Annotation rectangles are moved along with the content
+
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+
+use crate::config::{PageFit, PageSize};
+use crate::error::{PdfCatError, Result};
+use crate::merge::layout::{
+    Rect, content_streams, displayed_size, page_box, page_rotation, rect, rect_object,
+    scaled_placement_matrix,
+};
+
+/// Normalizer that resizes pages to a target paper size.
+#[derive(Debug, Clone)]
+pub struct PageNormalizer;
+
+impl PageNormalizer {
+    /// Create a new page normalizer.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Resize every page of a document to `size`.
+    ///
+    /// Landscape pages are placed on the landscape form of `size`. Pages
+    /// keep their aspect ratio and are centered on the new page. Pages
+    /// that already have the target size and no rotation are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to normalize
+    /// * `size` - Target paper size
+    /// * `fit` - Whether pages are scaled to fill the paper or only padded
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::normalize::PageNormalizer;
+    /// # use pdfcat::config::{PageFit, PageSize};
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let normalizer = PageNormalizer::new();
+    /// normalizer.normalize(&mut doc, PageSize::A4, PageFit::Fit)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalize(&self, doc: &mut Document, size: PageSize, fit: PageFit) -> Result<()> {
+        let max_scale = match fit {
+            PageFit::Fit => f32::INFINITY,
+            PageFit::Pad => 1.0,
+        };
+
+        for page_id in doc.get_pages().into_values() {
+            let bbox = page_box(doc, page_id);
+            let rotation = page_rotation(doc, page_id);
+            let (width, height) = displayed_size(bbox, rotation);
+            let (target_width, target_height) = size.oriented(width, height);
+            let target = [0.0, 0.0, target_width, target_height];
+
+            if rotation == 0 && bbox == target {
+                continue;
+            }
+
+            let matrix = scaled_placement_matrix(bbox, rotation, target, max_scale);
+            self.wrap_contents(doc, page_id, matrix, bbox)?;
+            self.move_annotations(doc, page_id, matrix);
+
+            let page = doc.get_dictionary_mut(page_id).map_err(|err| {
+                PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}"))
+            })?;
+            page.set("MediaBox", rect_object(target));
+            page.set("CropBox", rect_object(target));
+            page.set("Rotate", 0);
+            for key in [b"BleedBox".as_slice(), b"TrimBox", b"ArtBox"] {
+                page.remove(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Surround a page's content streams with streams that apply `matrix`
+    /// and clip to `clip`, given in the page's old user space.
+    fn wrap_contents(
+        &self,
+        doc: &mut Document,
+        page_id: ObjectId,
+        matrix: [f32; 6],
+        clip: Rect,
+    ) -> Result<()> {
+        let mut streams = content_streams(doc, page_id)?;
+
+        let prefix = format!(
+            "q {} cm {} {} {} {} re W n\n",
+            matrix.map(|value| value.to_string()).join(" "),
+            clip[0],
+            clip[1],
+            clip[2] - clip[0],
+            clip[3] - clip[1]
+        );
+        let prefix_id = doc.add_object(Stream::new(Dictionary::new(), prefix.into_bytes()));
+        let suffix_id = doc.add_object(Stream::new(Dictionary::new(), b"\nQ\n".to_vec()));
+
+        streams.insert(0, Object::Reference(prefix_id));
+        streams.push(Object::Reference(suffix_id));
+
+        let page = doc.get_dictionary_mut(page_id).map_err(|err| {
+            PdfCatError::merge_failed(format!("Failed to get page dictionary: {err}"))
+        })?;
+        page.set("Contents", Object::Array(streams));
+
+        Ok(())
+    }
+
+    /// Move the rectangles of a page's annotations by `matrix`.
+    fn move_annotations(&self, doc: &mut Document, page_id: ObjectId, matrix: [f32; 6]) {
+        let Ok(page) = doc.get_dictionary(page_id) else {
+            return;
+        };
+        let annots = match page.get(b"Annots") {
+            Ok(Object::Reference(id)) => doc.get_object(*id).and_then(Object::as_array).cloned(),
+            Ok(Object::Array(items)) => Ok(items.clone()),
+            _ => return,
+        };
+        let Ok(annots) = annots else {
+            return;
+        };
+
+        for annot_id in annots.iter().filter_map(|obj| obj.as_reference().ok()) {
+            let Some(moved) = doc
+                .get_dictionary(annot_id)
+                .ok()
+                .and_then(|annot| annot.get(b"Rect").ok())
+                .and_then(|obj| rect(doc, obj))
+                .map(|old| transform_rect(matrix, old))
+            else {
+                continue;
+            };
+
+            if let Ok(annot) = doc.get_dictionary_mut(annot_id) {
+                annot.set("Rect", rect_object(moved));
+            }
+        }
+    }
+}
+
+impl Default for PageNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bounding box of a rectangle after applying a transform.
+fn transform_rect(matrix: [f32; 6], rect: Rect) -> Rect {
+    let corners = [
+        (rect[0], rect[1]),
+        (rect[2], rect[1]),
+        (rect[0], rect[3]),
+        (rect[2], rect[3]),
+    ]
+    .map(|(x, y)| {
+        (
+            matrix[0] * x + matrix[2] * y + matrix[4],
+            matrix[1] * x + matrix[3] * y + matrix[5],
+        )
+    });
+
+    corners.iter().fold(
+        [f32::MAX, f32::MAX, f32::MIN, f32::MIN],
+        |[x0, y0, x1, y1], &(x, y)| [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::test_utils;
+    use lopdf::dictionary;
+
+    fn create_doc(sizes: &[(i64, i64, i64)]) -> Document {
+        let mut doc = test_utils::create_doc(sizes, b"0 0 m 10 10 l S");
+
+        for (_, page_id) in doc.get_pages() {
+            let annot_id = doc.add_object(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Square",
+                "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            });
+            let page = doc.get_dictionary_mut(page_id).unwrap();
+            let media_box = page.get(b"MediaBox").unwrap().clone();
+            page.set("TrimBox", media_box);
+            page.set("Annots", vec![annot_id.into()]);
+        }
+
+        doc
+    }
+
+    fn annot_rect(doc: &Document, page_id: ObjectId) -> Rect {
+        let page = doc.get_dictionary(page_id).unwrap();
+        let annot_id = page.get(b"Annots").unwrap().as_array().unwrap()[0]
+            .as_reference()
+            .unwrap();
+        let annot = doc.get_dictionary(annot_id).unwrap();
+        rect(doc, annot.get(b"Rect").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_fit() {
+        // Letter portrait, A3 landscape, and Letter rotated to landscape
+        let mut doc = create_doc(&[(612, 792, 0), (1191, 842, 0), (612, 792, 90)]);
+        PageNormalizer::new()
+            .normalize(&mut doc, PageSize::A4, PageFit::Fit)
+            .unwrap();
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(page_box(&doc, pages[0]), [0.0, 0.0, 595.0, 842.0]);
+        assert_eq!(page_box(&doc, pages[1]), [0.0, 0.0, 842.0, 595.0]);
+        assert_eq!(page_box(&doc, pages[2]), [0.0, 0.0, 842.0, 595.0]);
+
+        for &page_id in &pages {
+            assert_eq!(page_rotation(&doc, page_id), 0);
+            let page = doc.get_dictionary(page_id).unwrap();
+            assert!(!page.has(b"TrimBox"));
+        }
+
+        let content = String::from_utf8(doc.get_page_content(pages[0]).unwrap()).unwrap();
+        assert!(content.starts_with("q "));
+        assert!(content.contains("0 0 m 10 10 l S"));
+        assert!(content.trim_end().ends_with('Q'));
+
+        // Letter is scaled by 595/612 and centered vertically
+        let scale = 595.0 / 612.0;
+        let offset = (842.0 - 792.0 * scale) / 2.0;
+        let moved = annot_rect(&doc, pages[0]);
+        assert!((moved[0] - 0.0).abs() < 0.01);
+        assert!((moved[1] - offset).abs() < 0.01);
+        assert!((moved[2] - 10.0 * scale).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_normalize_pad() {
+        let mut doc = create_doc(&[(420, 595, 0), (842, 1191, 0)]);
+        PageNormalizer::new()
+            .normalize(&mut doc, PageSize::A4, PageFit::Pad)
+            .unwrap();
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+
+        // A5 keeps its size, centered on A4
+        let moved = annot_rect(&doc, pages[0]);
+        assert_eq!(moved, [87.5, 123.5, 97.5, 133.5]);
+
+        // A3 is scaled down to fit
+        let moved = annot_rect(&doc, pages[1]);
+        assert!(moved[2] - moved[0] < 10.0);
+    }
+
+    #[test]
+    fn test_normalize_leaves_matching_pages() {
+        let mut doc = create_doc(&[(595, 842, 0)]);
+        PageNormalizer::new()
+            .normalize(&mut doc, PageSize::A4, PageFit::Fit)
+            .unwrap();
+
+        let page_id = *doc.get_pages().values().next().unwrap();
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert!(page.get(b"Contents").unwrap().as_reference().is_ok());
+        assert!(page.has(b"TrimBox"));
+    }
+
+    #[test]
+    fn test_transform_rect() {
+        // Rotation by 90 degrees clockwise, then shifted up
+        let matrix = [0.0, -1.0, 1.0, 0.0, 0.0, 100.0];
+        assert_eq!(
+            transform_rect(matrix, [0.0, 0.0, 20.0, 10.0]),
+            [0.0, 80.0, 10.0, 100.0]
+        );
+    }
+}
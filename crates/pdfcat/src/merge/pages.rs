@@ -3,11 +3,15 @@
 //! This module handles page-level operations including:
 //! - Page extraction by range
 //! - Page rotation
+//! - Blank page creation
 //! - Page tree manipulation
 
-use crate::config::{PageRange, Rotation};
+use crate::config::{PageRange, PageRangePolicy, Rotation};
 use crate::error::{PdfCatError, Result};
-use lopdf::{Document, Object, ObjectId};
+use crate::io::repair::inherited_attributes;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::BTreeSet;
+use std::path::Path;
 
 /// Page rotation angles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -56,63 +60,95 @@ impl PageExtractor {
 
     /// Extract specific pages from a document.
     ///
-    /// Creates a new document containing only the specified pages.
+    /// Creates a new document containing only the specified pages, in the
+    /// order the range lists them.
     ///
     /// # Arguments
     ///
+    /// * `path` - Path the document was loaded from, used in error messages
     /// * `doc` - Source document
     /// * `page_range` - Range of pages to extract
+    /// * `policy` - How to treat pages beyond the end of the document
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - Requested pages don't exist
+    /// - The range selects no pages, or refers to missing pages under
+    ///   [`PageRangePolicy::Strict`]
     /// - Page tree manipulation fails
     ///
     /// # Examples
     ///
     /// ```no_run
     /// # use pdfcat::merge::pages::PageExtractor;
-    /// # use pdfcat::config::PageRange;
+    /// # use pdfcat::config::{PageRange, PageRangePolicy};
     /// # use lopdf::Document;
+    /// # use std::path::Path;
     /// # fn example(doc: Document) -> Result<(), Box<dyn std::error::Error>> {
     /// let extractor = PageExtractor::new();
     /// let page_range = PageRange::parse("1-5,10")?;
-    /// let extracted = extractor.extract_pages(&doc, &page_range)?;
+    /// let extracted = extractor.extract_pages(
+    ///     Path::new("input.pdf"),
+    ///     &doc,
+    ///     &page_range,
+    ///     PageRangePolicy::Strict,
+    /// )?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn extract_pages(&self, doc: &Document, page_range: &PageRange) -> Result<Document> {
+    pub fn extract_pages(
+        &self,
+        path: &Path,
+        doc: &Document,
+        page_range: &PageRange,
+        policy: PageRangePolicy,
+    ) -> Result<Document> {
+        let max_pages = doc.get_pages().len() as u32;
+        let requested_pages = page_range.resolve(path, max_pages, policy)?;
+
+        self.select_pages(doc, &requested_pages)
+    }
+
+    /// Create a new document containing the given pages in the given order.
+    ///
+    /// A page listed more than once is copied for each repeat.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Source document
+    /// * `pages` - 1-indexed page numbers, all of which must exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pages` is empty, a page does not exist, or page
+    /// tree manipulation fails.
+    pub fn select_pages(&self, doc: &Document, pages: &[u32]) -> Result<Document> {
         let all_pages = doc.get_pages();
-        let max_pages = all_pages.len() as u32;
-        let requested_pages = page_range.to_pages(max_pages);
 
-        if requested_pages.is_empty() {
+        if pages.is_empty() {
             return Err(PdfCatError::merge_failed("No pages in range"));
         }
 
-        // Verify all requested pages exist
-        for page_num in &requested_pages {
-            if *page_num > max_pages {
-                return Err(PdfCatError::InvalidPageRange {
-                    path: std::path::PathBuf::from("document"),
-                    range: format!("{page_range:?}"),
-                    total_pages: max_pages as usize,
-                });
-            }
-        }
-
         // Create a new document with extracted pages
         let mut new_doc = doc.clone();
 
-        // Get page IDs for requested pages (converting 1-indexed to 0-indexed)
-        let page_ids: Vec<ObjectId> = requested_pages
-            .iter()
-            .filter_map(|&page_num| all_pages.get(&(page_num)).copied())
-            .collect();
+        // Get page IDs for requested pages in the requested order. A page
+        // that is requested again gets its own copy in the page tree.
+        let mut seen = BTreeSet::new();
+        let mut page_ids = Vec::with_capacity(pages.len());
+        for page_num in pages {
+            let Some(&page_id) = all_pages.get(page_num) else {
+                return Err(PdfCatError::merge_failed(format!(
+                    "Page {page_num} does not exist (document has {} pages)",
+                    all_pages.len()
+                )));
+            };
 
-        if page_ids.is_empty() {
-            return Err(PdfCatError::merge_failed("Failed to extract pages"));
+            if seen.insert(page_id) {
+                page_ids.push(page_id);
+            } else {
+                page_ids.push(self.duplicate_page(&mut new_doc, page_id)?);
+            }
         }
 
         // Update the page tree to only include selected pages
@@ -121,8 +157,88 @@ impl PageExtractor {
         Ok(new_doc)
     }
 
+    /// Add a copy of a page to the document and return its ID.
+    ///
+    /// Content streams and resources are shared with the original page.
+    /// Annotations are copied, since an annotation may only belong to one
+    /// page, except form field widgets, which belong to their field, and
+    /// popups, which belong to the annotation they open from. The copy has
+    /// no structure tree keys, since the tree describes the original.
+    fn duplicate_page(&self, doc: &mut Document, page_id: ObjectId) -> Result<ObjectId> {
+        let mut page = doc
+            .get_dictionary(page_id)
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get page: {err}")))?
+            .clone();
+        page.remove(b"StructParents");
+
+        let new_id = doc.new_object_id();
+
+        let annots = page
+            .get(b"Annots")
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+            .cloned()
+            .ok();
+
+        if let Some(annots) = annots {
+            let copied: Vec<Object> = annots
+                .iter()
+                .filter_map(|annot| {
+                    let mut dict = doc.dereference(annot).ok()?.1.as_dict().ok()?.clone();
+                    let subtype = dict.get(b"Subtype").and_then(Object::as_name).ok();
+                    if matches!(subtype, Some(b"Widget" | b"Popup")) {
+                        return None;
+                    }
+                    dict.set("P", Object::Reference(new_id));
+                    for key in [b"StructParent".as_slice(), b"Popup"] {
+                        dict.remove(key);
+                    }
+                    Some(Object::Reference(doc.add_object(dict)))
+                })
+                .collect();
+            page.set("Annots", Object::Array(copied));
+        }
+
+        doc.objects.insert(new_id, Object::Dictionary(page));
+        Ok(new_id)
+    }
+
+    /// Add a blank page with the size and orientation of another page.
+    ///
+    /// The blank page copies the `MediaBox`, `CropBox` and `Rotate` of
+    /// `like`, including inherited values. It is not part of the page tree
+    /// until [`update_page_tree`](Self::update_page_tree) adds it.
+    pub(crate) fn blank_page(&self, doc: &mut Document, like: ObjectId) -> Result<ObjectId> {
+        let source = doc
+            .get_dictionary(like)
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get page: {err}")))?;
+
+        let mut page = Dictionary::new();
+        page.set("Type", Object::Name(b"Page".to_vec()));
+        page.set("Resources", Dictionary::new());
+
+        let inherited = inherited_attributes(doc, like);
+        for key in [b"MediaBox".as_slice(), b"CropBox", b"Rotate"] {
+            let value = source.get(key).ok().or_else(|| {
+                inherited
+                    .iter()
+                    .find(|(inherited_key, _)| inherited_key == key)
+                    .map(|(_, value)| value)
+            });
+            if let Some(value) = value {
+                page.set(key, value.clone());
+            }
+        }
+
+        Ok(doc.add_object(page))
+    }
+
     /// Update the page tree to contain only specified pages.
-    fn update_page_tree(&self, doc: &mut Document, page_ids: &[ObjectId]) -> Result<()> {
+    ///
+    /// Pages are attached directly to the root node. Attributes they
+    /// inherited from intermediate nodes are copied onto the pages, so the
+    /// old intermediate nodes (and the pages they list) become unreachable.
+    pub(crate) fn update_page_tree(&self, doc: &mut Document, page_ids: &[ObjectId]) -> Result<()> {
         let catalog = doc
             .catalog_mut()
             .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
@@ -134,6 +250,19 @@ impl PageExtractor {
                 PdfCatError::merge_failed(format!("Failed to get pages reference: {err}"))
             })?;
 
+        for &page_id in page_ids {
+            let inherited = inherited_attributes(doc, page_id);
+
+            if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id)
+                && page.get(b"Parent").and_then(|p| p.as_reference()).ok() != Some(pages_id)
+            {
+                for (key, value) in inherited {
+                    page.set(key, value);
+                }
+                page.set("Parent", Object::Reference(pages_id));
+            }
+        }
+
         let pages_obj = doc.get_object_mut(pages_id).map_err(|err| {
             PdfCatError::merge_failed(format!("Failed to get pages object: {err}"))
         })?;
@@ -273,24 +402,127 @@ mod tests {
         let extractor = PageExtractor::new();
         let page_range = PageRange::parse("1-5").unwrap();
 
-        let result = extractor.extract_pages(&doc, &page_range);
+        let result = extractor.extract_pages(
+            Path::new("test.pdf"),
+            &doc,
+            &page_range,
+            PageRangePolicy::Strict,
+        );
         assert!(result.is_ok());
 
         let extracted = result.unwrap();
         assert_eq!(extractor.page_count(&extracted), 5);
     }
 
-    // #[test]
-    // fn test_extract_pages_out_of_range() {
-    //     let doc = create_multi_page_pdf(5);
-    //     let extractor = PageExtractor::new();
-    //     let page_range = PageRange::parse("1-10").unwrap();
-    //     println!("PAGE_RANGE: {:?}", &page_range);
-    //     let result = extractor.extract_pages(&doc, &page_range);
-    //     println!("EXTRACTED PAGES: {:?}", &result);
-
-    //     assert!(result.is_err());
-    // }
+    #[test]
+    fn test_extract_pages_preserves_order() {
+        let doc = create_multi_page_pdf(4);
+        let original: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let extractor = PageExtractor::new();
+        let page_range = PageRange::parse("end-3").unwrap();
+
+        let extracted = extractor
+            .extract_pages(
+                Path::new("test.pdf"),
+                &doc,
+                &page_range,
+                PageRangePolicy::Strict,
+            )
+            .unwrap();
+        let pages: Vec<ObjectId> = extracted.get_pages().into_values().collect();
+
+        assert_eq!(pages, vec![original[3], original[2]]);
+    }
+
+    #[test]
+    fn test_extract_pages_duplicates() {
+        let doc = create_multi_page_pdf(3);
+        let extractor = PageExtractor::new();
+        let page_range = PageRange::parse("2,2,1").unwrap();
+
+        let extracted = extractor
+            .extract_pages(
+                Path::new("test.pdf"),
+                &doc,
+                &page_range,
+                PageRangePolicy::Strict,
+            )
+            .unwrap();
+        let pages: Vec<ObjectId> = extracted.get_pages().into_values().collect();
+
+        assert_eq!(pages.len(), 3);
+        assert_ne!(pages[0], pages[1]);
+        assert_eq!(
+            extracted.get_dictionary(pages[0]).unwrap(),
+            extracted.get_dictionary(pages[1]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_page_annotations() {
+        let mut doc = create_multi_page_pdf(1);
+        let page_id = doc.page_iter().next().unwrap();
+        let popup_id = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Popup" });
+        let note_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Text",
+            "P" => page_id,
+            "Popup" => popup_id,
+            "StructParent" => 1,
+        });
+        let widget_id = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Widget" });
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("StructParents", 0);
+        page.set(
+            "Annots",
+            vec![note_id.into(), popup_id.into(), widget_id.into()],
+        );
+
+        let copy_id = PageExtractor::new()
+            .duplicate_page(&mut doc, page_id)
+            .unwrap();
+
+        let copy = doc.get_dictionary(copy_id).unwrap();
+        assert!(!copy.has(b"StructParents"));
+        let annots = copy.get(b"Annots").unwrap().as_array().unwrap();
+        assert_eq!(annots.len(), 1);
+        let note = doc
+            .get_dictionary(annots[0].as_reference().unwrap())
+            .unwrap();
+        assert_eq!(note.get(b"P").unwrap().as_reference().unwrap(), copy_id);
+        assert!(!note.has(b"Popup"));
+        assert!(!note.has(b"StructParent"));
+    }
+
+    #[test]
+    fn test_extract_pages_out_of_range() {
+        let doc = create_multi_page_pdf(5);
+        let extractor = PageExtractor::new();
+        let page_range = PageRange::parse("1-10").unwrap();
+        let path = Path::new("five.pdf");
+
+        let lenient = extractor
+            .extract_pages(path, &doc, &page_range, PageRangePolicy::Lenient)
+            .unwrap();
+        assert_eq!(extractor.page_count(&lenient), 5);
+
+        let result = extractor.extract_pages(path, &doc, &page_range, PageRangePolicy::Strict);
+        match result {
+            Err(PdfCatError::InvalidPageRange { path, .. }) => {
+                assert_eq!(path, Path::new("five.pdf"));
+            }
+            other => panic!("expected InvalidPageRange, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_select_pages_missing_page() {
+        let doc = create_multi_page_pdf(2);
+        let extractor = PageExtractor::new();
+
+        assert!(extractor.select_pages(&doc, &[1, 3]).is_err());
+        assert!(extractor.select_pages(&doc, &[]).is_err());
+    }
 
     #[test]
     fn test_rotate_all_pages() {
@@ -0,0 +1,310 @@
+//! PDF Portfolio output.
+//!
+//! A portfolio (a "collection" in the PDF specification) is a cover
+//! document with other files embedded as attachments. Viewers that support
+//! portfolios list the files, with the columns of the collection schema,
+//! in place of the cover page; other viewers show the cover page and the
+//! attachments panel. The embedded files are stored byte for byte, so
+//! signed documents keep valid signatures.
+
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, dictionary};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::error::{PdfCatError, Result};
+use crate::merge::attachments::AttachmentManager;
+use crate::merge::font::helvetica;
+use crate::merge::layout::rect_object;
+use crate::merge::separator::{FONT_NAME, text_line};
+
+/// Size of the cover page (US Letter), in points.
+const COVER_SIZE: (f32, f32) = (612.0, 792.0);
+
+/// Most file names listed on the cover page.
+const MAX_LISTED: usize = 30;
+
+/// A file to put in a portfolio.
+#[derive(Debug, Clone)]
+pub struct PortfolioFile<'a> {
+    /// Path of the file; its name is shown in the portfolio.
+    pub path: &'a Path,
+
+    /// Contents of the file.
+    pub data: Vec<u8>,
+
+    /// When the file was last modified.
+    pub modified: Option<SystemTime>,
+
+    /// Number of pages, for PDF files.
+    pub page_count: Option<usize>,
+}
+
+/// Builder for PDF Portfolios.
+#[derive(Debug, Clone)]
+pub struct PortfolioBuilder {
+    /// Manager that embeds the files.
+    attachment_manager: AttachmentManager,
+}
+
+impl PortfolioBuilder {
+    /// Create a new portfolio builder.
+    pub fn new() -> Self {
+        Self {
+            attachment_manager: AttachmentManager::new(),
+        }
+    }
+
+    /// Build a portfolio of files.
+    ///
+    /// The cover page shows the title and lists the files. The portfolio
+    /// shows the files in the order given, with columns for the file name,
+    /// size and page count.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title shown on the cover page
+    /// * `files` - Files to embed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files cannot be listed in the document.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::portfolio::{PortfolioBuilder, PortfolioFile};
+    /// # use std::path::Path;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = PortfolioFile {
+    ///     path: Path::new("contract-signed.pdf"),
+    ///     data: std::fs::read("contract-signed.pdf")?,
+    ///     modified: None,
+    ///     page_count: Some(12),
+    /// };
+    /// let mut portfolio = PortfolioBuilder::new().build("Closing Documents", &[file])?;
+    /// portfolio.save("closing.pdf")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build(&self, title: &str, files: &[PortfolioFile]) -> Result<Document> {
+        let mut doc = Document::with_version("1.7");
+
+        let pages_id = doc.new_object_id();
+        let cover_id = self.add_cover(&mut doc, title, files);
+        doc.get_dictionary_mut(cover_id)
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get cover page: {err}")))?
+            .set("Parent", pages_id);
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![Object::Reference(cover_id)],
+                "Count" => 1,
+            }),
+        );
+
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "PageMode" => "UseAttachments",
+            "Collection" => collection(),
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut embedded = Vec::with_capacity(files.len());
+        for (index, file) in files.iter().enumerate() {
+            let name = file_name(file.path);
+            let entry =
+                self.attachment_manager
+                    .attach(&mut doc, &name, file.data.clone(), file.modified);
+
+            let mut item = dictionary! {
+                "Type" => "CollectionItem",
+                "Index" => index as i64,
+            };
+            if let Some(page_count) = file.page_count {
+                item.set("Pages", page_count as i64);
+            }
+            if let Object::Reference(spec_id) = entry.1
+                && let Ok(spec) = doc.get_dictionary_mut(spec_id)
+            {
+                spec.set("CI", item);
+            }
+
+            embedded.push(entry);
+        }
+        self.attachment_manager
+            .set_embedded_files(&mut doc, embedded)?;
+
+        Ok(doc)
+    }
+
+    /// Add the cover page, which is not yet part of the page tree.
+    fn add_cover(&self, doc: &mut Document, title: &str, files: &[PortfolioFile]) -> ObjectId {
+        let (width, height) = COVER_SIZE;
+
+        let count = match files.len() {
+            1 => "1 file".to_string(),
+            count => format!("{count} files"),
+        };
+        let mut lines = vec![(title.to_string(), 24.0), (count, 12.0)];
+        lines.extend(
+            files
+                .iter()
+                .take(MAX_LISTED)
+                .map(|file| (file_name(file.path), 11.0)),
+        );
+        if files.len() > MAX_LISTED {
+            lines.push((format!("and {} more", files.len() - MAX_LISTED), 11.0));
+        }
+        lines.push((
+            "Open this portfolio in a viewer that supports PDF Portfolios to see the files."
+                .to_string(),
+            9.0,
+        ));
+
+        // Stack the lines, centered on the page as a block
+        let line_heights: Vec<f32> = lines.iter().map(|(_, size)| size * 1.6).collect();
+        let mut baseline = (height + line_heights.iter().sum::<f32>()) / 2.0;
+
+        let mut content = String::new();
+        for ((text, size), line_height) in lines.iter().zip(line_heights) {
+            baseline -= line_height;
+            content.push_str(&text_line(text, *size, width, baseline));
+        }
+
+        let font_id = doc.add_object(helvetica());
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+        doc.add_object(dictionary! {
+            "Type" => "Page",
+            "MediaBox" => rect_object([0.0, 0.0, width, height]),
+            "Resources" => dictionary! {
+                "Font" => dictionary! { FONT_NAME => font_id },
+            },
+            "Contents" => content_id,
+        })
+    }
+}
+
+impl Default for PortfolioBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collection dictionary listing the files in the order they were added,
+/// with columns for the file name, size and page count.
+fn collection() -> Dictionary {
+    let field = |subtype: &str, name: &str, order: i64| {
+        dictionary! {
+            "Type" => "CollectionField",
+            "Subtype" => subtype,
+            "N" => lopdf::text_string(name),
+            "O" => order,
+        }
+    };
+
+    let mut index = field("N", "Order", 3);
+    index.set("V", false);
+
+    dictionary! {
+        "Type" => "Collection",
+        "Schema" => dictionary! {
+            "Type" => "CollectionSchema",
+            "FileName" => field("F", "File name", 0),
+            "Size" => field("Z", "Size", 1),
+            "Pages" => field("N", "Pages", 2),
+            "Index" => index,
+        },
+        "Sort" => dictionary! {
+            "S" => "Index",
+            "A" => true,
+        },
+        "View" => "D",
+    }
+}
+
+/// File name of a path, or the whole path if it has none.
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<PortfolioFile<'static>> {
+        vec![
+            PortfolioFile {
+                path: Path::new("signed/contract.pdf"),
+                data: b"%PDF-1.7 signed".to_vec(),
+                modified: None,
+                page_count: Some(12),
+            },
+            PortfolioFile {
+                path: Path::new("budget.xlsx"),
+                data: b"cells".to_vec(),
+                modified: None,
+                page_count: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_portfolio() {
+        let doc = PortfolioBuilder::new().build("Closing", &files()).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+
+        let catalog = doc.catalog().unwrap();
+        assert_eq!(
+            catalog.get(b"PageMode").unwrap().as_name().unwrap(),
+            b"UseAttachments"
+        );
+        let collection = catalog.get(b"Collection").unwrap().as_dict().unwrap();
+        let schema = collection.get(b"Schema").unwrap().as_dict().unwrap();
+        assert!(schema.has(b"FileName") && schema.has(b"Size") && schema.has(b"Pages"));
+
+        let embedded = AttachmentManager::new().embedded_files(&doc);
+        let names: Vec<&[u8]> = embedded.iter().map(|(name, _)| name.as_slice()).collect();
+        assert_eq!(names, vec![&b"budget.xlsx"[..], b"contract.pdf"]);
+
+        // Items keep the input order and the PDF's page count
+        let (_, spec) = doc.dereference(&embedded[1].1).unwrap();
+        let item = spec
+            .as_dict()
+            .unwrap()
+            .get(b"CI")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert_eq!(item.get(b"Index").unwrap().as_i64().unwrap(), 0);
+        assert_eq!(item.get(b"Pages").unwrap().as_i64().unwrap(), 12);
+
+        // Files are embedded byte for byte
+        let ef = spec
+            .as_dict()
+            .unwrap()
+            .get(b"EF")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        let stream_id = ef.get(b"F").unwrap().as_reference().unwrap();
+        let stream = doc.get_object(stream_id).unwrap().as_stream().unwrap();
+        assert_eq!(stream.content, b"%PDF-1.7 signed");
+    }
+
+    #[test]
+    fn test_cover_lists_files() {
+        let mut doc = Document::with_version("1.7");
+        let cover_id = PortfolioBuilder::new().add_cover(&mut doc, "Closing", &files());
+
+        let content = doc.get_page_content(cover_id).unwrap();
+        let content = String::from_utf8_lossy(&content);
+        assert!(content.contains("(Closing) Tj"));
+        assert!(content.contains("(2 files) Tj"));
+        assert!(content.contains("(contract.pdf) Tj"));
+    }
+}
@@ -0,0 +1,516 @@
+//! Removal of active content and hidden data.
+//!
+//! Sanitizing prepares a document for sending outside: it removes what
+//! runs when the document is opened or used (JavaScript, launch actions
+//! and additional actions) and what a reader doesn't see
+//! on the pages (embedded files, metadata, private application data and
+//! objects nothing refers to). Each input is sanitized on its own, and a
+//! [`SanitizeReport`] says what was removed from it.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::config::AnnotationFilter;
+use crate::error::Result;
+use crate::merge::annotations::AnnotationEditor;
+use crate::merge::metadata::MetadataManager;
+use crate::merge::trees::name_tree_entries;
+
+/// What sanitizing removed from a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanitizeReport {
+    /// Number of JavaScript actions and document-level scripts removed.
+    pub javascript: usize,
+
+    /// Number of actions that launch applications removed.
+    pub launch_actions: usize,
+
+    /// Number of additional-actions dictionaries (`/AA`), which run
+    /// actions on events such as closing the document, removed.
+    pub additional_actions: usize,
+
+    /// Whether an action run when the document opens was removed.
+    pub open_action: bool,
+
+    /// Number of embedded files and file attachment annotations removed.
+    pub embedded_files: usize,
+
+    /// Whether the Info dictionary or XMP metadata was removed.
+    pub metadata: bool,
+
+    /// Number of private application data dictionaries (`/PieceInfo`)
+    /// removed.
+    pub piece_info: usize,
+
+    /// Number of objects removed because nothing referred to them.
+    pub unused_objects: usize,
+}
+
+impl SanitizeReport {
+    /// Check if anything was removed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for SanitizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = [
+            (self.javascript, "JavaScript action", "JavaScript actions"),
+            (self.launch_actions, "launch action", "launch actions"),
+            (
+                self.additional_actions,
+                "additional-actions dictionary",
+                "additional-actions dictionaries",
+            ),
+            (self.embedded_files, "embedded file", "embedded files"),
+            (
+                self.piece_info,
+                "private application data dictionary",
+                "private application data dictionaries",
+            ),
+            (self.unused_objects, "unused object", "unused objects"),
+        ];
+
+        let mut parts: Vec<String> = Vec::new();
+        if self.open_action {
+            parts.push("open action".to_string());
+        }
+        if self.metadata {
+            parts.push("metadata".to_string());
+        }
+        for (count, one, many) in counts {
+            match count {
+                0 => {}
+                1 => parts.push(format!("1 {one}")),
+                _ => parts.push(format!("{count} {many}")),
+            }
+        }
+
+        if parts.is_empty() {
+            write!(f, "nothing to remove")
+        } else {
+            write!(f, "removed {}", parts.join(", "))
+        }
+    }
+}
+
+/// Sanitizer that strips active content and hidden data.
+#[derive(Debug, Clone)]
+pub struct Sanitizer {
+    /// Manager that clears the metadata.
+    metadata_manager: MetadataManager,
+
+    /// Editor that removes file attachment annotations.
+    annotation_editor: AnnotationEditor,
+}
+
+impl Sanitizer {
+    /// Create a new sanitizer.
+    pub fn new() -> Self {
+        Self {
+            metadata_manager: MetadataManager::new(),
+            annotation_editor: AnnotationEditor::new(),
+        }
+    }
+
+    /// Remove active content and hidden data from a document.
+    ///
+    /// Links and other actions that don't run scripts or launch
+    /// applications are kept, as is an open action that goes to a page or
+    /// runs such an action.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::Sanitizer;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let report = Sanitizer::new().sanitize(&mut doc)?;
+    /// println!("Sanitized: {report}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sanitize(&self, doc: &mut Document) -> Result<SanitizeReport> {
+        let mut report = SanitizeReport {
+            javascript: remove_name_tree(doc, b"JavaScript"),
+            embedded_files: remove_name_tree(doc, b"EmbeddedFiles"),
+            ..SanitizeReport::default()
+        };
+
+        report.embedded_files += doc
+            .objects
+            .values()
+            .filter_map(|obj| obj.as_dict().ok())
+            .filter(|dict| {
+                dict.get(b"Subtype")
+                    .and_then(Object::as_name)
+                    .is_ok_and(|subtype| subtype == b"FileAttachment")
+            })
+            .count();
+        let attachments = AnnotationFilter::Subtypes(vec!["FileAttachment".to_string()]);
+        self.annotation_editor.remove(doc, &attachments)?;
+
+        // An open action that runs a script or launches an application is
+        // removed with the other active actions below; destinations and
+        // other actions are kept
+        report.open_action = doc
+            .catalog()
+            .ok()
+            .and_then(|catalog| catalog.get_deref(b"OpenAction", doc).ok())
+            .and_then(|action| action.as_dict().ok())
+            .is_some_and(|action| active_action(action).is_some());
+
+        report.metadata = self.metadata_manager.has_metadata(doc)
+            || doc.catalog().is_ok_and(|catalog| catalog.has(b"Metadata"));
+        self.metadata_manager.clear_metadata(doc)?;
+
+        // Scripts and launch actions stored as objects of their own are
+        // counted once, however often they are referred to
+        let actions: HashSet<ObjectId> = doc
+            .objects
+            .iter()
+            .filter_map(|(&id, obj)| obj.as_dict().ok().map(|dict| (id, dict)))
+            .filter(|(_, dict)| active_action(dict).is_some())
+            .map(|(id, _)| id)
+            .collect();
+        for id in &actions {
+            if let Ok(dict) = doc.get_dictionary(*id) {
+                count_action(dict, &mut report);
+            }
+        }
+
+        for object in doc.objects.values_mut() {
+            strip(object, &actions, &mut report);
+        }
+
+        report.unused_objects = doc.prune_objects().len();
+
+        Ok(report)
+    }
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kinds of active actions.
+enum ActiveAction {
+    /// A JavaScript action.
+    JavaScript,
+    /// An action that launches an application.
+    Launch,
+}
+
+/// The kind of a JavaScript or launch action, or None for other
+/// dictionaries.
+fn active_action(dict: &Dictionary) -> Option<ActiveAction> {
+    match dict.get(b"S").and_then(Object::as_name) {
+        Ok(b"JavaScript") => Some(ActiveAction::JavaScript),
+        Ok(b"Launch") => Some(ActiveAction::Launch),
+        _ if dict.has(b"JS") => Some(ActiveAction::JavaScript),
+        _ => None,
+    }
+}
+
+/// Count a removed action in a report.
+fn count_action(dict: &Dictionary, report: &mut SanitizeReport) {
+    match active_action(dict) {
+        Some(ActiveAction::JavaScript) => report.javascript += 1,
+        Some(ActiveAction::Launch) => report.launch_actions += 1,
+        None => {}
+    }
+}
+
+/// Check whether an object is an active action, and count it if it is
+/// written in place.
+fn is_active(value: &Object, actions: &HashSet<ObjectId>, report: &mut SanitizeReport) -> bool {
+    match value {
+        Object::Reference(id) => actions.contains(id),
+        Object::Dictionary(dict) if active_action(dict).is_some() => {
+            count_action(dict, report);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Remove additional actions, active actions, metadata streams and
+/// private application data from an object and the objects written in it.
+fn strip(object: &mut Object, actions: &HashSet<ObjectId>, report: &mut SanitizeReport) {
+    match object {
+        Object::Dictionary(dict) => strip_dict(dict, actions, report),
+        Object::Stream(stream) => strip_dict(&mut stream.dict, actions, report),
+        Object::Array(items) => {
+            items.retain(|item| !is_active(item, actions, report));
+            for item in items {
+                strip(item, actions, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn strip_dict(dict: &mut Dictionary, actions: &HashSet<ObjectId>, report: &mut SanitizeReport) {
+    if dict.remove(b"AA").is_some() {
+        report.additional_actions += 1;
+    }
+    if dict.remove(b"PieceInfo").is_some() {
+        report.piece_info += 1;
+    }
+    if dict.remove(b"Metadata").is_some() {
+        report.metadata = true;
+    }
+
+    let active: Vec<Vec<u8>> = dict
+        .iter()
+        .filter(|(_, value)| is_active(value, actions, report))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in active {
+        dict.remove(&key);
+    }
+
+    for (_, value) in dict.iter_mut() {
+        strip(value, actions, report);
+    }
+}
+
+/// Remove a name tree from the catalog's `/Names`, returning the number
+/// of entries it had.
+fn remove_name_tree(doc: &mut Document, key: &[u8]) -> usize {
+    let Some(names) = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get_deref(b"Names", doc).ok())
+        .and_then(|names| names.as_dict().ok())
+    else {
+        return 0;
+    };
+    let Some(tree) = names
+        .get_deref(key, doc)
+        .ok()
+        .and_then(|tree| tree.as_dict().ok())
+    else {
+        return 0;
+    };
+    let count = name_tree_entries(doc, tree).len();
+
+    let names_id = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"Names").ok())
+        .and_then(|obj| obj.as_reference().ok());
+    let names = match names_id {
+        Some(id) => doc.get_dictionary_mut(id).ok(),
+        None => doc
+            .catalog_mut()
+            .ok()
+            .and_then(|catalog| catalog.get_mut(b"Names").ok())
+            .and_then(|obj| obj.as_dict_mut().ok()),
+    };
+    if let Some(names) = names {
+        names.remove(key);
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{Stream, dictionary};
+
+    /// A one-page document with a little of everything sanitizing removes.
+    fn create_doc() -> (Document, ObjectId) {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+
+        let script_id = doc.add_object(dictionary! {
+            "S" => "JavaScript",
+            "JS" => Object::string_literal("app.alert('hi')"),
+        });
+        let link_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            "A" => dictionary! {
+                "S" => "Launch",
+                "F" => Object::string_literal("calc.exe"),
+            },
+        });
+        let uri_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 20.into(), 10.into(), 30.into()],
+            "A" => dictionary! {
+                "S" => "URI",
+                "URI" => Object::string_literal("https://example.com"),
+                "Next" => vec![script_id.into()],
+            },
+        });
+        let file_id = doc.add_object(Stream::new(
+            dictionary! { "Type" => "EmbeddedFile" },
+            b"secret".to_vec(),
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Annots" => vec![link_id.into(), uri_id.into()],
+            "AA" => dictionary! { "O" => script_id },
+            "PieceInfo" => dictionary! {
+                "Illustrator" => dictionary! { "Private" => Object::string_literal("layers") },
+            },
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+
+        let info_id = doc.add_object(dictionary! {
+            "Author" => Object::string_literal("Someone"),
+        });
+        doc.trailer.set("Info", info_id);
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "OpenAction" => script_id,
+            "Names" => dictionary! {
+                "JavaScript" => dictionary! {
+                    "Names" => vec![Object::string_literal("init"), script_id.into()],
+                },
+                "EmbeddedFiles" => dictionary! {
+                    "Names" => vec![
+                        Object::string_literal("secret.txt"),
+                        dictionary! { "Type" => "Filespec", "EF" => dictionary! { "F" => file_id } }.into(),
+                    ],
+                },
+                "Dests" => dictionary! { "Names" => Vec::<Object>::new() },
+            },
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.add_object(Object::string_literal("unused"));
+
+        (doc, page_id)
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let (mut doc, page_id) = create_doc();
+        let report = Sanitizer::new().sanitize(&mut doc).unwrap();
+
+        assert_eq!(
+            report,
+            SanitizeReport {
+                // The script object and the document-level script entry
+                javascript: 2,
+                launch_actions: 1,
+                additional_actions: 1,
+                open_action: true,
+                embedded_files: 1,
+                metadata: true,
+                piece_info: 1,
+                unused_objects: 3,
+            }
+        );
+
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert!(!page.has(b"AA") && !page.has(b"PieceInfo"));
+        let catalog = doc.catalog().unwrap();
+        assert!(!catalog.has(b"OpenAction"));
+        let names = catalog.get(b"Names").unwrap().as_dict().unwrap();
+        assert!(names.has(b"Dests"));
+        assert!(!names.has(b"JavaScript") && !names.has(b"EmbeddedFiles"));
+        assert!(!doc.trailer.has(b"Info"));
+
+        // The links stay; the URI action loses the script it chained to
+        let annots = page.get(b"Annots").unwrap().as_array().unwrap();
+        assert_eq!(annots.len(), 2);
+        let link = doc
+            .get_dictionary(annots[0].as_reference().unwrap())
+            .unwrap();
+        assert!(!link.has(b"A"));
+        let uri = doc
+            .get_dictionary(annots[1].as_reference().unwrap())
+            .unwrap();
+        let action = uri.get(b"A").unwrap().as_dict().unwrap();
+        assert!(action.get(b"Next").unwrap().as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_keeps_destination_open_action() {
+        let (mut doc, page_id) = create_doc();
+        doc.catalog_mut().unwrap().set(
+            "OpenAction",
+            vec![page_id.into(), Object::Name(b"Fit".to_vec())],
+        );
+
+        let report = Sanitizer::new().sanitize(&mut doc).unwrap();
+        assert!(!report.open_action);
+        assert!(doc.catalog().unwrap().has(b"OpenAction"));
+    }
+
+    #[test]
+    fn test_sanitize_open_action_dictionary() {
+        // A go-to action is kept
+        let (mut doc, page_id) = create_doc();
+        doc.catalog_mut().unwrap().set(
+            "OpenAction",
+            dictionary! {
+                "S" => "GoTo",
+                "D" => vec![page_id.into(), Object::Name(b"Fit".to_vec())],
+            },
+        );
+
+        let report = Sanitizer::new().sanitize(&mut doc).unwrap();
+        assert!(!report.open_action);
+        let catalog = doc.catalog().unwrap();
+        let action = catalog.get(b"OpenAction").unwrap().as_dict().unwrap();
+        assert_eq!(action.get(b"S").unwrap().as_name().unwrap(), b"GoTo");
+
+        // A script written in place is removed and counted
+        let (mut doc, _) = create_doc();
+        doc.catalog_mut().unwrap().set(
+            "OpenAction",
+            dictionary! {
+                "S" => "JavaScript",
+                "JS" => Object::string_literal("app.alert(1)"),
+            },
+        );
+
+        let report = Sanitizer::new().sanitize(&mut doc).unwrap();
+        assert!(report.open_action);
+        assert_eq!(report.javascript, 3);
+        assert!(!doc.catalog().unwrap().has(b"OpenAction"));
+    }
+
+    #[test]
+    fn test_sanitize_report_display() {
+        assert_eq!(SanitizeReport::default().to_string(), "nothing to remove");
+
+        let report = SanitizeReport {
+            javascript: 2,
+            metadata: true,
+            unused_objects: 1,
+            ..SanitizeReport::default()
+        };
+        assert_eq!(
+            report.to_string(),
+            "removed metadata, 2 JavaScript actions, 1 unused object"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Separator pages placed between merged inputs.
+//!
+//! A separator page shows an input's file name, its title metadata and
+//! its page count. Text is set in Helvetica, one of the standard Type1
+//! fonts every PDF viewer provides, so no font needs to be embedded.
+
+use lopdf::{Dictionary, Document, ObjectId, Stream, dictionary};
+use std::path::Path;
+
+use crate::merge::font::{encode_win_ansi, helvetica, string_literal, text_width};
+use crate::merge::layout::rect_object;
+
+/// Resource name of the separator font.
+pub(crate) const FONT_NAME: &str = "F1";
+
+/// Space kept free on either side of a line of text, in points.
+const SIDE_MARGIN: f32 = 36.0;
+
+/// What a separator page shows about an input.
+#[derive(Debug, Clone)]
+pub struct SeparatorInfo<'a> {
+    /// Path of the input file.
+    pub path: &'a Path,
+
+    /// Title from the input's metadata, if any.
+    pub title: Option<String>,
+
+    /// Number of pages taken from the input.
+    pub page_count: usize,
+
+    /// Page size (width, height) in points.
+    pub size: (f32, f32),
+}
+
+/// Generator for separator pages.
+#[derive(Debug, Clone)]
+pub struct SeparatorGenerator;
+
+impl SeparatorGenerator {
+    /// Create a new separator generator.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Add one separator page to a document for each input.
+    ///
+    /// The pages are not part of the page tree until the caller adds them.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to add the pages to
+    /// * `inputs` - What to show on each separator page
+    ///
+    /// # Returns
+    ///
+    /// IDs of the new pages, in the order of `inputs`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::separator::{SeparatorGenerator, SeparatorInfo};
+    /// # use lopdf::Document;
+    /// # use std::path::Path;
+    /// # fn example(mut doc: Document) {
+    /// let info = SeparatorInfo {
+    ///     path: Path::new("chapter1.pdf"),
+    ///     title: Some("Introduction".to_string()),
+    ///     page_count: 12,
+    ///     size: (595.0, 842.0),
+    /// };
+    /// let pages = SeparatorGenerator::new().add_separators(&mut doc, &[info]);
+    /// # }
+    /// ```
+    pub fn add_separators(&self, doc: &mut Document, inputs: &[SeparatorInfo]) -> Vec<ObjectId> {
+        if inputs.is_empty() {
+            return Vec::new();
+        }
+
+        let font_id = doc.add_object(helvetica());
+
+        inputs
+            .iter()
+            .map(|info| self.add_separator(doc, font_id, info))
+            .collect()
+    }
+
+    /// Add a single separator page.
+    fn add_separator(
+        &self,
+        doc: &mut Document,
+        font_id: ObjectId,
+        info: &SeparatorInfo,
+    ) -> ObjectId {
+        let (width, height) = info.size;
+
+        let file_name = info
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| info.path.display().to_string());
+        let pages = match info.page_count {
+            1 => "1 page".to_string(),
+            count => format!("{count} pages"),
+        };
+
+        let mut lines = vec![(file_name, 24.0)];
+        if let Some(title) = info
+            .title
+            .as_deref()
+            .filter(|title| !title.trim().is_empty())
+        {
+            lines.push((title.trim().to_string(), 16.0));
+        }
+        lines.push((pages, 12.0));
+
+        // Stack the lines, centered on the page as a block
+        let line_heights: Vec<f32> = lines.iter().map(|(_, size)| size * 1.6).collect();
+        let mut baseline = (height + line_heights.iter().sum::<f32>()) / 2.0;
+
+        let mut content = String::new();
+        for ((text, size), line_height) in lines.iter().zip(line_heights) {
+            baseline -= line_height;
+            content.push_str(&text_line(text, *size, width, baseline));
+        }
+
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+        doc.add_object(dictionary! {
+            "Type" => "Page",
+            "MediaBox" => rect_object([0.0, 0.0, width, height]),
+            "Resources" => dictionary! {
+                "Font" => dictionary! { FONT_NAME => font_id },
+            },
+            "Contents" => content_id,
+        })
+    }
+}
+
+impl Default for SeparatorGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Content operators that show a line of text centered horizontally.
+///
+/// The font size is reduced if needed so that the line fits between the
+/// side margins.
+pub(crate) fn text_line(text: &str, size: f32, page_width: f32, baseline: f32) -> String {
+    let encoded = encode_win_ansi(text);
+    let available = (page_width - 2.0 * SIDE_MARGIN).max(1.0);
+    let size = size.min(available / text_width(&encoded, 1.0).max(0.001));
+    let width = text_width(&encoded, size);
+
+    format!(
+        "BT /{FONT_NAME} {size} Tf {} {baseline} Td {} Tj ET\n",
+        (page_width - width) / 2.0,
+        string_literal(&encoded)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_line_centered_and_escaped() {
+        // "(a)" is 333 + 556 + 333 = 1222 units wide
+        let line = text_line("(a)", 10.0, 612.0, 400.0);
+        assert_eq!(line, "BT /F1 10 Tf 299.89 400 Td (\\(a\\)) Tj ET\n");
+    }
+
+    #[test]
+    fn test_text_line_shrinks_to_fit() {
+        let text = "W".repeat(100);
+        let line = text_line(&text, 24.0, 200.0, 0.0);
+
+        // 100 x 944 units must fit in 128 points
+        let size: f32 = line.split_whitespace().nth(2).unwrap().parse().unwrap();
+        assert!(size * 94.4 <= 128.01);
+    }
+
+    #[test]
+    fn test_add_separators() {
+        let mut doc = Document::with_version("1.5");
+        let inputs = [
+            SeparatorInfo {
+                path: Path::new("dir/report.pdf"),
+                title: Some("Annual Report".to_string()),
+                page_count: 3,
+                size: (612.0, 792.0),
+            },
+            SeparatorInfo {
+                path: Path::new("notes.pdf"),
+                title: None,
+                page_count: 1,
+                size: (842.0, 595.0),
+            },
+        ];
+
+        let pages = SeparatorGenerator::new().add_separators(&mut doc, &inputs);
+        assert_eq!(pages.len(), 2);
+
+        let content = String::from_utf8(doc.get_page_content(pages[0]).unwrap()).unwrap();
+        assert!(content.contains("(report.pdf) Tj"));
+        assert!(content.contains("(Annual Report) Tj"));
+        assert!(content.contains("(3 pages) Tj"));
+
+        let content = String::from_utf8(doc.get_page_content(pages[1]).unwrap()).unwrap();
+        assert!(content.contains("(1 page) Tj"));
+
+        let page = doc.get_dictionary(pages[1]).unwrap();
+        let media_box: Vec<f32> = page
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect();
+        assert_eq!(media_box, vec![0.0, 0.0, 842.0, 595.0]);
+    }
+}
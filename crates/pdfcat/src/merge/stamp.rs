@@ -0,0 +1,414 @@
+//! Page numbers, headers and footers.
+//!
+//! Stamps are lines of text drawn on top of each page by appending a
+//! content stream. Positions refer to the page as it is displayed, so a
+//! stamp at the bottom of a page with `/Rotate 90` is still at the bottom
+//! when viewed, and each page's own size is used to place the text.
+
+use lopdf::{Document, ObjectId};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{Bates, Stamp, StampPosition};
+use crate::error::Result;
+use crate::merge::font::{encode_win_ansi, helvetica, string_literal, text_width};
+use crate::merge::layout::{
+    add_page_resource, append_content, displayed_matrix, displayed_size, invert_matrix, page_box,
+    page_rotation,
+};
+
+/// Resource name of the stamp font, chosen not to clash with the page's
+/// own fonts.
+const FONT_NAME: &str = "PdfCatStamp";
+
+/// Distance between a stamp and the edges of the page, in points.
+const MARGIN: f32 = 24.0;
+
+/// Height of Helvetica capitals, in thousandths of the font size.
+const CAP_HEIGHT: f32 = 718.0;
+
+/// Values for the placeholders in stamp text.
+#[derive(Debug, Clone, Default)]
+pub struct StampContext {
+    /// File name of the input each page came from. Pages not listed, such
+    /// as inserted blank pages, have an empty `{file}`.
+    pub files: HashMap<ObjectId, String>,
+
+    /// Date for `{date}`, as YYYY-MM-DD.
+    pub date: String,
+
+    /// Numbering for `{bates}`; without it, `{bates}` is empty.
+    pub bates: Option<Bates>,
+}
+
+impl StampContext {
+    /// Create a context with today's date and no file names.
+    pub fn new() -> Self {
+        Self {
+            files: HashMap::new(),
+            date: format_date(SystemTime::now()),
+            bates: None,
+        }
+    }
+}
+
+/// Stamper that draws text on pages.
+#[derive(Debug, Clone)]
+pub struct Stamper;
+
+impl Stamper {
+    /// Create a new stamper.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw stamps on the pages of a document.
+    ///
+    /// Page numbers in `{n}` and in each stamp's page range refer to the
+    /// pages of `doc`. The existing content of a stamped page is wrapped
+    /// in `q`/`Q`, so graphics state it leaves behind doesn't affect the
+    /// stamps.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to stamp
+    /// * `stamps` - Stamps to draw
+    /// * `context` - Values for placeholders
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::stamp::{StampContext, Stamper};
+    /// # use pdfcat::config::{Stamp, StampPosition};
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let footer = Stamp::new("Page {n} of {total}", StampPosition::BottomCenter);
+    /// Stamper::new().stamp(&mut doc, &[footer], &StampContext::new())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stamp(
+        &self,
+        doc: &mut Document,
+        stamps: &[Stamp],
+        context: &StampContext,
+    ) -> Result<()> {
+        if stamps.is_empty() {
+            return Ok(());
+        }
+
+        let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let total = page_ids.len();
+
+        // Page numbers each stamp applies to
+        let selections: Vec<Option<Vec<u32>>> = stamps
+            .iter()
+            .map(|stamp| {
+                stamp
+                    .pages
+                    .as_ref()
+                    .map(|range| range.to_pages(total as u32))
+            })
+            .collect();
+
+        let font_id = doc.add_object(helvetica());
+
+        for (index, &page_id) in page_ids.iter().enumerate() {
+            let number = index + 1;
+            let bates = context
+                .bates
+                .as_ref()
+                .map(|bates| bates.number(index))
+                .unwrap_or_default();
+            let file = context
+                .files
+                .get(&page_id)
+                .map(String::as_str)
+                .unwrap_or("");
+
+            let texts: Vec<(&Stamp, String)> = stamps
+                .iter()
+                .zip(&selections)
+                .filter(|(_, pages)| {
+                    pages
+                        .as_ref()
+                        .is_none_or(|pages| pages.contains(&(number as u32)))
+                })
+                .map(|(stamp, _)| {
+                    let text = fill_placeholders(
+                        &stamp.text,
+                        &[
+                            ("{n}", &number.to_string()),
+                            ("{total}", &total.to_string()),
+                            ("{file}", file),
+                            ("{date}", &context.date),
+                            ("{bates}", &bates),
+                        ],
+                    );
+                    (stamp, text)
+                })
+                .filter(|(_, text)| !text.trim().is_empty())
+                .collect();
+
+            if texts.is_empty() {
+                continue;
+            }
+
+            self.stamp_page(doc, page_id, font_id, &texts)?;
+        }
+
+        Ok(())
+    }
+
+    /// Append a content stream drawing `texts` to a page.
+    fn stamp_page(
+        &self,
+        doc: &mut Document,
+        page_id: ObjectId,
+        font_id: ObjectId,
+        texts: &[(&Stamp, String)],
+    ) -> Result<()> {
+        let (width, height) = displayed_size(page_box(doc, page_id), page_rotation(doc, page_id));
+
+        // Draw in the displayed page's coordinates
+        let mut content = format!(
+            "q {} cm 0 g\n",
+            invert_matrix(displayed_matrix(doc, page_id))
+                .map(|value| value.to_string())
+                .join(" ")
+        );
+        for (stamp, text) in texts {
+            content.push_str(&text_line(
+                text,
+                stamp.font_size,
+                stamp.position,
+                (width, height),
+            ));
+        }
+        content.push_str("Q\n");
+
+        append_content(doc, page_id, &content)?;
+        add_page_resource(doc, page_id, "Font", FONT_NAME, font_id)
+    }
+}
+
+impl Default for Stamper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Replace placeholders in stamp text with their values.
+///
+/// The text is read once from left to right, so a value that itself
+/// contains a placeholder, such as a file named "{n}.pdf", is kept as is.
+fn fill_placeholders(text: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        match values
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                filled.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    filled.push_str(rest);
+    filled
+}
+
+/// Content operators that show a line of text at a position on a page of
+/// the given displayed size.
+fn text_line(text: &str, size: f32, position: StampPosition, page_size: (f32, f32)) -> String {
+    let (width, height) = page_size;
+    let encoded = encode_win_ansi(text);
+    let text_width = text_width(&encoded, size);
+
+    let x = match position {
+        StampPosition::TopLeft | StampPosition::BottomLeft => MARGIN,
+        StampPosition::TopCenter | StampPosition::BottomCenter => (width - text_width) / 2.0,
+        StampPosition::TopRight | StampPosition::BottomRight => width - MARGIN - text_width,
+    };
+    let y = match position {
+        StampPosition::TopLeft | StampPosition::TopCenter | StampPosition::TopRight => {
+            height - MARGIN - size * CAP_HEIGHT / 1000.0
+        }
+        _ => MARGIN,
+    };
+
+    format!(
+        "BT /{FONT_NAME} {size} Tf {x} {y} Td {} Tj ET\n",
+        string_literal(&encoded)
+    )
+}
+
+/// Format a time as a YYYY-MM-DD date in UTC.
+fn format_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400;
+
+    // Days since 1970-01-01 to a civil date, counting in 400-year eras
+    // of 146,097 days that start on March 1st
+    let days = days as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PageRange;
+    use crate::merge::test_utils::{create_doc, page_content};
+    use std::time::Duration;
+
+    const BODY: &[u8] = b"2 0 0 2 0 0 cm BT /F1 12 Tf (Body) Tj ET";
+
+    #[test]
+    fn test_stamp_page_numbers() {
+        let mut doc = create_doc(&[(612, 792, 0), (842, 1191, 0), (595, 842, 0)], BODY);
+        let mut footer = Stamp::new("Page {n} of {total}", StampPosition::BottomCenter);
+        footer.pages = Some(PageRange::parse("1-2").unwrap());
+
+        Stamper::new()
+            .stamp(&mut doc, &[footer], &StampContext::new())
+            .unwrap();
+
+        // The original content is kept, isolated from the stamp
+        let content = page_content(&doc, 1);
+        assert!(content.starts_with("q\n"));
+        assert!(content.contains("(Body) Tj ET\nQ"));
+        assert!(content.contains("(Page 1 of 3) Tj"));
+
+        // Centered on the larger page
+        let width = text_width(b"Page 2 of 3", 10.0);
+        let content = page_content(&doc, 2);
+        assert!(content.contains(&format!(
+            "/PdfCatStamp 10 Tf {} 24 Td (Page 2 of 3) Tj",
+            (842.0 - width) / 2.0
+        )));
+
+        // Outside the stamp's page range
+        assert!(!page_content(&doc, 3).contains("PdfCatStamp"));
+
+        // Inherited fonts are still available
+        let page = doc.get_dictionary(doc.get_pages()[&1]).unwrap();
+        let fonts = page
+            .get(b"Resources")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Font")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert!(fonts.has(b"F1"));
+        assert!(fonts.has(b"PdfCatStamp"));
+    }
+
+    #[test]
+    fn test_stamp_rotated_page() {
+        // Portrait Letter turned to landscape by /Rotate 90
+        let mut doc = create_doc(&[(612, 792, 90)], BODY);
+        let mut context = StampContext::new();
+        context
+            .files
+            .insert(doc.get_pages()[&1], "report.pdf".to_string());
+
+        let header = Stamp::new("{file}", StampPosition::TopLeft);
+        Stamper::new().stamp(&mut doc, &[header], &context).unwrap();
+
+        // Maps displayed coordinates back to the unrotated page: the
+        // displayed top left corner is the page's bottom left corner
+        let content = page_content(&doc, 1);
+        assert!(content.contains("q 0 1 -1 0 612 0 cm 0 g"));
+        assert!(content.contains("24 580.82 Td (report.pdf) Tj"));
+    }
+
+    #[test]
+    fn test_stamp_bates_numbers() {
+        let mut doc = create_doc(&[(612, 792, 0), (612, 792, 0)], BODY);
+        let mut context = StampContext::new();
+        let mut bates = Bates::new("ACME-");
+        bates.start = 99;
+        context.bates = Some(bates);
+
+        let stamp = Stamp::new("Confidential {bates}", StampPosition::BottomRight);
+        Stamper::new().stamp(&mut doc, &[stamp], &context).unwrap();
+
+        assert!(page_content(&doc, 1).contains("(Confidential ACME-000099) Tj"));
+        assert!(page_content(&doc, 2).contains("(Confidential ACME-000100) Tj"));
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let values = [
+            ("{n}", "7"),
+            ("{file}", "{n} {date}.pdf"),
+            ("{date}", "2025-01-01"),
+        ];
+
+        assert_eq!(
+            fill_placeholders("{file} p{n} {{n}} {other} {", &values),
+            "{n} {date}.pdf p7 {7} {other} {"
+        );
+        assert_eq!(fill_placeholders("", &values), "");
+    }
+
+    #[test]
+    fn test_text_line_positions() {
+        let width = text_width(b"Draft", 12.0);
+
+        let line = text_line("Draft", 12.0, StampPosition::TopRight, (600.0, 800.0));
+        let expected_x = 600.0 - 24.0 - width;
+        let expected_y = 800.0 - 24.0 - 12.0 * 0.718;
+        assert_eq!(
+            line,
+            format!("BT /PdfCatStamp 12 Tf {expected_x} {expected_y} Td (Draft) Tj ET\n")
+        );
+
+        let line = text_line("Draft", 12.0, StampPosition::BottomLeft, (600.0, 800.0));
+        assert!(line.contains(" 24 24 Td "));
+    }
+
+    #[test]
+    fn test_format_date() {
+        let date = |secs| format_date(UNIX_EPOCH + Duration::from_secs(secs));
+
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_709_251_199), "2024-02-29");
+        assert_eq!(date(1_735_689_600), "2025-01-01");
+    }
+}
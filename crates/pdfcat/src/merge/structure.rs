@@ -0,0 +1,779 @@
+//! Tagged PDF structure trees.
+//!
+//! A tagged PDF describes its logical structure (headings, paragraphs,
+//! tables, figures) in a structure tree under the catalog's
+//! `/StructTreeRoot`. Marked content on a page is tied to its structure
+//! elements through the page's `/StructParents` key into the tree's
+//! `/ParentTree`; annotations and form XObjects use `/StructParent` and
+//! `/StructParents` keys the same way.
+//!
+//! Merging shifts each input's keys past those of the inputs before it
+//! and combines the inputs' trees under one root, so assistive technology
+//! can read every input, not just the first.
+
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat, dictionary};
+use std::collections::HashSet;
+
+use crate::error::{PdfCatError, Result};
+use crate::merge::trees::{name_tree_entries, number_tree_entries};
+
+/// The structure tree of one input, taken out of its document.
+#[derive(Debug, Clone)]
+pub struct StructureTree {
+    /// Top-level structure elements.
+    kids: Vec<Object>,
+
+    /// Parent tree entries, with shifted keys.
+    parent_tree: Vec<(i64, Object)>,
+
+    /// Structure elements by ID.
+    id_tree: Vec<(Vec<u8>, Object)>,
+
+    /// Mapping of custom structure types to standard ones.
+    role_map: Dictionary,
+
+    /// Attribute classes.
+    class_map: Dictionary,
+
+    /// Shifted key after the highest one the input uses.
+    next_key: i64,
+}
+
+impl StructureTree {
+    /// The parent tree key after the highest one this input uses. The next
+    /// input's keys are shifted to start here.
+    pub fn next_key(&self) -> i64 {
+        self.next_key
+    }
+}
+
+/// Merger for the structure trees of tagged inputs.
+#[derive(Debug, Clone)]
+pub struct StructureMerger;
+
+impl StructureMerger {
+    /// Create a new structure merger.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read the structure tree of an input and shift the parent tree keys
+    /// of its pages, annotations and XObjects.
+    ///
+    /// Elements on pages that page selection left out of the input's page
+    /// tree are left out of its structure tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Input document, with objects numbered as in the output
+    /// * `offset` - Amount to shift the input's keys by: the
+    ///   [`StructureTree::next_key`] of the input before it, or 0
+    ///
+    /// # Returns
+    ///
+    /// The input's structure tree, or None if it is not tagged; its keys
+    /// are then left alone.
+    pub fn take_tree(&self, doc: &mut Document, offset: i64) -> Option<StructureTree> {
+        let root = doc
+            .catalog()
+            .ok()?
+            .get_deref(b"StructTreeRoot", doc)
+            .ok()?
+            .as_dict()
+            .ok()?
+            .clone();
+
+        let mut kids = match root.get(b"K") {
+            Ok(kids @ Object::Reference(_)) => match doc.dereference(kids) {
+                Ok((_, Object::Array(kids))) => kids.clone(),
+                _ => vec![kids.clone()],
+            },
+            Ok(Object::Array(kids)) => kids.clone(),
+            Ok(kid) => vec![kid.clone()],
+            Err(_) => Vec::new(),
+        };
+        let mut parent_tree = root
+            .get_deref(b"ParentTree", doc)
+            .and_then(Object::as_dict)
+            .map(|tree| number_tree_entries(doc, tree))
+            .unwrap_or_default();
+        let mut id_tree = root
+            .get_deref(b"IDTree", doc)
+            .and_then(Object::as_dict)
+            .map(|tree| name_tree_entries(doc, tree))
+            .unwrap_or_default();
+        let role_map = root
+            .get_deref(b"RoleMap", doc)
+            .and_then(Object::as_dict)
+            .cloned()
+            .unwrap_or_default();
+        let class_map = root
+            .get_deref(b"ClassMap", doc)
+            .and_then(Object::as_dict)
+            .cloned()
+            .unwrap_or_default();
+
+        // Pages dropped by page selection are still in the document, but
+        // their content is not; neither should the tree refer to them
+        prune_tree(doc, &mut kids, &mut parent_tree, &mut id_tree);
+
+        let next_key = parent_tree
+            .iter()
+            .map(|(key, _)| key + 1)
+            .chain(root.get(b"ParentTreeNextKey").and_then(Object::as_i64).ok())
+            .chain([shift_keys(doc, offset)])
+            .max()
+            .unwrap_or_default();
+
+        Some(StructureTree {
+            kids,
+            parent_tree: parent_tree
+                .into_iter()
+                .map(|(key, value)| (key + offset, value))
+                .collect(),
+            id_tree,
+            role_map,
+            class_map,
+            next_key: next_key + offset,
+        })
+    }
+
+    /// Combine structure trees under a new root in a document.
+    ///
+    /// The trees' objects must already be in the document. Role and class
+    /// maps are combined, keeping the first input's entry for a name that
+    /// several inputs define, and so are element IDs. The document is
+    /// marked as tagged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::structure::StructureMerger;
+    /// # use lopdf::Document;
+    /// # fn example(mut merged: Document, mut input: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let merger = StructureMerger::new();
+    /// let mut trees = Vec::new();
+    /// trees.extend(merger.take_tree(&mut merged, 0));
+    /// let offset = trees.last().map_or(0, |tree| tree.next_key());
+    ///
+    /// input.renumber_objects_with(merged.max_id + 1);
+    /// trees.extend(merger.take_tree(&mut input, offset));
+    /// merged.objects.extend(input.objects);
+    ///
+    /// merger.merge_trees(&mut merged, trees)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_trees(&self, doc: &mut Document, trees: Vec<StructureTree>) -> Result<()> {
+        let root_id = doc.new_object_id();
+
+        let mut kids = Vec::new();
+        let mut parent_tree = Vec::new();
+        let mut id_tree = Vec::new();
+        let mut role_map = Dictionary::new();
+        let mut class_map = Dictionary::new();
+        let mut next_key = 0;
+
+        for tree in trees {
+            for mut kid in tree.kids {
+                match kid {
+                    Object::Reference(id) => {
+                        if let Ok(element) = doc.get_dictionary_mut(id) {
+                            element.set("P", root_id);
+                        }
+                    }
+                    Object::Dictionary(ref mut element) => element.set("P", root_id),
+                    _ => {}
+                }
+                kids.push(kid);
+            }
+
+            parent_tree.extend(tree.parent_tree);
+            id_tree.extend(tree.id_tree);
+            for (map, entries) in [
+                (&mut role_map, tree.role_map),
+                (&mut class_map, tree.class_map),
+            ] {
+                for (name, value) in entries.iter() {
+                    if !map.has(name) {
+                        map.set(name.clone(), value.clone());
+                    }
+                }
+            }
+            next_key = next_key.max(tree.next_key);
+        }
+
+        parent_tree.sort_by_key(|&(key, _)| key);
+        let nums: Vec<Object> = parent_tree
+            .into_iter()
+            .flat_map(|(key, value)| [Object::Integer(key), value])
+            .collect();
+
+        let mut root = dictionary! {
+            "Type" => "StructTreeRoot",
+            "K" => kids,
+            "ParentTree" => dictionary! { "Nums" => nums },
+            "ParentTreeNextKey" => next_key,
+        };
+        if !id_tree.is_empty() {
+            // Sorted by ID as name trees require; the first element with an
+            // ID keeps it
+            id_tree.sort_by(|(a, _), (b, _)| a.cmp(b));
+            id_tree.dedup_by(|(a, _), (b, _)| a == b);
+            let names: Vec<Object> = id_tree
+                .into_iter()
+                .flat_map(|(id, element)| [Object::String(id, StringFormat::Literal), element])
+                .collect();
+            root.set("IDTree", dictionary! { "Names" => names });
+        }
+        if !role_map.is_empty() {
+            root.set("RoleMap", role_map);
+        }
+        if !class_map.is_empty() {
+            root.set("ClassMap", class_map);
+        }
+        doc.objects.insert(root_id, Object::Dictionary(root));
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        catalog.set("StructTreeRoot", root_id);
+        catalog.set("MarkInfo", dictionary! { "Marked" => true });
+
+        Ok(())
+    }
+
+    /// Remove the structure tree from a document, for layouts that no
+    /// longer match it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    pub fn remove_tree(&self, doc: &mut Document) -> Result<()> {
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        catalog.remove(b"StructTreeRoot");
+        catalog.remove(b"MarkInfo");
+        Ok(())
+    }
+}
+
+impl Default for StructureMerger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether a document is tagged, that is, has a structure tree.
+pub fn is_tagged(doc: &Document) -> bool {
+    doc.catalog()
+        .is_ok_and(|catalog| catalog.has(b"StructTreeRoot"))
+}
+
+/// Remove the parts of an input's structure tree that belong to pages
+/// no longer in its page tree.
+///
+/// Content items on those pages are dropped, then elements left with no
+/// content, then the parent tree entries of the pages and their
+/// annotations, and the IDs of the dropped elements. Elements that remain
+/// lose a `/Pg` pointing at a dropped page.
+fn prune_tree(
+    doc: &mut Document,
+    kids: &mut Vec<Object>,
+    parent_tree: &mut Vec<(i64, Object)>,
+    id_tree: &mut Vec<(Vec<u8>, Object)>,
+) {
+    let pages: HashSet<ObjectId> = doc.get_pages().into_values().collect();
+    let dropped_pages: Vec<ObjectId> = doc
+        .objects
+        .iter()
+        .filter(|(id, obj)| !pages.contains(id) && has_type(obj, b"Page"))
+        .map(|(&id, _)| id)
+        .collect();
+    if dropped_pages.is_empty() {
+        return;
+    }
+
+    let mut dropped_keys = HashSet::new();
+    for &page_id in &dropped_pages {
+        let Ok(page) = doc.get_dictionary(page_id) else {
+            continue;
+        };
+        dropped_keys.extend(page.get(b"StructParents").and_then(Object::as_i64).ok());
+        let annots = page
+            .get(b"Annots")
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for annot in annots {
+            let key = doc
+                .dereference(annot)
+                .and_then(|(_, annot)| annot.as_dict())
+                .and_then(|annot| annot.get(b"StructParent"))
+                .and_then(Object::as_i64);
+            dropped_keys.extend(key.ok());
+        }
+    }
+
+    let mut pruner = TreePruner {
+        pages: &pages,
+        seen: HashSet::new(),
+        removed: HashSet::new(),
+    };
+    *kids = pruner.prune_kids(doc, std::mem::take(kids), None);
+    let removed = pruner.removed;
+
+    let is_removed = |obj: &Object| obj.as_reference().is_ok_and(|id| removed.contains(&id));
+    parent_tree.retain_mut(|(key, value)| {
+        if dropped_keys.contains(key) || is_removed(value) {
+            return false;
+        }
+        let items = match value {
+            Object::Reference(id) => match doc.get_object_mut(*id) {
+                Ok(Object::Array(items)) => items,
+                _ => return true,
+            },
+            Object::Array(items) => items,
+            _ => return true,
+        };
+        // Marked content of removed elements no longer has a parent
+        for item in items.iter_mut().filter(|item| is_removed(item)) {
+            *item = Object::Null;
+        }
+        true
+    });
+    id_tree.retain(|(_, element)| !is_removed(element));
+}
+
+/// Walker that removes structure elements on dropped pages.
+struct TreePruner<'a> {
+    /// Pages still in the document.
+    pages: &'a HashSet<ObjectId>,
+
+    /// Elements visited, so a malformed tree with cycles is walked once.
+    seen: HashSet<ObjectId>,
+
+    /// Elements removed from the tree.
+    removed: HashSet<ObjectId>,
+}
+
+impl TreePruner<'_> {
+    /// Keep the kids of an element, or of the root, that have content on
+    /// pages still in the document. `page` is the element's `/Pg`, which
+    /// marked content IDs among the kids are on.
+    fn prune_kids(
+        &mut self,
+        doc: &mut Document,
+        kids: Vec<Object>,
+        page: Option<ObjectId>,
+    ) -> Vec<Object> {
+        kids.into_iter()
+            .filter(|kid| match kid {
+                Object::Integer(_) => self.is_kept(page),
+                Object::Reference(id) => match doc.get_dictionary(*id) {
+                    Ok(dict) if is_content_reference(dict) => {
+                        self.is_kept(dict_page(dict).or(page))
+                    }
+                    Ok(_) => self.prune_element(doc, *id),
+                    Err(_) => true,
+                },
+                Object::Dictionary(dict) if is_content_reference(dict) => {
+                    self.is_kept(dict_page(dict).or(page))
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Prune the kids of an element, and say whether it is kept.
+    fn prune_element(&mut self, doc: &mut Document, id: ObjectId) -> bool {
+        if !self.seen.insert(id) {
+            return true;
+        }
+        let Ok(element) = doc.get_dictionary(id) else {
+            return true;
+        };
+
+        let page = dict_page(element);
+        let kids = match element.get(b"K") {
+            Ok(Object::Array(kids)) => kids.clone(),
+            Ok(kid) => vec![kid.clone()],
+            Err(_) => Vec::new(),
+        };
+        let count = kids.len();
+        let kids = self.prune_kids(doc, kids, page);
+
+        let kept = if count == 0 {
+            self.is_kept(page)
+        } else {
+            !kids.is_empty()
+        };
+        if !kept {
+            self.removed.insert(id);
+            return false;
+        }
+
+        if let Ok(element) = doc.get_dictionary_mut(id) {
+            if kids.len() < count {
+                element.set("K", kids);
+            }
+            if !self.is_kept(page) {
+                element.remove(b"Pg");
+            }
+        }
+        true
+    }
+
+    /// Whether content on `page` is kept; content with no page is.
+    fn is_kept(&self, page: Option<ObjectId>) -> bool {
+        page.is_none_or(|page| self.pages.contains(&page))
+    }
+}
+
+/// Whether a kid of a structure element refers to marked content or an
+/// object, rather than being an element itself.
+fn is_content_reference(dict: &Dictionary) -> bool {
+    matches!(
+        dict.get(b"Type").and_then(Object::as_name),
+        Ok(b"MCR" | b"OBJR")
+    )
+}
+
+/// The page a structure element or content reference is on.
+fn dict_page(dict: &Dictionary) -> Option<ObjectId> {
+    dict.get(b"Pg").and_then(Object::as_reference).ok()
+}
+
+/// Whether an object is a dictionary of the given `/Type`.
+fn has_type(obj: &Object, kind: &[u8]) -> bool {
+    obj.as_dict()
+        .and_then(|dict| dict.get(b"Type"))
+        .and_then(Object::as_name)
+        .is_ok_and(|name| name == kind)
+}
+
+/// Shift the `/StructParents` and `/StructParent` keys in a document.
+///
+/// Returns the key after the highest one before shifting.
+fn shift_keys(doc: &mut Document, offset: i64) -> i64 {
+    let mut next_key = 0;
+
+    for object in doc.objects.values_mut() {
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &mut stream.dict,
+            _ => continue,
+        };
+        shift_dict_keys(dict, offset, &mut next_key);
+
+        // Annotations written directly into a page
+        if let Ok(Object::Array(annots)) = dict.get_mut(b"Annots") {
+            for annot in annots {
+                if let Object::Dictionary(annot) = annot {
+                    shift_dict_keys(annot, offset, &mut next_key);
+                }
+            }
+        }
+    }
+
+    next_key
+}
+
+/// Shift the parent tree keys of one dictionary.
+fn shift_dict_keys(dict: &mut Dictionary, offset: i64, next_key: &mut i64) {
+    for name in [b"StructParents".as_slice(), b"StructParent"] {
+        if let Ok(Object::Integer(key)) = dict.get_mut(name) {
+            *next_key = (*next_key).max(*key + 1);
+            *key += offset;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::pages::PageExtractor;
+    use lopdf::Stream;
+
+    /// A tagged document with one page and one paragraph on it.
+    fn create_tagged_doc(id: &str) -> (Document, ObjectId) {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let root_id = doc.new_object_id();
+
+        let content_id = doc.add_object(Stream::new(
+            Dictionary::new(),
+            b"/P <</MCID 0>> BDC ET EMC".to_vec(),
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Contents" => content_id,
+            "StructParents" => 0,
+        });
+        let paragraph_id = doc.add_object(dictionary! {
+            "Type" => "StructElem",
+            "S" => "Para",
+            "P" => root_id,
+            "Pg" => page_id,
+            "K" => 0,
+            "ID" => Object::string_literal(id),
+        });
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![Object::Reference(page_id)],
+                "Count" => 1,
+            }),
+        );
+        doc.objects.insert(
+            root_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "StructTreeRoot",
+                "K" => paragraph_id,
+                "ParentTree" => dictionary! {
+                    "Nums" => vec![0.into(), vec![Object::Reference(paragraph_id)].into()],
+                },
+                "ParentTreeNextKey" => 1,
+                "IDTree" => dictionary! {
+                    "Names" => vec![Object::string_literal(id), paragraph_id.into()],
+                },
+                "RoleMap" => dictionary! { "Para" => "P" },
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "StructTreeRoot" => root_id,
+            "MarkInfo" => dictionary! { "Marked" => true },
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        (doc, paragraph_id)
+    }
+
+    #[test]
+    fn test_take_tree_shifts_keys() {
+        let (mut doc, _) = create_tagged_doc("p1");
+        let tree = StructureMerger::new().take_tree(&mut doc, 5).unwrap();
+
+        assert_eq!(tree.next_key(), 6);
+        assert_eq!(tree.parent_tree[0].0, 5);
+        let page_id = doc.page_iter().next().unwrap();
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert_eq!(page.get(b"StructParents").unwrap().as_i64().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_take_tree_drops_unselected_pages() {
+        let (mut doc, first_paragraph) = create_tagged_doc("p1");
+        let pages_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"Pages")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let root_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"StructTreeRoot")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+
+        // A second page, with a paragraph on it and a link annotation
+        let link_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "StructParent" => 2,
+        });
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "StructParents" => 1,
+            "Annots" => vec![link_id.into()],
+        });
+        let second_paragraph = doc.add_object(dictionary! {
+            "Type" => "StructElem",
+            "S" => "Para",
+            "P" => root_id,
+            "Pg" => page_id,
+            "K" => vec![
+                0.into(),
+                dictionary! { "Type" => "OBJR", "Obj" => link_id }.into(),
+            ],
+            "ID" => Object::string_literal("p2"),
+        });
+        let pages = doc.get_dictionary_mut(pages_id).unwrap();
+        pages.set(
+            "Kids",
+            vec![
+                pages.get(b"Kids").unwrap().as_array().unwrap()[0].clone(),
+                page_id.into(),
+            ],
+        );
+        pages.set("Count", 2);
+        let root = doc.get_dictionary_mut(root_id).unwrap();
+        root.set("K", vec![first_paragraph.into(), second_paragraph.into()]);
+        root.set(
+            "ParentTree",
+            dictionary! {
+                "Nums" => vec![
+                    0.into(),
+                    vec![Object::Reference(first_paragraph)].into(),
+                    1.into(),
+                    vec![Object::Reference(second_paragraph)].into(),
+                    2.into(),
+                    second_paragraph.into(),
+                ],
+            },
+        );
+        root.set(
+            "IDTree",
+            dictionary! {
+                "Names" => vec![
+                    Object::string_literal("p1"),
+                    first_paragraph.into(),
+                    Object::string_literal("p2"),
+                    second_paragraph.into(),
+                ],
+            },
+        );
+
+        let mut doc = PageExtractor::new().select_pages(&doc, &[1]).unwrap();
+        let tree = StructureMerger::new().take_tree(&mut doc, 0).unwrap();
+
+        assert_eq!(tree.kids, vec![Object::Reference(first_paragraph)]);
+        let keys: Vec<i64> = tree.parent_tree.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![0]);
+        assert_eq!(tree.id_tree.len(), 1);
+        assert_eq!(tree.id_tree[0].0, b"p1");
+    }
+
+    #[test]
+    fn test_prune_keeps_content_on_selected_pages() {
+        let (mut doc, paragraph) = create_tagged_doc("p1");
+        let kept_page = doc.page_iter().next().unwrap();
+        let dropped_page = doc.add_object(dictionary! { "Type" => "Page" });
+
+        // A paragraph that continues onto a page that is not selected
+        let element = doc.get_dictionary_mut(paragraph).unwrap();
+        element.set("Pg", dropped_page);
+        element.set(
+            "K",
+            vec![
+                0.into(),
+                dictionary! { "Type" => "MCR", "Pg" => kept_page, "MCID" => 0 }.into(),
+            ],
+        );
+
+        StructureMerger::new().take_tree(&mut doc, 0).unwrap();
+
+        let element = doc.get_dictionary(paragraph).unwrap();
+        assert!(!element.has(b"Pg"));
+        assert_eq!(element.get(b"K").unwrap().as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_take_tree_untagged() {
+        let (mut doc, _) = create_tagged_doc("p1");
+        doc.catalog_mut().unwrap().remove(b"StructTreeRoot");
+        assert!(!is_tagged(&doc));
+        assert!(StructureMerger::new().take_tree(&mut doc, 5).is_none());
+    }
+
+    #[test]
+    fn test_merge_trees() {
+        let merger = StructureMerger::new();
+        let (mut merged, first_paragraph) = create_tagged_doc("p1");
+        let (mut input, _) = create_tagged_doc("p1");
+
+        let mut trees = Vec::new();
+        trees.extend(merger.take_tree(&mut merged, 0));
+        input.renumber_objects_with(merged.max_id + 1);
+        let second_paragraph = input
+            .objects
+            .iter()
+            .find(|(_, obj)| obj.as_dict().is_ok_and(|d| d.has(b"Pg")))
+            .map(|(&id, _)| id)
+            .unwrap();
+        trees.extend(merger.take_tree(&mut input, trees[0].next_key()));
+        merged.max_id = input.max_id;
+        merged.objects.extend(input.objects);
+
+        merger.merge_trees(&mut merged, trees).unwrap();
+
+        assert!(is_tagged(&merged));
+        let root_id = merged
+            .catalog()
+            .unwrap()
+            .get(b"StructTreeRoot")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let root = merged.get_dictionary(root_id).unwrap();
+
+        let kids = root.get(b"K").unwrap().as_array().unwrap();
+        assert_eq!(kids.len(), 2);
+        let parent = |id| {
+            let element = merged.get_dictionary(id).unwrap();
+            element.get(b"P").unwrap().as_reference().unwrap()
+        };
+        assert_eq!(parent(first_paragraph), root_id);
+        assert_eq!(parent(second_paragraph), root_id);
+
+        // Keys 0 and 1, each leading to its own paragraph
+        let nums = root
+            .get(b"ParentTree")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Nums")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(nums.len(), 4);
+        assert_eq!(nums[2].as_i64().unwrap(), 1);
+        let second = nums[3].as_array().unwrap()[0].as_reference().unwrap();
+        assert_eq!(second, second_paragraph);
+        assert_eq!(root.get(b"ParentTreeNextKey").unwrap().as_i64().unwrap(), 2);
+
+        // The duplicate ID is kept by the first paragraph
+        let ids = root
+            .get(b"IDTree")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Names")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1].as_reference().unwrap(), first_paragraph);
+        assert!(
+            root.get(b"RoleMap")
+                .unwrap()
+                .as_dict()
+                .unwrap()
+                .has(b"Para")
+        );
+    }
+
+    #[test]
+    fn test_remove_tree() {
+        let (mut doc, _) = create_tagged_doc("p1");
+        StructureMerger::new().remove_tree(&mut doc).unwrap();
+        assert!(!is_tagged(&doc));
+        assert!(!doc.catalog().unwrap().has(b"MarkInfo"));
+    }
+}
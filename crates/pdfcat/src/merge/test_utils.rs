@@ -0,0 +1,53 @@
+//! Documents shared by the tests of the page editing modules.
+
+use lopdf::{Dictionary, Document, Object, Stream, dictionary};
+
+use crate::merge::font::helvetica;
+
+/// Create a document with a page of each (width, height, rotation), each
+/// drawn by `content`. The pages inherit a Helvetica font named `F1`
+/// from the page tree.
+pub(crate) fn create_doc(pages: &[(i64, i64, i64)], content: &[u8]) -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(helvetica());
+
+    let mut kids = Vec::new();
+    for &(width, height, rotate) in pages {
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.to_vec()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+            "Rotate" => rotate,
+            "Contents" => content_id,
+        });
+        kids.push(Object::Reference(page_id));
+    }
+
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => font_id },
+            },
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+
+    doc
+}
+
+/// Content of a page, by page number.
+pub(crate) fn page_content(doc: &Document, number: u32) -> String {
+    let page_id = doc.get_pages()[&number];
+    String::from_utf8(doc.get_page_content(page_id).unwrap()).unwrap()
+}
@@ -0,0 +1,115 @@
+//! Name and number trees.
+//!
+//! Name trees and number trees map keys (strings or integers) to values
+//! through a tree of nodes: leaf nodes list key-value pairs in `/Names` or
+//! `/Nums`, and other nodes list their children in `/Kids`.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+
+/// Entries of a name tree, in tree order.
+pub(crate) fn name_tree_entries(doc: &Document, root: &Dictionary) -> Vec<(Vec<u8>, Object)> {
+    let mut entries = Vec::new();
+    collect_entries(
+        doc,
+        root,
+        b"Names",
+        &mut HashSet::new(),
+        &mut |key, value| {
+            if let Ok(key) = key.as_str() {
+                entries.push((key.to_vec(), value.clone()));
+            }
+        },
+    );
+    entries
+}
+
+/// Entries of a number tree, in tree order.
+pub(crate) fn number_tree_entries(doc: &Document, root: &Dictionary) -> Vec<(i64, Object)> {
+    let mut entries = Vec::new();
+    collect_entries(
+        doc,
+        root,
+        b"Nums",
+        &mut HashSet::new(),
+        &mut |key, value| {
+            if let Ok(key) = key.as_i64() {
+                entries.push((key, value.clone()));
+            }
+        },
+    );
+    entries
+}
+
+/// Pass the key-value pairs of a tree node and its kids to `add`.
+///
+/// Each kid is read once, so a malformed tree whose kids repeat or form a
+/// cycle doesn't repeat entries.
+fn collect_entries(
+    doc: &Document,
+    node: &Dictionary,
+    pairs_key: &[u8],
+    seen: &mut HashSet<ObjectId>,
+    add: &mut dyn FnMut(&Object, &Object),
+) {
+    if let Ok(pairs) = node.get_deref(pairs_key, doc).and_then(Object::as_array) {
+        for pair in pairs.chunks_exact(2) {
+            add(&pair[0], &pair[1]);
+        }
+    }
+
+    if let Ok(kids) = node.get_deref(b"Kids", doc).and_then(Object::as_array) {
+        for kid in kids {
+            if let Ok(kid_id) = kid.as_reference()
+                && seen.insert(kid_id)
+                && let Ok(kid) = doc.get_dictionary(kid_id)
+            {
+                collect_entries(doc, kid, pairs_key, seen, add);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_number_tree_entries() {
+        let mut doc = Document::with_version("1.7");
+        let kid_id = doc.add_object(dictionary! {
+            "Limits" => vec![5.into(), 6.into()],
+            "Nums" => vec![5.into(), "e".into(), 6.into(), "f".into()],
+        });
+        let root = dictionary! {
+            "Nums" => vec![0.into(), "a".into()],
+            "Kids" => vec![Object::Reference(kid_id)],
+        };
+
+        let keys: Vec<i64> = number_tree_entries(&doc, &root)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec![0, 5, 6]);
+    }
+
+    #[test]
+    fn test_cyclic_tree() {
+        let mut doc = Document::with_version("1.7");
+        let node_id = doc.new_object_id();
+        doc.objects.insert(
+            node_id,
+            Object::Dictionary(dictionary! {
+                "Names" => vec![Object::string_literal("a"), 1.into()],
+                "Kids" => vec![Object::Reference(node_id), Object::Reference(node_id)],
+            }),
+        );
+        let root = dictionary! {
+            "Kids" => vec![Object::Reference(node_id), Object::Reference(node_id)],
+        };
+
+        // A node listed again, or its own kid, is read once
+        assert_eq!(name_tree_entries(&doc, &root).len(), 1);
+    }
+}
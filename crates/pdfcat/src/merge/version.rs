@@ -0,0 +1,301 @@
+//! PDF versions.
+//!
+//! A PDF declares its version in its header, and from PDF 1.4 can raise it
+//! with the catalog's `/Version`. A merged document starts with the first
+//! input's version, which may be older than the features of the other
+//! inputs, such as transparency or layers. The output gets the newest
+//! version of its inputs and of the features it uses, unless a version is
+//! given; features newer than a given version are kept as they are and
+//! reported.
+
+use lopdf::{Dictionary, Document, Object};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::config::PdfVersion;
+use crate::error::{PdfCatError, Result};
+
+/// A feature of a document that needs a later PDF version than 1.3.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionFeature {
+    /// Name of the feature, such as "transparency".
+    pub name: String,
+
+    /// First PDF version with the feature.
+    pub version: PdfVersion,
+}
+
+impl fmt::Display for VersionFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (PDF {})", self.name, self.version)
+    }
+}
+
+/// Manager for the PDF version of documents.
+#[derive(Debug, Clone)]
+pub struct VersionManager;
+
+impl VersionManager {
+    /// Create a new version manager.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the version a document declares.
+    ///
+    /// # Returns
+    ///
+    /// The later of the header version and the catalog's `/Version`, or
+    /// None if neither is a known version.
+    pub fn version(&self, doc: &Document) -> Option<PdfVersion> {
+        let header = doc.version.parse().ok();
+        let catalog = doc
+            .catalog()
+            .ok()
+            .and_then(|catalog| catalog.get_deref(b"Version", doc).ok())
+            .and_then(|version| version.as_name().ok())
+            .and_then(|version| std::str::from_utf8(version).ok()?.parse().ok());
+        header.max(catalog)
+    }
+
+    /// Find the features of a document that need PDF 1.4 or later.
+    ///
+    /// # Returns
+    ///
+    /// Each feature found once, ordered by version.
+    pub fn features(&self, doc: &Document) -> Vec<VersionFeature> {
+        let mut found = BTreeSet::new();
+
+        if let Ok(catalog) = doc.catalog() {
+            if catalog.has(b"OCProperties") {
+                found.insert((PdfVersion::new(1, 5), "layers"));
+            }
+            if catalog.has(b"Collection") {
+                found.insert((PdfVersion::new(1, 7), "portfolios"));
+            }
+            let has_xfa = catalog
+                .get_deref(b"AcroForm", doc)
+                .and_then(Object::as_dict)
+                .is_ok_and(|form| form.has(b"XFA"));
+            if has_xfa {
+                found.insert((PdfVersion::new(1, 5), "XFA forms"));
+            }
+        }
+
+        for object in doc.objects.values() {
+            find_features(object, &mut found);
+        }
+
+        found
+            .into_iter()
+            .map(|(version, name)| VersionFeature {
+                name: name.to_string(),
+                version,
+            })
+            .collect()
+    }
+
+    /// Set the version a document declares, in its header and, from
+    /// PDF 1.4, its catalog.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document has no catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::config::PdfVersion;
+    /// # use pdfcat::merge::VersionManager;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let manager = VersionManager::new();
+    /// let version = PdfVersion::new(1, 4);
+    /// manager.set_version(&mut doc, version)?;
+    /// for feature in manager.features(&doc) {
+    ///     if feature.version > version {
+    ///         println!("PDF {version} does not support {feature}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_version(&self, doc: &mut Document, version: PdfVersion) -> Result<()> {
+        doc.version = version.to_string();
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        if version >= PdfVersion::new(1, 4) {
+            catalog.set("Version", Object::Name(version.to_string().into_bytes()));
+        } else {
+            catalog.remove(b"Version");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for VersionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Add the features of an object, and of the dictionaries and arrays in
+/// it, to those found.
+fn find_features(object: &Object, found: &mut BTreeSet<(PdfVersion, &'static str)>) {
+    match object {
+        Object::Dictionary(dict) => find_dict_features(dict, false, found),
+        Object::Stream(stream) => find_dict_features(&stream.dict, true, found),
+        Object::Array(array) => {
+            for item in array {
+                find_features(item, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add the features of a dictionary to those found.
+fn find_dict_features(
+    dict: &Dictionary,
+    is_stream: bool,
+    found: &mut BTreeSet<(PdfVersion, &'static str)>,
+) {
+    let name = |key: &[u8]| dict.get(key).and_then(Object::as_name).ok();
+
+    // Soft masks, constant opacity below 1, blend modes and transparency
+    // groups all need a reader that composites transparency
+    let soft_mask = dict.get(b"SMask").is_ok_and(|mask| mask.as_name().is_err());
+    let opacity = [b"CA".as_slice(), b"ca"].iter().any(|key| {
+        dict.get(key)
+            .and_then(Object::as_float)
+            .is_ok_and(|alpha| alpha < 1.0)
+    });
+    let blend_mode = dict
+        .get(b"BM")
+        .is_ok_and(|mode| !matches!(mode.as_name(), Ok(b"Normal" | b"Compatible")));
+    if soft_mask || opacity || blend_mode || name(b"S") == Some(b"Transparency") {
+        found.insert((PdfVersion::new(1, 4), "transparency"));
+    }
+
+    if is_stream {
+        let filters = match dict.get(b"Filter") {
+            Ok(Object::Array(filters)) => filters.iter().collect(),
+            Ok(filter) => vec![filter],
+            Err(_) => Vec::new(),
+        };
+        found.extend(
+            filters
+                .into_iter()
+                .filter_map(|filter| match filter.as_name() {
+                    Ok(b"JBIG2Decode") => Some((PdfVersion::new(1, 4), "JBIG2 images")),
+                    Ok(b"JPXDecode") => Some((PdfVersion::new(1, 5), "JPEG 2000 images")),
+                    _ => None,
+                }),
+        );
+
+        // Font programs of OpenType fonts are embedded as `/FontFile3`
+        // streams of subtype `/OpenType`
+        if name(b"Subtype") == Some(b"OpenType") {
+            found.insert((PdfVersion::new(1, 6), "OpenType fonts"));
+        }
+    }
+
+    for (_, value) in dict.iter() {
+        if matches!(value, Object::Dictionary(_) | Object::Array(_)) {
+            find_features(value, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{Stream, dictionary};
+
+    fn create_doc(version: &str) -> Document {
+        let mut doc = Document::with_version(version);
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog" });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    fn names(features: &[VersionFeature]) -> Vec<&str> {
+        features.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_version() {
+        let manager = VersionManager::new();
+        let mut doc = create_doc("1.4");
+        assert_eq!(manager.version(&doc), Some(PdfVersion::new(1, 4)));
+
+        // The catalog can raise the header's version
+        doc.catalog_mut().unwrap().set("Version", "1.6");
+        assert_eq!(manager.version(&doc), Some(PdfVersion::new(1, 6)));
+
+        assert_eq!(manager.version(&create_doc("PDF")), None);
+    }
+
+    #[test]
+    fn test_set_version() {
+        let manager = VersionManager::new();
+        let mut doc = create_doc("1.4");
+        manager
+            .set_version(&mut doc, PdfVersion::new(1, 7))
+            .unwrap();
+        assert_eq!(doc.version, "1.7");
+        assert_eq!(
+            doc.catalog()
+                .unwrap()
+                .get(b"Version")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"1.7"
+        );
+
+        manager
+            .set_version(&mut doc, PdfVersion::new(1, 3))
+            .unwrap();
+        assert_eq!(manager.version(&doc), Some(PdfVersion::new(1, 3)));
+        assert!(!doc.catalog().unwrap().has(b"Version"));
+    }
+
+    #[test]
+    fn test_features() {
+        let manager = VersionManager::new();
+        let mut doc = create_doc("1.7");
+        assert!(manager.features(&doc).is_empty());
+
+        // Opacity in a direct graphics state of a page's resources
+        doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Resources" => dictionary! {
+                "ExtGState" => dictionary! {
+                    "GS0" => dictionary! { "ca" => 0.5, "BM" => "Normal" },
+                    "GS1" => dictionary! { "CA" => 1, "SMask" => "None" },
+                },
+            },
+        });
+        doc.add_object(Stream::new(
+            dictionary! { "Subtype" => "Image", "Filter" => vec!["JPXDecode".into()] },
+            Vec::new(),
+        ));
+        doc.catalog_mut().unwrap().set(
+            "OCProperties",
+            dictionary! { "OCGs" => Vec::<Object>::new() },
+        );
+
+        let features = manager.features(&doc);
+        assert_eq!(
+            names(&features),
+            vec!["transparency", "JPEG 2000 images", "layers"]
+        );
+        assert_eq!(features[0].to_string(), "transparency (PDF 1.4)");
+    }
+}
@@ -0,0 +1,233 @@
+//! Viewer settings.
+//!
+//! The catalog tells viewers how to show a document when it opens: which
+//! panel to show (`/PageMode`), how to arrange pages (`/PageLayout`),
+//! window options (`/ViewerPreferences`) and the page and zoom to open at
+//! (`/OpenAction`). A merged document starts with the first input's
+//! catalog, so these settings are written over the first input's.
+
+use lopdf::{Document, Object};
+
+use crate::config::{OpenAt, ViewerSettings, Zoom};
+use crate::error::{PdfCatError, Result};
+
+/// Configurator for the viewer settings of a document.
+#[derive(Debug, Clone)]
+pub struct ViewerConfigurator;
+
+impl ViewerConfigurator {
+    /// Create a new viewer configurator.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Write viewer settings to a document's catalog.
+    ///
+    /// Settings left unset keep the document's own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the page to open at is past the end of the
+    /// document, or the document has no catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::config::{PageMode, ViewerSettings};
+    /// # use pdfcat::merge::ViewerConfigurator;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let settings = ViewerSettings {
+    ///     page_mode: Some(PageMode::Outline),
+    ///     open_at: Some("1:fit".parse()?),
+    ///     ..ViewerSettings::default()
+    /// };
+    /// ViewerConfigurator::new().configure(&mut doc, &settings)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn configure(&self, doc: &mut Document, settings: &ViewerSettings) -> Result<()> {
+        let open_action = settings
+            .open_at
+            .map(|open_at| destination(doc, open_at))
+            .transpose()?;
+
+        let mut preferences = doc
+            .catalog()
+            .ok()
+            .and_then(|catalog| catalog.get_deref(b"ViewerPreferences", doc).ok())
+            .and_then(|obj| obj.as_dict().ok())
+            .cloned()
+            .unwrap_or_default();
+        if settings.display_doc_title {
+            preferences.set("DisplayDocTitle", true);
+        }
+        if settings.fit_window {
+            preferences.set("FitWindow", true);
+        }
+
+        let catalog = doc
+            .catalog_mut()
+            .map_err(|err| PdfCatError::merge_failed(format!("Failed to get catalog: {err}")))?;
+        if let Some(mode) = settings.page_mode {
+            catalog.set("PageMode", Object::Name(mode.as_name().as_bytes().to_vec()));
+        }
+        if let Some(layout) = settings.page_layout {
+            catalog.set(
+                "PageLayout",
+                Object::Name(layout.as_name().as_bytes().to_vec()),
+            );
+        }
+        if settings.display_doc_title || settings.fit_window {
+            catalog.set("ViewerPreferences", preferences);
+        }
+        if let Some(open_action) = open_action {
+            catalog.set("OpenAction", open_action);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ViewerConfigurator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Destination array for a page and zoom.
+fn destination(doc: &Document, open_at: OpenAt) -> Result<Object> {
+    let pages = doc.get_pages();
+    let page_id = pages.get(&open_at.page).ok_or_else(|| {
+        PdfCatError::invalid_config(format!(
+            "Cannot open at page {}: the output has {} pages",
+            open_at.page,
+            pages.len()
+        ))
+    })?;
+
+    let mut destination = vec![Object::Reference(*page_id)];
+    match open_at.zoom {
+        Some(Zoom::Fit) => destination.push("Fit".into()),
+        Some(Zoom::FitWidth) => destination.extend(["FitH".into(), Object::Null]),
+        Some(Zoom::Percent(percent)) => destination.extend([
+            "XYZ".into(),
+            Object::Null,
+            Object::Null,
+            Object::Real(percent / 100.0),
+        ]),
+        // Null coordinates and zoom keep the viewer's
+        None => destination.extend(["XYZ".into(), Object::Null, Object::Null, Object::Null]),
+    }
+
+    Ok(Object::Array(destination))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PageLayout, PageMode};
+    use lopdf::{ObjectId, dictionary};
+
+    fn create_doc(pages: usize) -> (Document, Vec<ObjectId>) {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_ids: Vec<ObjectId> = (0..pages)
+            .map(|_| {
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                })
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => page_ids.iter().map(|&id| Object::Reference(id)).collect::<Vec<_>>(),
+                "Count" => pages as i64,
+            }),
+        );
+        let prefs_id = doc.add_object(dictionary! { "HideToolbar" => true });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "PageMode" => "UseThumbs",
+            "ViewerPreferences" => prefs_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        (doc, page_ids)
+    }
+
+    #[test]
+    fn test_configure() {
+        let (mut doc, page_ids) = create_doc(3);
+        let settings = ViewerSettings {
+            page_mode: Some(PageMode::Outline),
+            page_layout: Some(PageLayout::TwoPageRight),
+            display_doc_title: true,
+            fit_window: false,
+            open_at: Some("2:150%".parse().unwrap()),
+        };
+        ViewerConfigurator::new()
+            .configure(&mut doc, &settings)
+            .unwrap();
+
+        let catalog = doc.catalog().unwrap();
+        let name = |key: &[u8]| catalog.get(key).unwrap().as_name().unwrap().to_vec();
+        assert_eq!(name(b"PageMode"), b"UseOutlines");
+        assert_eq!(name(b"PageLayout"), b"TwoPageRight");
+
+        // The input's preferences are kept
+        let prefs = catalog
+            .get(b"ViewerPreferences")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert!(prefs.get(b"HideToolbar").unwrap().as_bool().unwrap());
+        assert!(prefs.get(b"DisplayDocTitle").unwrap().as_bool().unwrap());
+        assert!(!prefs.has(b"FitWindow"));
+
+        let action = catalog.get(b"OpenAction").unwrap().as_array().unwrap();
+        assert_eq!(action[0].as_reference().unwrap(), page_ids[1]);
+        assert_eq!(action[1].as_name().unwrap(), b"XYZ");
+        assert_eq!(action[4].as_float().unwrap(), 1.5);
+    }
+
+    #[test]
+    fn test_configure_keeps_unset() {
+        let (mut doc, _) = create_doc(1);
+        ViewerConfigurator::new()
+            .configure(&mut doc, &ViewerSettings::default())
+            .unwrap();
+
+        let catalog = doc.catalog().unwrap();
+        assert_eq!(
+            catalog.get(b"PageMode").unwrap().as_name().unwrap(),
+            b"UseThumbs"
+        );
+        assert!(
+            catalog
+                .get(b"ViewerPreferences")
+                .unwrap()
+                .as_reference()
+                .is_ok()
+        );
+        assert!(!catalog.has(b"OpenAction"));
+    }
+
+    #[test]
+    fn test_open_at_past_end() {
+        let (mut doc, _) = create_doc(2);
+        let settings = ViewerSettings {
+            open_at: Some("3:fit".parse().unwrap()),
+            ..ViewerSettings::default()
+        };
+        assert!(
+            ViewerConfigurator::new()
+                .configure(&mut doc, &settings)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Text and PDF page watermarks.
+//!
+//! A watermark is drawn over or under the content of each selected page,
+//! centered on the page as it is displayed, with the opacity set through
+//! an ExtGState resource. Pages of another PDF are imported as Form
+//! XObjects, together with every object their resources refer to.
+
+use lopdf::{Document, Object, ObjectId, dictionary};
+use std::path::Path;
+
+use crate::config::{Watermark, WatermarkLayer};
+use crate::error::{PdfCatError, Result};
+use crate::merge::font::{encode_win_ansi, helvetica, string_literal, text_width};
+use crate::merge::layout::{
+    Rect, add_page_resource, append_content, displayed_matrix, displayed_size, invert_matrix,
+    page_box, page_rotation, page_to_form, placement_matrix, prepend_content,
+};
+use crate::utils::copy_references;
+
+/// Share of the available length that automatically sized text covers.
+const TEXT_COVERAGE: f32 = 0.8;
+
+/// Gray level of watermark text.
+const TEXT_GRAY: f32 = 0.5;
+
+/// Height of Helvetica capitals, in thousandths of the font size.
+const CAP_HEIGHT: f32 = 718.0;
+
+/// Watermarker that draws text or PDF pages on pages.
+#[derive(Debug, Clone)]
+pub struct Watermarker;
+
+impl Watermarker {
+    /// Create a new watermarker.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw a line of text on the pages selected by `watermark`.
+    ///
+    /// The text is set in gray Helvetica, centered and rotated by the
+    /// watermark's angle, or along each page's diagonal if it has none.
+    /// Without a font size, text is made as large as fits the page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a page dictionary cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use pdfcat::merge::watermark::Watermarker;
+    /// # use pdfcat::config::Watermark;
+    /// # use lopdf::Document;
+    /// # fn example(mut doc: Document) -> Result<(), Box<dyn std::error::Error>> {
+    /// let watermark = Watermark::text("CONFIDENTIAL");
+    /// Watermarker::new().add_text(&mut doc, &watermark, "CONFIDENTIAL")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_text(&self, doc: &mut Document, watermark: &Watermark, text: &str) -> Result<()> {
+        let font_id = doc.add_object(helvetica());
+        let font_name = format!("PdfCatWatermark{}", font_id.0);
+        let encoded = encode_win_ansi(text);
+
+        self.draw(
+            doc,
+            watermark,
+            ("Font", &font_name, font_id),
+            |width, height| {
+                let angle = watermark
+                    .angle
+                    .map_or_else(|| height.atan2(width), f32::to_radians);
+                let size = watermark
+                    .font_size
+                    .unwrap_or_else(|| auto_font_size(&encoded, angle, width, height));
+                let text_width = text_width(&encoded, size);
+
+                format!(
+                    "{} cm {TEXT_GRAY} g BT /{font_name} {size} Tf {} {} Td {} Tj ET",
+                    rotation_about(angle, width / 2.0, height / 2.0),
+                    -text_width / 2.0,
+                    -size * CAP_HEIGHT / 2000.0,
+                    string_literal(&encoded)
+                )
+            },
+        )
+    }
+
+    /// Draw a page of another PDF on the pages selected by `watermark`.
+    ///
+    /// The page is scaled to fit each page, keeping its aspect ratio, and
+    /// centered. A watermark angle rotates it about the page's center.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document to watermark
+    /// * `watermark` - Layer, opacity, angle and pages
+    /// * `path` - Path of `source`, used in error messages
+    /// * `source` - The PDF to take the page from
+    /// * `page` - 1-indexed page number in `source`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` has no such page, or a page dictionary
+    /// cannot be read.
+    pub fn add_page(
+        &self,
+        doc: &mut Document,
+        watermark: &Watermark,
+        path: &Path,
+        source: &Document,
+        page: u32,
+    ) -> Result<()> {
+        let (form_id, bbox, rotation) = import_page(doc, path, source, page)?;
+        let form_name = format!("PdfCatWatermark{}", form_id.0);
+
+        self.draw(
+            doc,
+            watermark,
+            ("XObject", &form_name, form_id),
+            |width, height| {
+                let placement = placement_matrix(bbox, rotation, [0.0, 0.0, width, height]);
+                let rotate = match watermark.angle {
+                    Some(angle) if angle % 360.0 != 0.0 => format!(
+                        "{} cm 1 0 0 1 {} {} cm ",
+                        rotation_about(angle.to_radians(), width / 2.0, height / 2.0),
+                        -width / 2.0,
+                        -height / 2.0
+                    ),
+                    _ => String::new(),
+                };
+
+                format!(
+                    "{rotate}{} cm /{form_name} Do",
+                    placement.map(|value| value.to_string()).join(" ")
+                )
+            },
+        )
+    }
+
+    /// Add the operators from `draw`, which get the displayed page size
+    /// and draw in displayed coordinates, to each selected page.
+    fn draw(
+        &self,
+        doc: &mut Document,
+        watermark: &Watermark,
+        resource: (&str, &str, ObjectId),
+        draw: impl Fn(f32, f32) -> String,
+    ) -> Result<()> {
+        let (category, name, resource_id) = resource;
+        let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let selected = watermark
+            .pages
+            .as_ref()
+            .map(|range| range.to_pages(page_ids.len() as u32));
+
+        let state_id = doc.add_object(dictionary! {
+            "Type" => "ExtGState",
+            "ca" => watermark.opacity,
+            "CA" => watermark.opacity,
+        });
+        let state_name = format!("PdfCatWatermark{}", state_id.0);
+
+        for (index, &page_id) in page_ids.iter().enumerate() {
+            if let Some(ref selected) = selected
+                && !selected.contains(&(index as u32 + 1))
+            {
+                continue;
+            }
+
+            let (width, height) =
+                displayed_size(page_box(doc, page_id), page_rotation(doc, page_id));
+            let content = format!(
+                "q {} cm /{state_name} gs {}\nQ\n",
+                invert_matrix(displayed_matrix(doc, page_id))
+                    .map(|value| value.to_string())
+                    .join(" "),
+                draw(width, height)
+            );
+
+            match watermark.layer {
+                WatermarkLayer::Over => append_content(doc, page_id, &content)?,
+                WatermarkLayer::Under => prepend_content(doc, page_id, &content)?,
+            }
+            add_page_resource(doc, page_id, "ExtGState", &state_name, state_id)?;
+            add_page_resource(doc, page_id, category, name, resource_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Watermarker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copy a page of `source` into `doc` as a Form XObject.
+///
+/// Returns the form, the page's visible box and its rotation.
+fn import_page(
+    doc: &mut Document,
+    path: &Path,
+    source: &Document,
+    page: u32,
+) -> Result<(ObjectId, Rect, i64)> {
+    // Move the source's objects above the document's, so they can be
+    // copied without clashing
+    let mut source = source.clone();
+    source.renumber_objects_with(doc.max_id + 1);
+
+    let pages = source.get_pages();
+    let page_id = *pages
+        .get(&page)
+        .ok_or_else(|| PdfCatError::InvalidPageRange {
+            path: path.to_path_buf(),
+            range: page.to_string(),
+            total_pages: pages.len(),
+        })?;
+
+    let form_id = page_to_form(&mut source, page_id)?;
+    copy_references(doc, &source, &Object::Reference(form_id));
+    doc.max_id = doc.max_id.max(source.max_id);
+
+    Ok((
+        form_id,
+        page_box(&source, page_id),
+        page_rotation(&source, page_id),
+    ))
+}
+
+/// The largest font size at which text at `angle` fits the page, through
+/// its center, with some room to spare.
+fn auto_font_size(encoded: &[u8], angle: f32, width: f32, height: f32) -> f32 {
+    let (sin, cos) = angle.sin_cos();
+    let along_width = if cos.abs() > 1e-3 {
+        width / cos.abs()
+    } else {
+        f32::INFINITY
+    };
+    let along_height = if sin.abs() > 1e-3 {
+        height / sin.abs()
+    } else {
+        f32::INFINITY
+    };
+
+    let length = along_width.min(along_height) * TEXT_COVERAGE;
+    length / text_width(encoded, 1.0).max(0.001)
+}
+
+/// `cm` operands for a counterclockwise rotation by `angle` radians
+/// about the point (x, y), which becomes the new origin.
+fn rotation_about(angle: f32, x: f32, y: f32) -> String {
+    let (sin, cos) = angle.sin_cos();
+    [cos, sin, -sin, cos, x, y]
+        .map(|value| (value + 0.0).to_string())
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PageRange, WatermarkContent};
+    use crate::merge::test_utils::{create_doc, page_content};
+
+    fn resource_names(doc: &Document, number: u32, category: &[u8]) -> Vec<String> {
+        let page = doc.get_dictionary(doc.get_pages()[&number]).unwrap();
+        let resources = page.get(b"Resources").unwrap().as_dict().unwrap();
+        resources
+            .get(category)
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .iter()
+            .map(|(name, _)| String::from_utf8_lossy(name).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_add_text_over() {
+        let mut doc = create_doc(&[(612, 792, 0), (612, 792, 0)], b"0 0 m 10 10 l S");
+        let mut watermark = Watermark::text("DRAFT");
+        watermark.pages = Some(PageRange::parse("2").unwrap());
+
+        Watermarker::new()
+            .add_text(&mut doc, &watermark, "DRAFT")
+            .unwrap();
+
+        assert_eq!(page_content(&doc, 1), "0 0 m 10 10 l S");
+
+        let content = page_content(&doc, 2);
+        assert!(content.starts_with("q\n0 0 m 10 10 l S\nQ\nq 1 0 0 1 0 0 cm /PdfCatWatermark"));
+        assert!(content.contains("(DRAFT) Tj ET\nQ"));
+
+        let states = resource_names(&doc, 2, b"ExtGState");
+        assert_eq!(states.len(), 1);
+        let fonts = resource_names(&doc, 2, b"Font");
+        assert_eq!(fonts.len(), 2);
+        assert!(fonts.contains(&"F1".to_string()));
+    }
+
+    #[test]
+    fn test_add_text_under_rotated_page() {
+        let mut doc = create_doc(&[(612, 792, 90)], b"0 0 m 10 10 l S");
+        let watermark = Watermark {
+            layer: WatermarkLayer::Under,
+            angle: Some(0.0),
+            font_size: Some(40.0),
+            ..Watermark::text("COPY")
+        };
+
+        Watermarker::new()
+            .add_text(&mut doc, &watermark, "COPY")
+            .unwrap();
+
+        // Drawn first, centered on the landscape page as displayed
+        let content = page_content(&doc, 1);
+        assert!(content.starts_with("q 0 1 -1 0 612 0 cm /PdfCatWatermark"));
+        assert!(content.contains("1 0 0 1 396 306 cm"));
+        assert!(content.trim_end().ends_with("0 0 m 10 10 l S"));
+    }
+
+    #[test]
+    fn test_add_page() {
+        let mut doc = create_doc(&[(612, 792, 0)], b"0 0 m 10 10 l S");
+        let source = create_doc(
+            &[(100, 100, 0), (306, 396, 0)],
+            b"BT /F1 12 Tf (Letterhead) Tj ET",
+        );
+        let watermark = Watermark::page("letterhead.pdf", 2);
+
+        Watermarker::new()
+            .add_page(
+                &mut doc,
+                &watermark,
+                Path::new("letterhead.pdf"),
+                &source,
+                2,
+            )
+            .unwrap();
+
+        // Half-size page scaled by 2 to fit
+        let content = page_content(&doc, 1);
+        assert!(content.contains("2 0 0 2 0 0 cm /PdfCatWatermark"));
+
+        let forms = resource_names(&doc, 1, b"XObject");
+        let page = doc.get_dictionary(doc.get_pages()[&1]).unwrap();
+        let form_id = page
+            .get(b"Resources")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"XObject")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(forms[0].as_bytes())
+            .unwrap()
+            .as_reference()
+            .unwrap();
+
+        // The form and the font its resources refer to were copied
+        let form = doc.get_object(form_id).unwrap().as_stream().unwrap();
+        let font_ref = form
+            .dict
+            .get(b"Resources")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Font")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"F1")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        assert!(doc.get_object(font_ref).is_ok());
+        assert!(doc.max_id >= form_id.0);
+    }
+
+    #[test]
+    fn test_add_page_missing() {
+        let mut doc = create_doc(&[(612, 792, 0)], b"");
+        let source = create_doc(&[(612, 792, 0)], b"");
+        let watermark = Watermark::page("one-page.pdf", 3);
+        assert_eq!(
+            watermark.content,
+            WatermarkContent::Page {
+                path: "one-page.pdf".into(),
+                page: 3
+            }
+        );
+
+        let result = Watermarker::new().add_page(
+            &mut doc,
+            &watermark,
+            Path::new("one-page.pdf"),
+            &source,
+            3,
+        );
+        assert!(matches!(
+            result,
+            Err(PdfCatError::InvalidPageRange { total_pages: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_auto_font_size() {
+        let width = text_width(b"DRAFT", 1.0);
+
+        // Horizontal text is limited by the page width
+        let size = auto_font_size(b"DRAFT", 0.0, 612.0, 792.0);
+        assert!((size * width - 612.0 * TEXT_COVERAGE).abs() < 0.1);
+
+        // Upright text is limited by the page height
+        let size = auto_font_size(b"DRAFT", std::f32::consts::FRAC_PI_2, 612.0, 792.0);
+        assert!((size * width - 792.0 * TEXT_COVERAGE).abs() < 0.1);
+    }
+}
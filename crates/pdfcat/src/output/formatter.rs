@@ -14,7 +14,9 @@
 //! formatter.error("Something went wrong");
 //! ```
 
-use crate::config::Config;
+use crate::config::{Config, OutputFormat};
+use crate::error::Result;
+use serde::Serialize;
 use std::io::{self, Write};
 
 /// Level of output message.
@@ -41,6 +43,8 @@ pub struct OutputFormatter {
     verbose: bool,
     /// Whether to use colored output.
     colored: bool,
+    /// Whether stdout is reserved for a JSON document.
+    json: bool,
 }
 
 impl OutputFormatter {
@@ -55,6 +59,7 @@ impl OutputFormatter {
             quiet,
             verbose,
             colored: Self::should_use_color(),
+            json: false,
         }
     }
 
@@ -64,7 +69,10 @@ impl OutputFormatter {
     ///
     /// * `config` - Configuration containing output settings
     pub fn from_config(config: &Config) -> Self {
-        Self::new(config.quiet, config.verbose)
+        match config.output_format {
+            OutputFormat::Text => Self::new(config.quiet, config.verbose),
+            OutputFormat::Json => Self::json(),
+        }
     }
 
     /// Create a quiet formatter (only errors).
@@ -77,6 +85,19 @@ impl OutputFormatter {
         Self::new(false, true)
     }
 
+    /// Create a formatter for JSON output.
+    ///
+    /// Human-readable messages are suppressed, and warnings and errors
+    /// go to stderr so that stdout only carries the JSON document.
+    pub fn json() -> Self {
+        Self {
+            quiet: true,
+            verbose: false,
+            colored: false,
+            json: true,
+        }
+    }
+
     /// Detect if colored output should be used.
     ///
     /// Returns true if stdout is a TTY and TERM is set.
@@ -158,7 +179,9 @@ impl OutputFormatter {
 
         let reset = "\x1b[0m";
 
-        if self.colored && !color_code.is_empty() {
+        if self.json {
+            eprintln!("{prefix}{message}");
+        } else if self.colored && !color_code.is_empty() {
             println!("{color_code}{prefix}{message}{reset}");
         } else {
             println!("{prefix}{message}");
@@ -285,6 +308,27 @@ impl OutputFormatter {
     pub fn is_quiet(&self) -> bool {
         self.quiet
     }
+
+    /// Check if JSON output is enabled.
+    pub fn is_json(&self) -> bool {
+        self.json
+    }
+
+    /// Print a value as a pretty-printed JSON document on stdout.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Value to serialize
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be serialized.
+    pub fn print_json<T: Serialize>(&self, value: &T) -> Result<()> {
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| crate::PdfCatError::other(format!("Failed to serialize output: {e}")))?;
+        println!("{json}");
+        Ok(())
+    }
 }
 
 impl Default for OutputFormatter {
@@ -321,6 +365,17 @@ mod tests {
         assert!(formatter.should_print());
     }
 
+    #[test]
+    fn test_json_formatter() {
+        let formatter = OutputFormatter::json();
+        assert!(formatter.is_json());
+        assert!(formatter.is_quiet());
+        assert!(!formatter.should_print());
+
+        // Warnings go to stderr in JSON mode
+        formatter.warning("Not on stdout");
+    }
+
     #[test]
     fn test_info_message() {
         let formatter = OutputFormatter::new(false, false);
@@ -5,6 +5,7 @@
 //! - Progress indicators
 //! - Error and warning display
 //! - Summary reports
+//! - Machine-readable JSON reports for merge, info and split runs
 //! - Quiet and verbose modes
 //!
 //! # Examples
@@ -22,12 +23,16 @@
 
 pub mod formatter;
 pub mod progress;
+pub mod report;
 
 pub use formatter::{MessageLevel, OutputFormatter};
 pub use progress::{ProgressBar, ProgressStyle};
+pub use report::{InfoReport, RunReport, SplitReport};
 
 use crate::config::Config;
+use crate::inspect::DocumentInfo;
 use crate::io::LoadStatistics;
+use crate::merge::MergeResult;
 use crate::validation::ValidationSummary;
 
 /// Create an output formatter from configuration.
@@ -50,6 +55,12 @@ pub fn create_formatter(config: &Config) -> OutputFormatter {
 /// * `formatter` - Output formatter to use
 /// * `summary` - Validation summary to display
 pub fn display_validation_summary(formatter: &OutputFormatter, summary: &ValidationSummary) {
+    for result in &summary.results {
+        if let Some(ref report) = result.repair {
+            formatter.warning(&format!("Repaired {}: {report}", result.path.display()));
+        }
+    }
+
     if summary.files_failed > 0 {
         formatter.warning(&format!(
             "Warning: {} file(s) failed validation",
@@ -65,6 +76,146 @@ pub fn display_validation_summary(formatter: &OutputFormatter, summary: &Validat
     ));
 }
 
+/// Warn if the validated files have pages of more than one size.
+///
+/// # Arguments
+///
+/// * `formatter` - Output formatter to use
+/// * `summary` - Validation summary to check
+pub fn display_mixed_page_sizes(formatter: &OutputFormatter, summary: &ValidationSummary) {
+    let sizes = summary.page_dimensions();
+    if sizes.len() < 2 {
+        return;
+    }
+
+    let sizes: Vec<String> = sizes
+        .iter()
+        .map(|(width, height)| format!("{} x {} pt", width.round(), height.round()))
+        .collect();
+    formatter.warning(&format!(
+        "Warning: Inputs have mixed page sizes ({}); use --page-size to normalize them",
+        sizes.join(", ")
+    ));
+}
+
+/// Warn if merging will lose the tags of tagged inputs.
+///
+/// # Arguments
+///
+/// * `formatter` - Output formatter to use
+/// * `summary` - Validation summary to check
+/// * `config` - Configuration of the merge
+pub fn display_tag_loss(formatter: &OutputFormatter, summary: &ValidationSummary, config: &Config) {
+    if let Some(reason) = summary.tag_loss(config) {
+        formatter.warning(&format!("Warning: Tags will be lost: {reason}"));
+    }
+}
+
+/// Warn if the merged document uses features its PDF version doesn't
+/// support.
+///
+/// # Arguments
+///
+/// * `formatter` - Output formatter to use
+/// * `result` - Result of the merge
+pub fn display_unsupported_features(formatter: &OutputFormatter, result: &MergeResult) {
+    if result.unsupported_features.is_empty() {
+        return;
+    }
+
+    let features: Vec<String> = result
+        .unsupported_features
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    formatter.warning(&format!(
+        "Warning: The output is PDF {} but uses {}; older readers may not show it correctly",
+        result.statistics.pdf_version,
+        features.join(", ")
+    ));
+}
+
+/// Display inspection results for a single file.
+///
+/// Per-page sizes and rotations are only listed in verbose mode.
+///
+/// # Arguments
+///
+/// * `formatter` - Output formatter to use
+/// * `info` - Inspection results to display
+pub fn display_document_info(formatter: &OutputFormatter, info: &DocumentInfo) {
+    let summary = &info.summary;
+    let yes_no = |value: bool| if value { "Yes" } else { "No" };
+    let field = |label: &str, value: &str| formatter.info(&format!("  {label}: {value}"));
+
+    formatter.section(&summary.path.display().to_string());
+
+    if let Some(ref report) = summary.repair {
+        formatter.warning(&format!("Repaired: {report}"));
+    }
+
+    if let Some((major, minor)) = summary.version {
+        field("PDF version", &format!("{major}.{minor}"));
+    }
+    field("Pages", &summary.page_count.to_string());
+    field("File size", &summary.format_file_size());
+    field("Objects", &summary.object_count.to_string());
+    field("Encrypted", yes_no(summary.is_encrypted));
+    field("Outline", yes_no(info.has_outline));
+
+    let metadata = [
+        ("Title", &info.metadata.title),
+        ("Author", &info.metadata.author),
+        ("Subject", &info.metadata.subject),
+        ("Keywords", &info.metadata.keywords),
+    ];
+    for (label, value) in metadata {
+        if let Some(value) = value {
+            field(label, value);
+        }
+    }
+
+    let lists = [
+        ("Fonts", &info.fonts),
+        ("Attachments", &info.attachments),
+        ("Form fields", &info.form_fields),
+    ];
+    for (label, values) in lists {
+        let value = if values.is_empty() {
+            "None".to_string()
+        } else {
+            values.join(", ")
+        };
+        field(label, &value);
+    }
+
+    if formatter.is_verbose() {
+        for page in &info.pages {
+            field(
+                &format!("Page {}", page.number),
+                &format!(
+                    "{} x {} pt, rotated {}°",
+                    page.width, page.height, page.rotation
+                ),
+            );
+        }
+    } else if let Some(first) = info.pages.first() {
+        let uniform = info
+            .pages
+            .iter()
+            .all(|p| p.width == first.width && p.height == first.height);
+        let size = format!("{} x {} pt", first.width, first.height);
+        field(
+            "Page size",
+            if uniform {
+                &size
+            } else {
+                "Mixed (use --verbose)"
+            },
+        );
+    }
+}
+
 /// Display load statistics to the user.
 ///
 /// # Arguments
@@ -91,7 +242,10 @@ pub fn display_load_statistics(formatter: &OutputFormatter, stats: &LoadStatisti
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{CompressionLevel, Config, Metadata, OverwriteMode};
+    use crate::config::{
+        CompressionLevel, Config, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit,
+        PageLabels, PageRangePolicy, ViewerSettings,
+    };
     use std::path::PathBuf;
 
     fn create_test_config(quiet: bool, verbose: bool) -> Config {
@@ -106,9 +260,33 @@ mod tests {
             compression: CompressionLevel::Standard,
             metadata: Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: ViewerSettings::default(),
+            pdf_version: None,
+            output_format: OutputFormat::Text,
         }
     }
 
@@ -0,0 +1,286 @@
+//! Machine-readable run reports.
+//!
+//! This module defines the JSON documents printed when `--format json` is
+//! used. A single [`RunReport`] describes a whole merge run and an
+//! [`InfoReport`] describes an `info` run, so scripts can consume one
+//! document instead of scraping human-readable output.
+//!
+//! # Examples
+//!
+//! ```
+//! use pdfcat::output::report::RunReport;
+//! use std::path::PathBuf;
+//!
+//! let mut report = RunReport::new(PathBuf::from("merged.pdf"), false);
+//! report.success = true;
+//!
+//! let json = report.to_json().unwrap();
+//! assert!(json.contains("\"success\": true"));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::error::{ErrorReport, PdfCatError, Result};
+use crate::inspect::DocumentInfo;
+use crate::io::RepairReport;
+use crate::io::writer::WriteStatistics;
+use crate::merge::{MergeResult, MergeStatistics, SanitizeReport, VersionFeature};
+use crate::split::SplitOutput;
+use crate::validation::{SkippedFile, ValidationResult, ValidationSummary};
+
+/// A repaired input file and what was recovered from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairedFile {
+    /// Path to the repaired file.
+    pub path: PathBuf,
+
+    /// What was recovered.
+    pub report: RepairReport,
+}
+
+/// A sanitized input file and what was removed from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanitizedFile {
+    /// Path to the sanitized file.
+    pub path: PathBuf,
+
+    /// What was removed.
+    pub report: SanitizeReport,
+}
+
+/// Structured description of a complete pdfcat run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    /// Version of pdfcat that produced the report.
+    pub version: String,
+
+    /// Whether the run completed successfully.
+    pub success: bool,
+
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+
+    /// Requested output path.
+    pub output_path: PathBuf,
+
+    /// Inputs that passed validation.
+    pub inputs: Vec<ValidationResult>,
+
+    /// Inputs that were skipped because of errors.
+    pub skipped: Vec<SkippedFile>,
+
+    /// Inputs that had to be repaired.
+    pub repaired: Vec<RepairedFile>,
+
+    /// Inputs that were sanitized, with what was removed from each.
+    pub sanitized: Vec<SanitizedFile>,
+
+    /// Features of the output that its PDF version doesn't support.
+    pub unsupported_features: Vec<VersionFeature>,
+
+    /// Statistics about the merge, if one was performed.
+    pub statistics: Option<MergeStatistics>,
+
+    /// Details about the written output file, if one was written.
+    pub output: Option<WriteStatistics>,
+
+    /// The error that ended the run, if it failed.
+    pub error: Option<ErrorReport>,
+}
+
+impl RunReport {
+    /// Create an empty report for a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_path` - Requested output path
+    /// * `dry_run` - Whether this is a dry run
+    pub fn new(output_path: PathBuf, dry_run: bool) -> Self {
+        Self {
+            version: crate::VERSION.to_string(),
+            success: false,
+            dry_run,
+            output_path,
+            inputs: Vec::new(),
+            skipped: Vec::new(),
+            repaired: Vec::new(),
+            sanitized: Vec::new(),
+            unsupported_features: Vec::new(),
+            statistics: None,
+            output: None,
+            error: None,
+        }
+    }
+
+    /// Record the outcome of input validation.
+    pub fn record_validation(&mut self, summary: &ValidationSummary) {
+        self.inputs = summary.results.clone();
+        self.skipped = summary.skipped.clone();
+        self.repaired = summary
+            .results
+            .iter()
+            .filter_map(|result| {
+                result.repair.clone().map(|report| RepairedFile {
+                    path: result.path.clone(),
+                    report,
+                })
+            })
+            .collect();
+    }
+
+    /// Record the outcome of the merge.
+    pub fn record_merge(&mut self, result: &MergeResult) {
+        self.statistics = Some(result.statistics.clone());
+        self.sanitized = result
+            .sanitized_files
+            .iter()
+            .map(|(path, report)| SanitizedFile {
+                path: path.clone(),
+                report: report.clone(),
+            })
+            .collect();
+        self.unsupported_features = result.unsupported_features.clone();
+
+        for skipped in &result.skipped_files {
+            if !self.skipped.iter().any(|s| s.path == skipped.path) {
+                self.skipped.push(skipped.clone());
+            }
+        }
+    }
+
+    /// Record the written output file.
+    pub fn record_output(&mut self, stats: &WriteStatistics) {
+        self.output = Some(stats.clone());
+    }
+
+    /// Record the error that ended the run.
+    pub fn record_error(&mut self, err: &PdfCatError) {
+        self.success = false;
+        self.error = Some(ErrorReport::from(err));
+    }
+
+    /// Serialize the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| PdfCatError::other(format!("Failed to serialize report: {e}")))
+    }
+}
+
+/// Structured description of an `info` run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoReport {
+    /// Files that were inspected successfully.
+    pub files: Vec<DocumentInfo>,
+
+    /// Files that could not be inspected.
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// Structured description of a `split` run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitReport {
+    /// Whether the run completed successfully.
+    pub success: bool,
+
+    /// The file that was split.
+    pub input: PathBuf,
+
+    /// Parts that were written.
+    pub parts: Vec<SplitOutput>,
+
+    /// The error that ended the run, if it failed.
+    pub error: Option<ErrorReport>,
+}
+
+impl SplitReport {
+    /// Create an empty report for splitting a file.
+    pub fn new(input: PathBuf) -> Self {
+        Self {
+            success: false,
+            input,
+            parts: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Record the error that ended the run.
+    pub fn record_error(&mut self, err: &PdfCatError) {
+        self.success = false;
+        self.error = Some(ErrorReport::from(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_report() {
+        let report = RunReport::new(PathBuf::from("out.pdf"), true);
+        assert!(!report.success);
+        assert!(report.dry_run);
+        assert_eq!(report.version, crate::VERSION);
+        assert!(report.error.is_none());
+    }
+
+    #[test]
+    fn test_record_error() {
+        let mut report = RunReport::new(PathBuf::from("out.pdf"), false);
+        report.record_error(&PdfCatError::output_exists(PathBuf::from("out.pdf")));
+
+        let error = report.error.as_ref().unwrap();
+        assert_eq!(error.kind, "outputExists");
+        assert_eq!(error.exit_code, 4);
+    }
+
+    #[test]
+    fn test_record_validation() {
+        let result = ValidationResult {
+            path: PathBuf::from("a.pdf"),
+            page_count: 2,
+            version: Some((1, 4)),
+            file_size: 100,
+            is_encrypted: false,
+            object_count: 5,
+            page_dimensions: Vec::new(),
+            tagged: false,
+            repair: Some(RepairReport::default()),
+        };
+        let mut summary = ValidationSummary::from_results(vec![result]);
+        summary.skipped.push(SkippedFile::new(
+            PathBuf::from("b.pdf"),
+            &PdfCatError::file_not_found(PathBuf::from("b.pdf")),
+        ));
+
+        let mut report = RunReport::new(PathBuf::from("out.pdf"), true);
+        report.record_validation(&summary);
+
+        assert_eq!(report.inputs.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.repaired.len(), 1);
+    }
+
+    #[test]
+    fn test_to_json_round_trip() {
+        let mut report = RunReport::new(PathBuf::from("out.pdf"), false);
+        report.success = true;
+
+        let json = report.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["success"], true);
+        assert_eq!(value["outputPath"], "out.pdf");
+        assert!(value["statistics"].is_null());
+        assert_eq!(value["sanitized"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,770 @@
+//! PDF splitting operations.
+//!
+//! This module breaks a single PDF into several smaller files, the inverse
+//! of merging. Parts can be cut:
+//! - Every N pages
+//! - At each top-level bookmark
+//! - By explicit page ranges
+//! - By a file size limit
+//!
+//! Each part only keeps the objects its pages actually use, so parts are
+//! not as large as the original document.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use pdfcat::io::PdfReader;
+//! use pdfcat::split::{SplitMode, SplitOptions, Splitter};
+//! use std::path::PathBuf;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = PathBuf::from("book.pdf");
+//! let loaded = PdfReader::new().load(&path).await?;
+//!
+//! let splitter = Splitter::new(SplitOptions::new(SplitMode::EveryPages(10)));
+//! let parts = splitter.split(&path, &loaded.document).await?;
+//! println!("Wrote {} parts", parts.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::{PageRange, PageRangePolicy};
+use crate::error::{PdfCatError, Result};
+use crate::io::PdfWriter;
+use crate::merge::PageExtractor;
+
+/// Default file name template for split parts.
+pub const DEFAULT_TEMPLATE: &str = "{stem}-{n}.pdf";
+
+/// How to divide a document into parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitMode {
+    /// Start a new part every N pages.
+    EveryPages(u32),
+    /// Start a new part at each top-level bookmark.
+    Bookmarks,
+    /// Create one part per page range.
+    Ranges(Vec<PageRange>),
+    /// Keep each part at or below a size in bytes.
+    ///
+    /// A single page larger than the limit becomes a part on its own.
+    MaxSize(u64),
+}
+
+/// Options controlling a split operation.
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    /// How to divide the document.
+    pub mode: SplitMode,
+
+    /// Directory the parts are written to.
+    pub output_dir: PathBuf,
+
+    /// File name template for the parts.
+    ///
+    /// Supported placeholders:
+    /// - `{stem}` - input file name without extension
+    /// - `{n}` - part number, zero-padded to the width of the part count
+    /// - `{start}` / `{end}` - first and last page of the part
+    /// - `{title}` - bookmark title, or the part number if there is none
+    pub template: String,
+
+    /// Overwrite existing files instead of failing.
+    pub overwrite: bool,
+}
+
+impl SplitOptions {
+    /// Create options that write parts to the current directory.
+    pub fn new(mode: SplitMode) -> Self {
+        Self {
+            mode,
+            output_dir: PathBuf::from("."),
+            template: DEFAULT_TEMPLATE.to_string(),
+            overwrite: false,
+        }
+    }
+}
+
+/// A planned part of a split document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitPart {
+    /// Part number (1-indexed).
+    pub number: usize,
+
+    /// Bookmark title the part starts at, if split by bookmarks.
+    pub title: Option<String>,
+
+    /// Pages of the source document in this part (1-indexed).
+    pub pages: Vec<u32>,
+
+    /// Path the part is written to.
+    pub path: PathBuf,
+}
+
+/// A part that has been written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitOutput {
+    /// The written part.
+    #[serde(flatten)]
+    pub part: SplitPart,
+
+    /// Size of the written file in bytes.
+    pub file_size: u64,
+}
+
+impl SplitOutput {
+    /// Format file size as human-readable string.
+    pub fn format_file_size(&self) -> String {
+        format_file_size(self.file_size)
+    }
+}
+
+/// Splitter for breaking a PDF into multiple files.
+#[derive(Debug, Clone)]
+pub struct Splitter {
+    options: SplitOptions,
+    extractor: PageExtractor,
+    writer: PdfWriter,
+}
+
+impl Splitter {
+    /// Create a new splitter.
+    pub fn new(options: SplitOptions) -> Self {
+        Self {
+            options,
+            extractor: PageExtractor::new(),
+            writer: PdfWriter::new(),
+        }
+    }
+
+    /// Split a document and write the parts to disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Path the document was loaded from
+    /// * `doc` - Document to split
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The document cannot be divided as requested
+    /// - A part would overwrite an existing file without `overwrite`
+    /// - A part cannot be written
+    pub async fn split(&self, source: &Path, doc: &Document) -> Result<Vec<SplitOutput>> {
+        let parts = self.plan(source, doc)?;
+
+        if !self.options.overwrite
+            && let Some(part) = parts.iter().find(|part| part.path.exists())
+        {
+            return Err(PdfCatError::output_exists(part.path.clone()));
+        }
+
+        tokio::fs::create_dir_all(&self.options.output_dir)
+            .await
+            .map_err(|e| PdfCatError::FailedToCreateOutput {
+                path: self.options.output_dir.clone(),
+                source: e,
+            })?;
+
+        let mut outputs = Vec::with_capacity(parts.len());
+
+        for part in parts {
+            let part_doc = self.extract_part(doc, &part.pages)?;
+            let stats = self.writer.save_with_stats(&part_doc, &part.path).await?;
+
+            outputs.push(SplitOutput {
+                part,
+                file_size: stats.file_size,
+            });
+        }
+
+        Ok(outputs)
+    }
+
+    /// Work out the pages and file name of each part without writing.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Path the document was loaded from
+    /// * `doc` - Document to split
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be divided as requested or
+    /// the template produces the same file name for two parts.
+    pub fn plan(&self, source: &Path, doc: &Document) -> Result<Vec<SplitPart>> {
+        let total_pages = doc.get_pages().len() as u32;
+        if total_pages == 0 {
+            return Err(PdfCatError::split_failed("Document has no pages"));
+        }
+
+        let groups = match &self.options.mode {
+            SplitMode::EveryPages(0) => {
+                return Err(PdfCatError::invalid_config(
+                    "Pages per part must be at least 1",
+                ));
+            }
+            SplitMode::EveryPages(n) => (1..=total_pages)
+                .collect::<Vec<_>>()
+                .chunks(*n as usize)
+                .map(|chunk| (None, chunk.to_vec()))
+                .collect(),
+            SplitMode::Bookmarks => bookmark_groups(doc, total_pages)?,
+            SplitMode::Ranges(ranges) => {
+                let mut groups = Vec::with_capacity(ranges.len());
+                for range in ranges {
+                    let pages = range.resolve(source, total_pages, PageRangePolicy::Lenient)?;
+                    groups.push((None, pages));
+                }
+                groups
+            }
+            SplitMode::MaxSize(limit) => self
+                .size_groups(doc, total_pages, *limit)?
+                .into_iter()
+                .map(|pages| (None, pages))
+                .collect(),
+        };
+
+        let stem = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "part".to_string());
+        let width = groups.len().to_string().len();
+
+        let mut seen = HashSet::new();
+        let mut parts = Vec::with_capacity(groups.len());
+
+        for (index, (title, pages)) in groups.into_iter().enumerate() {
+            let number = index + 1;
+            let file_name = render_template(
+                &self.options.template,
+                &stem,
+                number,
+                width,
+                &pages,
+                title.as_deref(),
+            );
+            let path = self.options.output_dir.join(file_name);
+
+            if !seen.insert(path.clone()) {
+                return Err(PdfCatError::invalid_config(format!(
+                    "Output template produces the same file name for several parts: {}",
+                    path.display()
+                )));
+            }
+
+            parts.push(SplitPart {
+                number,
+                title,
+                pages,
+                path,
+            });
+        }
+
+        Ok(parts)
+    }
+
+    /// Build a standalone document containing only the given pages.
+    ///
+    /// Objects that are only used by other pages are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Source document
+    /// * `pages` - 1-indexed page numbers to keep
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pages cannot be extracted.
+    pub fn extract_part(&self, doc: &Document, pages: &[u32]) -> Result<Document> {
+        let mut part = self.extractor.select_pages(doc, pages)?;
+
+        let kept: BTreeSet<ObjectId> = part.get_pages().into_values().collect();
+        let dropped: BTreeSet<ObjectId> = doc
+            .get_pages()
+            .into_values()
+            .filter(|id| !kept.contains(id))
+            .collect();
+
+        prune_part(&mut part, &dropped);
+
+        Ok(part)
+    }
+
+    /// Group pages so that each part stays within a size limit.
+    ///
+    /// Part sizes are measured by serializing candidate parts. The end of
+    /// each part is found by doubling the page count and then bisecting,
+    /// which keeps the number of trial serializations logarithmic.
+    fn size_groups(&self, doc: &Document, total_pages: u32, limit: u64) -> Result<Vec<Vec<u32>>> {
+        let fits = |start: u32, end: u32| -> Result<bool> {
+            let pages: Vec<u32> = (start..=end).collect();
+            let part = self.extract_part(doc, &pages)?;
+            Ok(self.writer.to_bytes(&part)?.len() as u64 <= limit)
+        };
+
+        let mut groups = Vec::new();
+        let mut start = 1;
+
+        while start <= total_pages {
+            // A part always contains at least one page
+            let mut good = start;
+            let mut bad = None;
+            let mut step = 1;
+
+            while good < total_pages {
+                let candidate = (good + step).min(total_pages);
+                if fits(start, candidate)? {
+                    good = candidate;
+                    step *= 2;
+                } else {
+                    bad = Some(candidate);
+                    break;
+                }
+            }
+
+            if let Some(mut bad) = bad {
+                while bad - good > 1 {
+                    let mid = good + (bad - good) / 2;
+                    if fits(start, mid)? {
+                        good = mid;
+                    } else {
+                        bad = mid;
+                    }
+                }
+            }
+
+            groups.push((start..=good).collect());
+            start = good + 1;
+        }
+
+        Ok(groups)
+    }
+}
+
+/// Group pages by the top-level bookmark they fall under.
+///
+/// Pages before the first bookmark form an untitled part of their own.
+fn bookmark_groups(doc: &Document, total_pages: u32) -> Result<Vec<(Option<String>, Vec<u32>)>> {
+    let toc = doc
+        .get_toc()
+        .map_err(|_| PdfCatError::split_failed("Document has no bookmarks"))?;
+
+    let mut starts: Vec<(u32, String)> = Vec::new();
+    for entry in toc.toc.iter().filter(|entry| entry.level == 1) {
+        let page = entry.page as u32;
+        if !starts.iter().any(|(start, _)| *start == page) {
+            starts.push((page, entry.title.clone()));
+        }
+    }
+    starts.sort_by_key(|(page, _)| *page);
+
+    if starts.is_empty() {
+        return Err(PdfCatError::split_failed(
+            "Document has no top-level bookmarks",
+        ));
+    }
+
+    let mut groups = Vec::new();
+
+    if starts[0].0 > 1 {
+        groups.push((None, (1..starts[0].0).collect()));
+    }
+
+    for (index, (start, title)) in starts.iter().enumerate() {
+        let end = starts
+            .get(index + 1)
+            .map(|(next, _)| next - 1)
+            .unwrap_or(total_pages);
+        groups.push((Some(title.clone()), (*start..=end).collect()));
+    }
+
+    Ok(groups)
+}
+
+/// Remove dropped pages and everything only they referenced.
+///
+/// The outline and page labels describe the whole original document, so
+/// they are removed as well. Form fields whose widgets sit on dropped
+/// pages are removed from the AcroForm.
+fn prune_part(doc: &mut Document, dropped: &BTreeSet<ObjectId>) {
+    for id in dropped {
+        doc.objects.remove(id);
+    }
+
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.remove(b"Outlines");
+        catalog.remove(b"PageLabels");
+    }
+
+    let acro_form_id = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|obj| obj.as_reference().ok());
+
+    let fields: Option<Vec<Object>> = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok())
+        .and_then(|form| form.get(b"Fields").ok())
+        .and_then(|obj| obj.as_array().ok())
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|field| !field_on_dropped_page(doc, field, dropped))
+                .cloned()
+                .collect()
+        });
+
+    if let Some(fields) = fields {
+        let form = match acro_form_id {
+            Some(id) => doc.get_dictionary_mut(id).ok(),
+            None => doc
+                .catalog_mut()
+                .ok()
+                .and_then(|catalog| catalog.get_mut(b"AcroForm").ok())
+                .and_then(|obj| obj.as_dict_mut().ok()),
+        };
+
+        if let Some(form) = form {
+            form.set("Fields", Object::Array(fields));
+        }
+    }
+
+    doc.prune_objects();
+}
+
+/// Check whether every widget of a form field sits on a dropped page.
+fn field_on_dropped_page(doc: &Document, field: &Object, dropped: &BTreeSet<ObjectId>) -> bool {
+    let Some(dict) = doc
+        .dereference(field)
+        .ok()
+        .and_then(|(_, obj)| obj.as_dict().ok())
+    else {
+        return false;
+    };
+
+    if let Ok(page_id) = dict.get(b"P").and_then(Object::as_reference) {
+        return dropped.contains(&page_id);
+    }
+
+    match dict.get(b"Kids").and_then(Object::as_array) {
+        Ok(kids) if !kids.is_empty() => kids
+            .iter()
+            .all(|kid| field_on_dropped_page(doc, kid, dropped)),
+        _ => false,
+    }
+}
+
+/// Parse a size limit such as `500K`, `10MB` or `1G`.
+///
+/// Suffixes are binary multiples (1K = 1024 bytes) and case-insensitive.
+/// A number without a suffix is a size in bytes.
+///
+/// # Errors
+///
+/// Returns an error if the string is not a positive size.
+///
+/// # Examples
+///
+/// ```
+/// use pdfcat::split::parse_size_limit;
+///
+/// assert_eq!(parse_size_limit("10M").unwrap(), 10 * 1024 * 1024);
+/// assert_eq!(parse_size_limit("512").unwrap(), 512);
+/// ```
+pub fn parse_size_limit(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let upper = s.to_uppercase();
+    let digits_end = upper
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(upper.len());
+    let (number, suffix) = upper.split_at(digits_end);
+
+    let multiplier: u64 = match suffix.trim() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => {
+            return Err(PdfCatError::invalid_config(format!(
+                "Invalid size: {s}. Expected a number with an optional K, M or G suffix"
+            )));
+        }
+    };
+
+    let size = number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|&n| n > 0)
+        .ok_or_else(|| PdfCatError::invalid_config(format!("Invalid size: {s}")))?;
+
+    Ok(size)
+}
+
+/// Format file size as human-readable string.
+fn format_file_size(size: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if size >= GB {
+        format!("{:.2} GB", size as f64 / GB as f64)
+    } else if size >= MB {
+        format!("{:.2} MB", size as f64 / MB as f64)
+    } else if size >= KB {
+        format!("{:.2} KB", size as f64 / KB as f64)
+    } else {
+        format!("{size} bytes")
+    }
+}
+
+/// Render a part file name from a template.
+fn render_template(
+    template: &str,
+    stem: &str,
+    number: usize,
+    width: usize,
+    pages: &[u32],
+    title: Option<&str>,
+) -> String {
+    let number = format!("{number:0width$}");
+    let start = pages.first().copied().unwrap_or_default().to_string();
+    let end = pages.last().copied().unwrap_or_default().to_string();
+    let title = title
+        .map(sanitize_file_name)
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| number.clone());
+
+    template
+        .replace("{stem}", stem)
+        .replace("{n}", &number)
+        .replace("{start}", &start)
+        .replace("{end}", &end)
+        .replace("{title}", &title)
+}
+
+/// Replace characters that are unsafe in file names.
+fn sanitize_file_name(name: &str) -> String {
+    name.trim()
+        .trim_end_matches('.')
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{Stream, dictionary};
+    use tempfile::TempDir;
+
+    fn create_test_document(page_count: usize) -> Document {
+        let mut doc = Document::with_version("1.4");
+        let pages_id = doc.new_object_id();
+
+        let mut kids = Vec::new();
+        for i in 0..page_count {
+            let content = format!("BT /F1 12 Tf 72 720 Td (Page {}) Tj ET", i + 1);
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+
+        doc.objects.insert(
+            pages_id,
+            dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => page_count as i64,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }
+            .into(),
+        );
+
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        doc
+    }
+
+    fn splitter(mode: SplitMode, dir: &Path) -> Splitter {
+        let mut options = SplitOptions::new(mode);
+        options.output_dir = dir.to_path_buf();
+        Splitter::new(options)
+    }
+
+    #[test]
+    fn test_plan_every_pages() {
+        let doc = create_test_document(5);
+        let splitter = splitter(SplitMode::EveryPages(2), Path::new("out"));
+
+        let parts = splitter.plan(Path::new("book.pdf"), &doc).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].pages, vec![1, 2]);
+        assert_eq!(parts[2].pages, vec![5]);
+        assert_eq!(parts[0].path, PathBuf::from("out/book-1.pdf"));
+    }
+
+    #[test]
+    fn test_plan_every_zero_pages() {
+        let doc = create_test_document(3);
+        let splitter = splitter(SplitMode::EveryPages(0), Path::new("out"));
+        assert!(splitter.plan(Path::new("book.pdf"), &doc).is_err());
+    }
+
+    #[test]
+    fn test_plan_ranges() {
+        let doc = create_test_document(10);
+        let ranges = vec![
+            PageRange::parse("1-3").unwrap(),
+            PageRange::parse("4,8-10").unwrap(),
+        ];
+        let splitter = splitter(SplitMode::Ranges(ranges), Path::new("out"));
+
+        let parts = splitter.plan(Path::new("book.pdf"), &doc).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].pages, vec![4, 8, 9, 10]);
+    }
+
+    #[test]
+    fn test_plan_range_out_of_bounds() {
+        let doc = create_test_document(3);
+        let ranges = vec![PageRange::parse("5-6").unwrap()];
+        let splitter = splitter(SplitMode::Ranges(ranges), Path::new("out"));
+
+        let err = splitter.plan(Path::new("book.pdf"), &doc).unwrap_err();
+        assert!(matches!(
+            err,
+            PdfCatError::InvalidPageRange { ref path, .. } if path == Path::new("book.pdf")
+        ));
+    }
+
+    #[test]
+    fn test_plan_duplicate_file_names() {
+        let doc = create_test_document(4);
+        let mut options = SplitOptions::new(SplitMode::EveryPages(1));
+        options.template = "{stem}.pdf".to_string();
+
+        let result = Splitter::new(options).plan(Path::new("book.pdf"), &doc);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_plan_without_bookmarks() {
+        let doc = create_test_document(3);
+        let splitter = splitter(SplitMode::Bookmarks, Path::new("out"));
+        assert!(splitter.plan(Path::new("book.pdf"), &doc).is_err());
+    }
+
+    #[test]
+    fn test_extract_part_prunes_objects() {
+        let doc = create_test_document(10);
+        let splitter = splitter(SplitMode::EveryPages(1), Path::new("out"));
+
+        let part = splitter.extract_part(&doc, &[3]).unwrap();
+        assert_eq!(part.get_pages().len(), 1);
+
+        // Catalog, page tree, one page and its content stream
+        assert_eq!(part.objects.len(), 4);
+    }
+
+    #[test]
+    fn test_size_groups_respect_limit() {
+        let doc = create_test_document(8);
+        let writer = PdfWriter::new();
+
+        let single = splitter(SplitMode::EveryPages(1), Path::new("out"))
+            .extract_part(&doc, &[1, 2, 3])
+            .unwrap();
+        let limit = writer.to_bytes(&single).unwrap().len() as u64;
+
+        let splitter = splitter(SplitMode::MaxSize(limit), Path::new("out"));
+        let parts = splitter.plan(Path::new("book.pdf"), &doc).unwrap();
+
+        assert!(parts.len() >= 3);
+        for part in &parts {
+            let part_doc = splitter.extract_part(&doc, &part.pages).unwrap();
+            assert!(writer.to_bytes(&part_doc).unwrap().len() as u64 <= limit);
+        }
+        let total: usize = parts.iter().map(|p| p.pages.len()).sum();
+        assert_eq!(total, 8);
+    }
+
+    #[test]
+    fn test_parse_size_limit() {
+        assert_eq!(parse_size_limit("100").unwrap(), 100);
+        assert_eq!(parse_size_limit("2k").unwrap(), 2048);
+        assert_eq!(parse_size_limit("1 MB").unwrap(), 1024 * 1024);
+        assert_eq!(parse_size_limit("1G").unwrap(), 1024 * 1024 * 1024);
+        assert!(parse_size_limit("0").is_err());
+        assert!(parse_size_limit("10X").is_err());
+        assert!(parse_size_limit("M").is_err());
+    }
+
+    #[test]
+    fn test_render_template() {
+        assert_eq!(
+            render_template("{stem}-{n}.pdf", "book", 3, 2, &[5, 6], None),
+            "book-03.pdf"
+        );
+        assert_eq!(
+            render_template(
+                "{start}-{end} {title}.pdf",
+                "book",
+                1,
+                1,
+                &[5, 6],
+                Some("A/B")
+            ),
+            "5-6 A_B.pdf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_writes_parts() {
+        let temp_dir = TempDir::new().unwrap();
+        let doc = create_test_document(4);
+        let splitter = splitter(SplitMode::EveryPages(2), temp_dir.path());
+
+        let outputs = splitter.split(Path::new("book.pdf"), &doc).await.unwrap();
+        assert_eq!(outputs.len(), 2);
+
+        for output in &outputs {
+            assert!(output.part.path.exists());
+            assert!(output.file_size > 0);
+            let written = Document::load(&output.part.path).await.unwrap();
+            assert_eq!(written.get_pages().len(), 2);
+        }
+
+        // A second run refuses to overwrite the parts
+        assert!(matches!(
+            splitter.split(Path::new("book.pdf"), &doc).await,
+            Err(PdfCatError::OutputExists { .. })
+        ));
+    }
+}
@@ -1,15 +1,23 @@
 //! Utilities for path collection, PDF merge helpers, etc.
 
+use crate::io::image::ImageFormat;
 use crate::{Result, error::PdfCatError};
 use lopdf::{Document, Object};
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Readers accept up to this many bytes of other data before a PDF's
+/// `%PDF-` header.
+const PDF_HEADER_OFFSET: usize = 1024;
 
 /// Expand multiple glob patterns into filesystem paths.
 ///
 /// Accepts anything iterable with items that convert to `&str`, e.g.:
 /// `&[&str]`, `Vec<String>`, or `Vec<&str>`.
 ///
-/// Returns a flattened list of resolved paths.
+/// Returns a flattened list of resolved paths. Files matched by wildcards
+/// are only kept if they are PDFs or JPEG or PNG images (see
+/// [`is_input_file`]), so a pattern such as `scans/*` skips other files.
 ///
 /// Errors:
 /// - Propagates `glob` parse errors.
@@ -37,7 +45,10 @@ where
 fn collect_paths_for_pattern<P: AsRef<str>>(pattern: P) -> Result<Vec<PathBuf>> {
     let mut resolved_paths = Vec::new();
 
-    let paths = glob::glob(pattern.as_ref()).map_err(|err| PdfCatError::Other {
+    let pattern = pattern.as_ref();
+    let has_wildcards = glob::Pattern::escape(pattern) != pattern;
+
+    let paths = glob::glob(pattern).map_err(|err| PdfCatError::Other {
         message: err.to_string(),
     })?;
 
@@ -45,12 +56,51 @@ fn collect_paths_for_pattern<P: AsRef<str>>(pattern: P) -> Result<Vec<PathBuf>>
         let path = entry.map_err(|err| PdfCatError::Other {
             message: err.to_string(),
         })?;
+        if has_wildcards && !is_input_file(&path) {
+            continue;
+        }
         resolved_paths.push(path);
     }
 
     Ok(resolved_paths)
 }
 
+/// Check if a path is a file that can be merged: a PDF, or a JPEG or PNG
+/// image.
+///
+/// Files are recognized by their first bytes, so a PDF without a `.pdf`
+/// extension is found, or else by their extension, so a damaged PDF is
+/// still tried.
+pub fn is_input_file(path: &Path) -> bool {
+    if !path.is_file() {
+        return false;
+    }
+
+    let is_input_header = read_file_header(path)
+        .is_some_and(|header| is_pdf_header(&header) || ImageFormat::from_bytes(&header).is_some());
+    let is_pdf = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"));
+    is_input_header || is_pdf || ImageFormat::from_path(path).is_some()
+}
+
+/// Read the first bytes of a file, enough to tell what kind of file it is.
+///
+/// Returns None if the file can't be read.
+pub(crate) fn read_file_header(path: &Path) -> Option<Vec<u8>> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut header = Vec::with_capacity(PDF_HEADER_OFFSET);
+    file.take(PDF_HEADER_OFFSET as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(header)
+}
+
+/// Check if the first bytes of a file are those of a PDF.
+pub(crate) fn is_pdf_header(header: &[u8]) -> bool {
+    header.windows(5).any(|window| window == b"%PDF-")
+}
+
 /// Copy object references from one PDF document to another.
 ///
 /// If `obj` is a reference, this walks the structure recursively and inserts
@@ -84,3 +134,32 @@ pub fn copy_references(target: &mut Document, source: &Document, obj: &Object) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_paths_for_patterns() {
+        let dir = TempDir::new().unwrap();
+        for name in ["a.pdf", "b.JPG", "c.png", "notes.txt"] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        // Scanner output without an extension
+        std::fs::write(dir.path().join("scan0001"), b"%PDF-1.4\n").unwrap();
+        std::fs::create_dir(dir.path().join("d.pdf")).unwrap();
+
+        let pattern = format!("{}/*", dir.path().display());
+        let names: Vec<String> = collect_paths_for_patterns([pattern])
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.pdf", "b.JPG", "c.png", "scan0001"]);
+
+        // Paths without wildcards are kept whatever they are
+        let notes = dir.path().join("notes.txt").display().to_string();
+        assert_eq!(collect_paths_for_patterns([notes]).unwrap().len(), 1);
+    }
+}
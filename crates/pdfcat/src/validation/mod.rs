@@ -27,8 +27,13 @@ use lopdf::Document;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-use crate::config::Config;
-use crate::error::{PdfCatError, Result};
+use crate::config::{Config, MergeMode, WatermarkContent};
+use crate::error::{ErrorReport, PdfCatError, Result};
+use crate::io::image::{self, ImageFormat};
+use crate::io::repair::{self, RepairReport};
+use crate::merge::VersionManager;
+use crate::merge::layout::{displayed_size, page_box, page_rotation};
+use crate::merge::structure::is_tagged;
 
 /// Result of validating a single PDF file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,7 +45,8 @@ pub struct ValidationResult {
     /// Number of pages in the PDF.
     pub page_count: usize,
 
-    /// PDF version (major, minor).
+    /// PDF version (major, minor), the later of the header and the
+    /// catalog's `/Version`.
     pub version: Option<(u8, u8)>,
 
     /// Size of the file in bytes.
@@ -52,8 +58,15 @@ pub struct ValidationResult {
     /// Number of objects in the PDF.
     pub object_count: usize,
 
-    /// Page dimensions (width, height) in points, if available.
-    pub page_dimensions: Option<(f32, f32)>,
+    /// Distinct page dimensions (width, height) in points, as displayed
+    /// after `/Rotate`, in order of first appearance.
+    pub page_dimensions: Vec<(f32, f32)>,
+
+    /// Whether the PDF is tagged, with a structure tree for accessibility.
+    pub tagged: bool,
+
+    /// What was recovered, if the file had to be repaired.
+    pub repair: Option<RepairReport>,
 }
 
 impl ValidationResult {
@@ -67,38 +80,23 @@ impl ValidationResult {
     /// # Errors
     ///
     /// Returns an error if page information cannot be extracted.
-    fn from_document(path: PathBuf, doc: &Document) -> Result<Self> {
+    pub(crate) fn from_document(path: PathBuf, doc: &Document) -> Result<Self> {
         let pages = doc.get_pages();
         let page_count = pages.len();
 
-        let version = doc.version.split_once(".").map(|(major, minor)| {
-            (
-                major.to_string().parse::<u8>().unwrap_or_default(),
-                minor.to_string().parse::<u8>().unwrap_or_default(),
-            )
-        });
+        // The catalog's `/Version` can raise the header's
+        let version = VersionManager::new()
+            .version(doc)
+            .map(|version| (version.major, version.minor));
 
         let object_count = doc.objects.len();
 
-        // Try to get page dimensions from first page
-        let page_dimensions = pages.iter().next().and_then(|(_, page_id)| {
-            doc.get_object(*page_id).ok().and_then(|page_obj| {
-                if let lopdf::Object::Dictionary(page_dict) = page_obj {
-                    page_dict.get(b"MediaBox").ok().and_then(|mediabox| {
-                        if let lopdf::Object::Array(arr) = mediabox
-                            && arr.len() >= 4
-                        {
-                            let width = arr[2].as_float().ok()?;
-                            let height = arr[3].as_float().ok()?;
-                            return Some((width, height));
-                        }
-                        None
-                    })
-                } else {
-                    None
-                }
-            })
-        });
+        // Collect the distinct sizes of the pages, as displayed
+        let mut page_dimensions = Vec::new();
+        for &page_id in pages.values() {
+            let size = displayed_size(page_box(doc, page_id), page_rotation(doc, page_id));
+            add_distinct_size(&mut page_dimensions, size);
+        }
 
         // Get file size
         let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
@@ -111,8 +109,36 @@ impl ValidationResult {
             is_encrypted: false, // lopdf would fail to load if encrypted
             object_count,
             page_dimensions,
+            tagged: is_tagged(doc),
+            repair: None,
         })
     }
+
+    /// Format file size as human-readable string.
+    pub fn format_file_size(&self) -> String {
+        format_file_size(self.file_size)
+    }
+}
+
+/// An input file that was skipped because of an error.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    /// Path to the skipped file.
+    pub path: PathBuf,
+
+    /// Why the file was skipped.
+    pub error: ErrorReport,
+}
+
+impl SkippedFile {
+    /// Create a skipped file entry from the error that caused it.
+    pub fn new(path: PathBuf, error: &PdfCatError) -> Self {
+        Self {
+            path,
+            error: ErrorReport::from(error),
+        }
+    }
 }
 
 /// Summary of validation results for multiple files.
@@ -133,6 +159,9 @@ pub struct ValidationSummary {
 
     /// Number of files that failed validation.
     pub files_failed: usize,
+
+    /// Files skipped because they failed validation.
+    pub skipped: Vec<SkippedFile>,
 }
 
 impl ValidationSummary {
@@ -148,6 +177,49 @@ impl ValidationSummary {
             total_size,
             files_validated,
             files_failed: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Distinct page dimensions across all validated files, in order of
+    /// first appearance.
+    pub fn page_dimensions(&self) -> Vec<(f32, f32)> {
+        let mut sizes = Vec::new();
+        for &size in self.results.iter().flat_map(|r| &r.page_dimensions) {
+            add_distinct_size(&mut sizes, size);
+        }
+        sizes
+    }
+
+    /// Why merging will lose the tags of tagged inputs, if it will.
+    ///
+    /// Tags are lost when pages are laid out onto sheets, and the merged
+    /// file is only partly tagged when some inputs are not tagged.
+    pub fn tag_loss(&self, config: &Config) -> Option<String> {
+        if config.merge_mode == MergeMode::Portfolio || !self.results.iter().any(|r| r.tagged) {
+            return None;
+        }
+
+        if config.layout.is_some() {
+            return Some("--layout removes the tags of tagged inputs".to_string());
+        }
+
+        let untagged: Vec<String> = self
+            .results
+            .iter()
+            .filter(|r| !r.tagged)
+            .map(|r| r.path.display().to_string())
+            .collect();
+        match untagged.len() {
+            0 => None,
+            1 => Some(format!(
+                "{} is not tagged, so the merged file will only be partly tagged",
+                untagged[0]
+            )),
+            _ => Some(format!(
+                "{} are not tagged, so the merged file will only be partly tagged",
+                untagged.join(", ")
+            )),
         }
     }
 
@@ -162,19 +234,39 @@ pub struct Validator {
     /// Whether to perform strict validation.
     #[expect(unused)]
     strict: bool,
+
+    /// Whether to attempt repairing files that fail to load.
+    repair: bool,
 }
 
 impl Validator {
     /// Create a new validator with default settings.
     pub fn new() -> Self {
-        Self { strict: false }
+        Self {
+            strict: false,
+            repair: false,
+        }
     }
 
     /// Create a new validator with strict mode enabled.
     ///
     /// In strict mode, warnings are treated as errors.
     pub fn strict() -> Self {
-        Self { strict: true }
+        Self {
+            strict: true,
+            repair: false,
+        }
+    }
+
+    /// Create a new validator that repairs damaged files.
+    ///
+    /// Files that would otherwise fail to load are reconstructed and the
+    /// recovery is recorded in [`ValidationResult::repair`].
+    pub fn with_repair() -> Self {
+        Self {
+            strict: false,
+            repair: true,
+        }
     }
 
     /// Validate a single PDF file.
@@ -240,28 +332,47 @@ impl Validator {
             ));
         }
 
-        // Load the PDF document
-        let doc = Document::load(path).await.map_err(|e| {
-            // Check if it's an encryption error
-            let err_msg = e.to_string();
-            if err_msg.contains("encrypt") || err_msg.contains("password") {
-                PdfCatError::encrypted_pdf(path.to_path_buf())
-            } else {
-                PdfCatError::failed_to_load_pdf(path.to_path_buf(), err_msg)
+        // Load the PDF document, or the image as a document of one page
+        let image_format = ImageFormat::detect(path);
+        let load_result = match image_format {
+            Some(format) => image::load_image(path, format).await,
+            None => Document::load(path).await.map_err(|e| {
+                // Check if it's an encryption error
+                let err_msg = e.to_string();
+                if err_msg.contains("encrypt") || err_msg.contains("password") {
+                    PdfCatError::encrypted_pdf(path.to_path_buf())
+                } else {
+                    PdfCatError::failed_to_load_pdf(path.to_path_buf(), err_msg)
+                }
+            }),
+        };
+
+        let (mut doc, mut report) = match load_result {
+            Ok(doc) => (doc, None),
+            Err(PdfCatError::FailedToLoadPdf { .. }) if self.repair && image_format.is_none() => {
+                let (doc, report) = repair::repair_file(path).await?;
+                (doc, Some(report))
             }
-        })?;
+            Err(err) => return Err(err),
+        };
 
         // Verify the document has pages
-        let pages = doc.get_pages();
-        if pages.is_empty() {
-            return Err(PdfCatError::corrupted_pdf(
-                path.to_path_buf(),
-                "PDF has no pages",
-            ));
+        if doc.get_pages().is_empty() {
+            if !self.repair {
+                return Err(PdfCatError::corrupted_pdf(
+                    path.to_path_buf(),
+                    "PDF has no pages",
+                ));
+            }
+
+            report = Some(repair::repair_page_tree(path, &mut doc)?);
         }
 
         // Create validation result
-        ValidationResult::from_document(path.to_path_buf(), &doc)
+        let mut result = ValidationResult::from_document(path.to_path_buf(), &doc)?;
+        result.repair = report;
+
+        Ok(result)
     }
 
     /// Validate multiple PDF files.
@@ -283,7 +394,7 @@ impl Validator {
         continue_on_error: bool,
     ) -> Result<ValidationSummary> {
         let mut results = Vec::new();
-        let mut failed_count = 0;
+        let mut skipped = Vec::new();
 
         for path in paths {
             match self.validate_file(path).await {
@@ -293,7 +404,7 @@ impl Validator {
                 Err(e) => {
                     if continue_on_error {
                         eprintln!("Warning: Skipping {}: {}", path.display(), e);
-                        failed_count += 1;
+                        skipped.push(SkippedFile::new(path.clone(), &e));
                     } else {
                         return Err(e);
                     }
@@ -306,7 +417,8 @@ impl Validator {
         }
 
         let mut summary = ValidationSummary::from_results(results);
-        summary.files_failed = failed_count;
+        summary.files_failed = skipped.len();
+        summary.skipped = skipped;
 
         Ok(summary)
     }
@@ -394,23 +506,53 @@ impl Validator {
         // Validate output path
         self.validate_output(config).await?;
 
-        // Validate page ranges if specified
+        // Validate reversed input positions
+        if let Some(&position) = config
+            .reverse_inputs
+            .iter()
+            .find(|&&position| position == 0 || position > config.inputs.len())
+        {
+            return Err(PdfCatError::invalid_config(format!(
+                "Cannot reverse input {position}: there are {} input(s)",
+                config.inputs.len()
+            )));
+        }
+
+        // Validate page ranges against each input's page count
         if let Some(ref page_range) = config.page_range {
             for result in &summary.results {
-                // Check if any requested pages exceed the document's page count
-                let requested_pages = page_range.to_pages(result.page_count as u32);
-                if let Some(max_page) = requested_pages.iter().max()
-                    && *max_page as usize > result.page_count
-                {
+                page_range.resolve(
+                    &result.path,
+                    result.page_count as u32,
+                    config.page_range_policy,
+                )?;
+            }
+        }
+
+        // Validate watermark PDFs and the pages taken from them
+        for watermark in &config.watermarks {
+            if let WatermarkContent::Page { ref path, page } = watermark.content {
+                let result = self.validate_file(path).await?;
+                if page as usize > result.page_count {
                     return Err(PdfCatError::InvalidPageRange {
-                        path: result.path.clone(),
-                        range: format!("{page_range:?}"),
+                        path: path.clone(),
+                        range: page.to_string(),
                         total_pages: result.page_count,
                     });
                 }
             }
         }
 
+        // Check that the files to attach can be read
+        for path in &config.attachments {
+            if !path.exists() {
+                return Err(PdfCatError::file_not_found(path.clone()));
+            }
+            if !path.is_file() {
+                return Err(PdfCatError::NotAFile { path: path.clone() });
+            }
+        }
+
         Ok(summary)
     }
 }
@@ -421,6 +563,17 @@ impl Default for Validator {
     }
 }
 
+/// Add a page size to a list unless a size within a point of it is
+/// already there.
+fn add_distinct_size(sizes: &mut Vec<(f32, f32)>, size: (f32, f32)) {
+    let known = sizes
+        .iter()
+        .any(|&(width, height)| (width - size.0).abs() < 1.0 && (height - size.1).abs() < 1.0);
+    if !known {
+        sizes.push(size);
+    }
+}
+
 /// Format file size as human-readable string.
 ///
 /// # Arguments
@@ -505,6 +658,39 @@ mod tests {
         assert!(validation.file_size > 0);
     }
 
+    #[tokio::test]
+    async fn test_validate_catalog_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("raised.pdf");
+
+        let mut doc = Document::load("tests/fixtures/basic.pdf").await.unwrap();
+        doc.version = "1.4".to_string();
+        doc.catalog_mut().unwrap().set("Version", "1.7");
+        doc.save(&path).unwrap();
+
+        let validation = Validator::new().validate_file(&path).await.unwrap();
+        assert_eq!(validation.version, Some((1, 7)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_damaged_pdf_with_repair() {
+        let temp_dir = TempDir::new().unwrap();
+        let damaged = temp_dir.path().join("damaged.pdf");
+
+        let content = std::fs::read("tests/fixtures/basic.pdf").unwrap();
+        let end = content.windows(6).rposition(|w| w == b"endobj").unwrap() + 6;
+        std::fs::write(&damaged, &content[..end]).unwrap();
+
+        assert!(Validator::new().validate_file(&damaged).await.is_err());
+
+        let result = Validator::with_repair()
+            .validate_file(&damaged)
+            .await
+            .unwrap();
+        assert_eq!(result.page_count, 1);
+        assert!(result.repair.is_some());
+    }
+
     #[tokio::test]
     async fn test_validate_multiple_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -537,6 +723,8 @@ mod tests {
         let summary = result.unwrap();
         assert_eq!(summary.files_validated, 1);
         assert_eq!(summary.files_failed, 1);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].error.kind, "corruptedPdf");
     }
 
     #[tokio::test]
@@ -556,9 +744,33 @@ mod tests {
             compression: crate::config::CompressionLevel::Standard,
             metadata: crate::config::Metadata::default(),
             continue_on_error: false,
+            repair: false,
             jobs: None,
             page_range: None,
+            page_range_policy: crate::config::PageRangePolicy::Lenient,
             rotation: None,
+            merge_mode: crate::config::MergeMode::Concatenate,
+            reverse_inputs: Vec::new(),
+            duplex: false,
+            blank_pages: Vec::new(),
+            separators: false,
+            layout: None,
+            box_edits: Vec::new(),
+            page_size: None,
+            page_fit: crate::config::PageFit::Fit,
+            stamps: Vec::new(),
+            bates: None,
+            bates_log: None,
+            watermarks: Vec::new(),
+            page_labels: crate::config::PageLabels::Keep,
+            attachments: Vec::new(),
+            group_layers: false,
+            remove_annotations: None,
+            flatten: false,
+            sanitize: false,
+            viewer: crate::config::ViewerSettings::default(),
+            pdf_version: None,
+            output_format: crate::config::OutputFormat::Text,
         };
 
         let validator = Validator::new();
@@ -589,7 +801,9 @@ mod tests {
             file_size: 1024,
             is_encrypted: false,
             object_count: 10,
-            page_dimensions: None,
+            page_dimensions: vec![(595.0, 842.0), (842.0, 595.0)],
+            tagged: false,
+            repair: None,
         };
 
         let result2 = ValidationResult {
@@ -599,7 +813,9 @@ mod tests {
             file_size: 2048,
             is_encrypted: false,
             object_count: 8,
-            page_dimensions: None,
+            page_dimensions: vec![(595.28, 841.89), (612.0, 792.0)],
+            tagged: false,
+            repair: None,
         };
 
         let summary = ValidationSummary::from_results(vec![result1, result2]);
@@ -608,5 +824,50 @@ mod tests {
         assert_eq!(summary.total_size, 3072);
         assert_eq!(summary.files_validated, 2);
         assert_eq!(summary.format_total_size(), "3.00 KB");
+        assert_eq!(
+            summary.page_dimensions(),
+            vec![(595.0, 842.0), (842.0, 595.0), (612.0, 792.0)]
+        );
+    }
+
+    #[test]
+    fn test_validation_summary_tag_loss() {
+        let result = |path: &str, tagged| ValidationResult {
+            path: PathBuf::from(path),
+            page_count: 1,
+            version: Some((1, 7)),
+            file_size: 1024,
+            is_encrypted: false,
+            object_count: 10,
+            page_dimensions: vec![(612.0, 792.0)],
+            tagged,
+            repair: None,
+        };
+        let mut config = Config {
+            inputs: vec![PathBuf::from("a.pdf"), PathBuf::from("b.pdf")],
+            ..Config::default()
+        };
+
+        let untagged = ValidationSummary::from_results(vec![result("a.pdf", false)]);
+        assert_eq!(untagged.tag_loss(&config), None);
+
+        let tagged = ValidationSummary::from_results(vec![result("a.pdf", true)]);
+        assert_eq!(tagged.tag_loss(&config), None);
+
+        let mixed =
+            ValidationSummary::from_results(vec![result("a.pdf", true), result("b.pdf", false)]);
+        assert!(
+            mixed
+                .tag_loss(&config)
+                .unwrap()
+                .starts_with("b.pdf is not tagged")
+        );
+
+        config.layout = Some(crate::config::Layout::Booklet);
+        assert!(tagged.tag_loss(&config).unwrap().contains("--layout"));
+
+        config.layout = None;
+        config.merge_mode = MergeMode::Portfolio;
+        assert_eq!(mixed.tag_loss(&config), None);
     }
 }
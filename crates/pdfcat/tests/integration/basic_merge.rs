@@ -1,9 +1,14 @@
 //! Integration tests for basic PDF merging operations.
 
-use pdfcat::config::{CompressionLevel, Config, Metadata, OverwriteMode};
+use lopdf::{Object, dictionary};
+use pdfcat::config::{
+    Bates, BoxEdit, BoxKind, CompressionLevel, Config, Layout, MergeMode, Metadata, OutputFormat,
+    OverwriteMode, PageFit, PageLabels, PageLayout, PageRange, PageRangePolicy, PageSize,
+    PdfVersion, Stamp, StampPosition, ViewerSettings, Watermark, WatermarkLayer,
+};
 use pdfcat::io::load_pdf;
-use pdfcat::merge::merge_pdfs;
-// use pdfcat::validation::Validator;
+use pdfcat::merge::{AttachmentManager, Merger, merge_pdfs};
+use pdfcat::validation::Validator;
 // use std::path::PathBuf;
 
 use crate::common::{fixture_path, require_fixture, temp_output_path};
@@ -25,9 +30,33 @@ async fn test_merge_two_simple_pdfs() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -59,9 +88,33 @@ async fn test_merge_multi_page_pdfs() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -89,9 +142,33 @@ async fn test_merge_single_pdf() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -119,9 +196,33 @@ async fn test_merge_with_compression_none() {
         compression: CompressionLevel::None,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -148,9 +249,33 @@ async fn test_merge_with_compression_maximum() {
         compression: CompressionLevel::Maximum,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -185,9 +310,33 @@ async fn test_merge_with_metadata() {
         compression: CompressionLevel::Standard,
         metadata,
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -228,9 +377,33 @@ async fn test_merge_preserves_page_count() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -258,9 +431,33 @@ async fn test_merge_output_is_valid_pdf() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -303,9 +500,33 @@ async fn test_merge_with_parallel_loading() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: Some(4), // Force parallel loading
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -314,3 +535,844 @@ async fn test_merge_with_parallel_loading() {
     let (_, stats) = result.unwrap();
     assert_eq!(stats.files_merged, 4);
 }
+
+#[tokio::test]
+async fn test_bookmarks_point_at_each_input() {
+    require_fixture("multi_page.pdf");
+    require_fixture("multi_page_2.pdf");
+
+    let output = temp_output_path();
+
+    let mut config = Config {
+        inputs: vec![
+            fixture_path("multi_page.pdf"),
+            fixture_path("multi_page_2.pdf"),
+        ],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        bookmarks: true,
+        ..Config::default()
+    };
+
+    let bookmark_pages = |document: &lopdf::Document| -> Vec<usize> {
+        let toc = document.get_toc().unwrap();
+        toc.toc
+            .iter()
+            .filter(|entry| entry.level == 1)
+            .map(|entry| entry.page)
+            .collect()
+    };
+
+    // 4 pages followed by 3 pages
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 7);
+    assert_eq!(bookmark_pages(&document), vec![1, 5]);
+
+    // Interleaved: the second input starts on page 2
+    config.merge_mode = MergeMode::Collate;
+    config.reverse_inputs = vec![2];
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 7);
+    assert_eq!(bookmark_pages(&document), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_merge_with_layout() {
+    require_fixture("multi_page.pdf");
+    require_fixture("multi_page_2.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![
+            fixture_path("multi_page.pdf"),
+            fixture_path("multi_page_2.pdf"),
+        ],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        bookmarks: true,
+        layout: Some(Layout::Grid {
+            columns: 2,
+            rows: 2,
+        }),
+        ..Config::default()
+    };
+
+    // 7 pages on 4-up sheets
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 2);
+
+    let sheets: Vec<_> = document.get_pages().into_values().collect();
+    let content = String::from_utf8(document.get_page_content(sheets[0]).unwrap()).unwrap();
+    assert_eq!(content.matches(" Do ").count(), 4);
+
+    // The second input starts on page 5, which is on the second sheet
+    let toc = document.get_toc().unwrap();
+    let pages: Vec<usize> = toc
+        .toc
+        .iter()
+        .filter(|entry| entry.level == 1)
+        .map(|entry| entry.page)
+        .collect();
+    assert_eq!(pages, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_merge_with_stamps() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        compression: CompressionLevel::None,
+        stamps: vec![
+            Stamp::new("Page {n} of {total}", StampPosition::BottomCenter),
+            Stamp::new("{file}", StampPosition::TopLeft),
+        ],
+        ..Config::default()
+    };
+
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 5);
+
+    let pages: Vec<_> = document.get_pages().into_values().collect();
+    let first = String::from_utf8_lossy(&document.get_page_content(pages[0]).unwrap()).into_owned();
+    assert!(first.contains("(Page 1 of 5) Tj"));
+    assert!(first.contains("(basic.pdf) Tj"));
+
+    let last = String::from_utf8_lossy(&document.get_page_content(pages[4]).unwrap()).into_owned();
+    assert!(last.contains("(Page 5 of 5) Tj"));
+    assert!(last.contains("(multi_page.pdf) Tj"));
+}
+
+#[tokio::test]
+async fn test_merge_with_watermarks() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+    require_fixture("multi_page_2.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        compression: CompressionLevel::None,
+        watermarks: vec![
+            Watermark::text("CONFIDENTIAL"),
+            Watermark {
+                layer: WatermarkLayer::Under,
+                pages: Some(PageRange::parse("1").unwrap()),
+                ..Watermark::page(fixture_path("multi_page_2.pdf"), 2)
+            },
+        ],
+        ..Config::default()
+    };
+
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 5);
+
+    let pages: Vec<_> = document.get_pages().into_values().collect();
+    let first = String::from_utf8_lossy(&document.get_page_content(pages[0]).unwrap()).into_owned();
+    assert!(first.contains("(CONFIDENTIAL) Tj"));
+
+    // The imported page is drawn first, under the page's own content,
+    // and only on the first page
+    let draws_form = |content: &str| {
+        content.split("/PdfCatWatermark").skip(1).any(|rest| {
+            rest.trim_start_matches(|c: char| c.is_ascii_digit())
+                .starts_with(" Do")
+        })
+    };
+    let (start, _) = first.split_once("\nQ\n").unwrap();
+    assert!(draws_form(start));
+
+    let last = String::from_utf8_lossy(&document.get_page_content(pages[4]).unwrap()).into_owned();
+    assert!(last.contains("(CONFIDENTIAL) Tj"));
+    assert!(!draws_form(&last));
+}
+
+#[tokio::test]
+async fn test_merge_with_page_labels() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    // A copy of multi_page.pdf with roman numerals on its first two pages
+    let mut labeled = load_pdf(&fixture_path("multi_page.pdf")).await.unwrap();
+    labeled.catalog_mut().unwrap().set(
+        "PageLabels",
+        dictionary! {
+            "Nums" => vec![
+                0.into(),
+                Object::Dictionary(dictionary! { "S" => "r" }),
+                2.into(),
+                Object::Dictionary(dictionary! { "S" => "D" }),
+            ],
+        },
+    );
+    let labeled_path = temp_output_path();
+    labeled.save(&labeled_path).unwrap();
+
+    let output = temp_output_path();
+
+    let mut config = Config {
+        inputs: vec![fixture_path("basic.pdf"), labeled_path.to_path_buf()],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        compression: CompressionLevel::None,
+        page_range: Some(PageRange::parse("1,3-4").unwrap()),
+        ..Config::default()
+    };
+
+    let nums = |document: &lopdf::Document| {
+        let labels = document
+            .catalog()
+            .unwrap()
+            .get_deref(b"PageLabels", document)
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        labels.get(b"Nums").unwrap().as_array().unwrap().clone()
+    };
+
+    // basic.pdf only has page 1, which is labeled by its position; the
+    // labeled input keeps "i", "1", "2"
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+    let kept = nums(&document);
+    assert_eq!(kept.len(), 6);
+    assert_eq!(kept[2].as_i64().unwrap(), 1);
+    let roman = kept[3].as_dict().unwrap();
+    assert_eq!(roman.get(b"S").unwrap().as_name().unwrap(), b"r");
+    assert!(roman.get(b"St").is_err());
+    assert_eq!(kept[4].as_i64().unwrap(), 2);
+    assert!(kept[5].as_dict().unwrap().get(b"St").is_err());
+
+    // Generated labels number each input's pages from 1
+    config.page_range = None;
+    config.page_labels = PageLabels::Generate("{stem}-{n}".to_string());
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+    let generated = nums(&document);
+    assert_eq!(generated.len(), 4);
+    let basic = generated[1].as_dict().unwrap();
+    assert_eq!(basic.get(b"P").unwrap().as_str().unwrap(), b"basic-");
+    assert!(basic.get(b"St").is_err());
+    assert_eq!(generated[2].as_i64().unwrap(), 1);
+
+    config.page_labels = PageLabels::Remove;
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+    assert!(document.catalog().unwrap().get(b"PageLabels").is_err());
+}
+
+#[tokio::test]
+async fn test_merge_with_attachments() {
+    require_fixture("basic.pdf");
+
+    // An input with a notes.txt attachment, merged twice, plus another
+    // notes.txt to attach
+    let manager = AttachmentManager::new();
+    let mut input = load_pdf(&fixture_path("basic.pdf")).await.unwrap();
+    let file = manager.attach(&mut input, "notes.txt", b"from input".to_vec(), None);
+    manager.set_embedded_files(&mut input, vec![file]).unwrap();
+    let input_path = temp_output_path();
+    input.save(&input_path).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.txt");
+    std::fs::write(&notes, "attached").unwrap();
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![input_path.to_path_buf(), input_path.to_path_buf()],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        attachments: vec![notes],
+        ..Config::default()
+    };
+
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+    let files = manager.embedded_files(&document);
+    let names: Vec<&[u8]> = files.iter().map(|(name, _)| name.as_slice()).collect();
+    assert_eq!(
+        names,
+        vec![&b"notes (2).txt"[..], b"notes (3).txt", b"notes.txt"]
+    );
+
+    // Each name still leads to its own file
+    let mut contents: Vec<Vec<u8>> = files
+        .iter()
+        .map(|(_, spec)| {
+            let (_, spec) = document.dereference(spec).unwrap();
+            let ef = spec
+                .as_dict()
+                .unwrap()
+                .get(b"EF")
+                .unwrap()
+                .as_dict()
+                .unwrap();
+            let stream_id = ef.get(b"F").unwrap().as_reference().unwrap();
+            let stream = document.get_object(stream_id).unwrap().as_stream().unwrap();
+            stream
+                .decompressed_content()
+                .unwrap_or(stream.content.clone())
+        })
+        .collect();
+    contents.sort();
+    assert_eq!(
+        contents,
+        vec![
+            b"attached".to_vec(),
+            b"from input".to_vec(),
+            b"from input".to_vec()
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_merge_tagged_inputs() {
+    require_fixture("basic.pdf");
+
+    // Tag the page of an input with a structure tree of one paragraph
+    let mut input = load_pdf(&fixture_path("basic.pdf")).await.unwrap();
+    let page_id = input.page_iter().next().unwrap();
+    let root_id = input.new_object_id();
+    let paragraph_id = input.add_object(dictionary! {
+        "Type" => "StructElem",
+        "S" => "P",
+        "P" => root_id,
+        "Pg" => page_id,
+        "K" => 0,
+    });
+    input.objects.insert(
+        root_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "StructTreeRoot",
+            "K" => paragraph_id,
+            "ParentTree" => dictionary! {
+                "Nums" => vec![0.into(), vec![Object::Reference(paragraph_id)].into()],
+            },
+            "ParentTreeNextKey" => 1,
+        }),
+    );
+    input
+        .get_dictionary_mut(page_id)
+        .unwrap()
+        .set("StructParents", 0);
+    input.catalog_mut().unwrap().set("StructTreeRoot", root_id);
+    let input_path = temp_output_path();
+    input.save(&input_path).unwrap();
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![
+            input_path.to_path_buf(),
+            fixture_path("basic.pdf"),
+            input_path.to_path_buf(),
+        ],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        ..Config::default()
+    };
+
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+
+    let catalog = document.catalog().unwrap();
+    let root_id = catalog
+        .get(b"StructTreeRoot")
+        .unwrap()
+        .as_reference()
+        .unwrap();
+    let root = document.get_dictionary(root_id).unwrap();
+    assert_eq!(root.get(b"K").unwrap().as_array().unwrap().len(), 2);
+    assert_eq!(root.get(b"ParentTreeNextKey").unwrap().as_i64().unwrap(), 2);
+
+    // The tagged pages have their own keys; the untagged page has none
+    let keys: Vec<Option<i64>> = document
+        .page_iter()
+        .map(|id| {
+            let page = document.get_dictionary(id).unwrap();
+            page.get(b"StructParents").and_then(Object::as_i64).ok()
+        })
+        .collect();
+    assert_eq!(keys, vec![Some(0), None, Some(1)]);
+}
+
+#[tokio::test]
+async fn test_merge_layered_inputs() {
+    require_fixture("basic.pdf");
+
+    // Copies of an input with one layer each, the second starting hidden
+    let mut layered = Vec::new();
+    for (name, hidden) in [("Walls", false), ("Roads", true)] {
+        let mut input = load_pdf(&fixture_path("basic.pdf")).await.unwrap();
+        let ocg = Object::Reference(input.add_object(dictionary! {
+            "Type" => "OCG",
+            "Name" => lopdf::text_string(name),
+        }));
+        let config = if hidden {
+            dictionary! { "OFF" => vec![ocg.clone()] }
+        } else {
+            dictionary! { "Order" => vec![ocg.clone()] }
+        };
+        input.catalog_mut().unwrap().set(
+            "OCProperties",
+            dictionary! { "OCGs" => vec![ocg], "D" => config },
+        );
+        let path = temp_output_path();
+        input.save(&path).unwrap();
+        layered.push(path);
+    }
+    let (plan, site) = (&layered[0], &layered[1]);
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![
+            plan.to_path_buf(),
+            fixture_path("basic.pdf"),
+            site.to_path_buf(),
+        ],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        group_layers: true,
+        ..Config::default()
+    };
+
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+
+    let properties = document
+        .catalog()
+        .unwrap()
+        .get(b"OCProperties")
+        .unwrap()
+        .as_dict()
+        .unwrap();
+    assert_eq!(
+        properties.get(b"OCGs").unwrap().as_array().unwrap().len(),
+        2
+    );
+    let default = properties.get(b"D").unwrap().as_dict().unwrap();
+    assert_eq!(default.get(b"OFF").unwrap().as_array().unwrap().len(), 1);
+
+    // Each input's layer is listed under its file name
+    let headings: Vec<String> = default
+        .get(b"Order")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|group| lopdf::decode_text_string(&group.as_array().unwrap()[0]).unwrap())
+        .collect();
+    let file_name =
+        |path: &std::path::Path| path.file_name().unwrap().to_string_lossy().into_owned();
+    assert_eq!(headings, vec![file_name(plan), file_name(site)]);
+}
+
+#[tokio::test]
+async fn test_merge_without_comments() {
+    require_fixture("basic.pdf");
+
+    // An input with a highlight, a stamp and a link
+    let mut input = load_pdf(&fixture_path("basic.pdf")).await.unwrap();
+    let page_id = input.page_iter().next().unwrap();
+    let stamp_form = input.add_object(lopdf::Stream::new(
+        dictionary! { "BBox" => vec![0.into(), 0.into(), 50.into(), 20.into()] },
+        b"0 0 50 20 re S".to_vec(),
+    ));
+    let rect = || vec![10.into(), 10.into(), 60.into(), 30.into()];
+    let annots: Vec<Object> = [
+        dictionary! { "Type" => "Annot", "Subtype" => "Highlight", "Rect" => rect() },
+        dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Stamp",
+            "Rect" => rect(),
+            "AP" => dictionary! { "N" => stamp_form },
+        },
+        dictionary! { "Type" => "Annot", "Subtype" => "Link", "Rect" => rect() },
+    ]
+    .into_iter()
+    .map(|annot| Object::Reference(input.add_object(annot)))
+    .collect();
+    input
+        .get_dictionary_mut(page_id)
+        .unwrap()
+        .set("Annots", annots);
+    let input_path = temp_output_path();
+    input.save(&input_path).unwrap();
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![input_path.to_path_buf(), input_path.to_path_buf()],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        remove_annotations: Some("comments".parse().unwrap()),
+        flatten: true,
+        ..Config::default()
+    };
+
+    let (document, _) = merge_pdfs(&config).await.unwrap();
+
+    // Only the links are left; the stamps are drawn into the pages
+    for page_id in document.page_iter() {
+        let page = document.get_dictionary(page_id).unwrap();
+        let annots = page.get(b"Annots").unwrap().as_array().unwrap();
+        assert_eq!(annots.len(), 1);
+        let annot = document
+            .get_dictionary(annots[0].as_reference().unwrap())
+            .unwrap();
+        assert_eq!(annot.get(b"Subtype").unwrap().as_name().unwrap(), b"Link");
+
+        let content = document.get_page_content(page_id).unwrap();
+        assert!(String::from_utf8_lossy(&content).contains("/PdfCatAnnot"));
+    }
+}
+
+#[tokio::test]
+async fn test_merge_sanitized() {
+    require_fixture("basic.pdf");
+
+    // An input with a script run when it opens and an attachment
+    let mut input = load_pdf(&fixture_path("basic.pdf")).await.unwrap();
+    let script_id = input.add_object(dictionary! {
+        "S" => "JavaScript",
+        "JS" => Object::string_literal("app.alert('hi')"),
+    });
+    input.catalog_mut().unwrap().set("OpenAction", script_id);
+    let manager = AttachmentManager::new();
+    let file = manager.attach(&mut input, "notes.txt", b"private".to_vec(), None);
+    manager.set_embedded_files(&mut input, vec![file]).unwrap();
+    let input_path = temp_output_path();
+    input.save(&input_path).unwrap();
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![input_path.to_path_buf(), fixture_path("basic.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        sanitize: true,
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+
+    let (path, report) = &result.sanitized_files[0];
+    assert_eq!(path, &input_path.to_path_buf());
+    assert!(report.open_action);
+    assert_eq!(report.javascript, 1);
+    assert_eq!(report.embedded_files, 1);
+    assert_eq!(result.sanitized_files.len(), 2);
+
+    let catalog = result.document.catalog().unwrap();
+    assert!(!catalog.has(b"OpenAction"));
+    assert!(manager.embedded_files(&result.document).is_empty());
+    assert!(
+        !result
+            .document
+            .objects
+            .values()
+            .any(|obj| { obj.as_dict().is_ok_and(|dict| dict.has(b"JS")) })
+    );
+}
+
+#[tokio::test]
+async fn test_merge_viewer_settings() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        bookmarks: true,
+        viewer: ViewerSettings {
+            page_layout: Some(PageLayout::TwoPageRight),
+            fit_window: true,
+            open_at: Some("2:fit".parse().unwrap()),
+            ..ViewerSettings::default()
+        },
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+    let doc = &result.document;
+    let catalog = doc.catalog().unwrap();
+
+    // Bookmarks are shown when the document opens
+    let name = |key: &[u8]| catalog.get(key).unwrap().as_name().unwrap();
+    assert_eq!(name(b"PageMode"), b"UseOutlines");
+    assert_eq!(name(b"PageLayout"), b"TwoPageRight");
+
+    let preferences = catalog
+        .get_deref(b"ViewerPreferences", doc)
+        .unwrap()
+        .as_dict()
+        .unwrap();
+    assert!(preferences.get(b"FitWindow").unwrap().as_bool().unwrap());
+
+    let action = catalog.get(b"OpenAction").unwrap().as_array().unwrap();
+    let pages = doc.get_pages();
+    assert_eq!(action[0].as_reference().unwrap(), pages[&2]);
+    assert_eq!(action[1].as_name().unwrap(), b"Fit");
+}
+
+#[tokio::test]
+async fn test_merge_newest_version() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+
+    // basic.pdf is PDF 1.3 and multi_page.pdf is PDF 1.4
+    assert_eq!(result.document.version, "1.4");
+    assert_eq!(result.statistics.pdf_version, PdfVersion::new(1, 4));
+    let catalog = result.document.catalog().unwrap();
+    assert_eq!(catalog.get(b"Version").unwrap().as_name().unwrap(), b"1.4");
+    assert!(result.unsupported_features.is_empty());
+}
+
+#[tokio::test]
+async fn test_merge_forced_version() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        watermarks: vec![Watermark::text("DRAFT")],
+        pdf_version: Some(PdfVersion::new(1, 3)),
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+
+    // The watermark's opacity needs transparency, from PDF 1.4
+    assert_eq!(result.document.version, "1.3");
+    assert!(!result.document.catalog().unwrap().has(b"Version"));
+    let features: Vec<String> = result
+        .unsupported_features
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(features, vec!["transparency (PDF 1.4)"]);
+}
+
+#[tokio::test]
+async fn test_merge_image_inputs() {
+    require_fixture("basic.pdf");
+
+    // A 300 x 150 pixel PNG at 300 dpi, a quarter of it transparent
+    let dir = tempfile::TempDir::new().unwrap();
+    let scan = dir.path().join("scan.png");
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, 300, 150);
+    encoder.set_color(png::ColorType::GrayscaleAlpha);
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: 11811,
+        yppu: 11811,
+        unit: png::Unit::Meter,
+    }));
+    let pixels: Vec<u8> = (0..300 * 150)
+        .flat_map(|i| [128, if i % 4 == 0 { 0 } else { 255 }])
+        .collect();
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&pixels).unwrap();
+    writer.finish().unwrap();
+    std::fs::write(&scan, data).unwrap();
+
+    let result = Validator::new().validate_file(&scan).await.unwrap();
+    assert_eq!(result.page_count, 1);
+    assert_eq!(result.page_dimensions, vec![(72.0, 36.0)]);
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![scan.clone(), fixture_path("basic.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        page_size: Some(PageSize::LETTER),
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+    let doc = &result.document;
+    assert_eq!(doc.get_pages().len(), 2);
+
+    // The image's page is fit to letter paper like any other page, in
+    // landscape since the image is wider than it is tall
+    let media_box = doc
+        .get_dictionary(doc.get_pages()[&1])
+        .unwrap()
+        .get(b"MediaBox")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_float().unwrap().round())
+        .collect::<Vec<_>>();
+    assert_eq!(media_box, vec![0.0, 0.0, 792.0, 612.0]);
+
+    let has_mask = doc.objects.values().any(|obj| {
+        obj.as_stream()
+            .is_ok_and(|stream| stream.dict.has(b"SMask"))
+    });
+    assert!(has_mask);
+}
+
+#[tokio::test]
+async fn test_merge_trim_with_page_size() {
+    require_fixture("basic.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        box_edits: vec![
+            BoxEdit::parse(BoxKind::Trim, "36").unwrap(),
+            BoxEdit::parse(BoxKind::Bleed, "rect:10,10,500,500").unwrap(),
+        ],
+        page_size: Some(PageSize::A4),
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+    let doc = &result.document;
+
+    // Normalization replaces the pages' boxes, so the TrimBox and BleedBox
+    // are set afterwards, on the A4 page
+    let page = doc.get_dictionary(doc.get_pages()[&1]).unwrap();
+    let page_box = |key: &[u8]| {
+        page.get(key)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value.as_float().unwrap().round())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(page_box(b"MediaBox"), vec![0.0, 0.0, 595.0, 842.0]);
+    assert_eq!(page_box(b"TrimBox"), vec![36.0, 36.0, 559.0, 806.0]);
+    assert_eq!(page_box(b"BleedBox"), vec![10.0, 10.0, 500.0, 500.0]);
+}
+
+#[tokio::test]
+async fn test_merge_portfolio() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("multi_page.pdf"), fixture_path("basic.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        merge_mode: MergeMode::Portfolio,
+        ..Config::default()
+    };
+
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.files_merged, 2);
+    assert_eq!(stats.total_pages, 1);
+    assert!(document.catalog().unwrap().has(b"Collection"));
+
+    // Inputs are embedded byte for byte, in input order
+    let files = AttachmentManager::new().embedded_files(&document);
+    assert_eq!(files.len(), 2);
+    for (name, spec) in &files {
+        let (_, spec) = document.dereference(spec).unwrap();
+        let spec = spec.as_dict().unwrap();
+        let ef = spec.get(b"EF").unwrap().as_dict().unwrap();
+        let stream_id = ef.get(b"F").unwrap().as_reference().unwrap();
+        let stream = document.get_object(stream_id).unwrap().as_stream().unwrap();
+
+        let original = std::fs::read(fixture_path(&String::from_utf8_lossy(name))).unwrap();
+        assert_eq!(stream.decompressed_content().unwrap(), original);
+
+        let item = spec.get(b"CI").unwrap().as_dict().unwrap();
+        let (index, pages) = if name == b"multi_page.pdf" {
+            (0, 4)
+        } else {
+            (1, 1)
+        };
+        assert_eq!(item.get(b"Index").unwrap().as_i64().unwrap(), index);
+        assert_eq!(item.get(b"Pages").unwrap().as_i64().unwrap(), pages);
+    }
+}
+
+#[tokio::test]
+async fn test_merge_with_bates_numbers() {
+    require_fixture("basic.pdf");
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("basic.pdf"), fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        compression: CompressionLevel::None,
+        reverse_inputs: vec![2],
+        bates: Some(Bates {
+            start: 100,
+            ..Bates::new("ACME")
+        }),
+        ..Config::default()
+    };
+
+    let result = Merger::new().merge(&config).await.unwrap();
+    assert_eq!(result.statistics.total_pages, 5);
+
+    let pages: Vec<_> = result.document.get_pages().into_values().collect();
+    let last =
+        String::from_utf8_lossy(&result.document.get_page_content(pages[4]).unwrap()).into_owned();
+    assert!(last.contains("(ACME000104) Tj"));
+
+    // One range per input; the second input's pages are reversed
+    let log = &result.bates_log;
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].first, "ACME000100");
+    assert_eq!(log[0].pages, vec![1]);
+    assert_eq!(log[1].first, "ACME000101");
+    assert_eq!(log[1].last, "ACME000104");
+    assert_eq!(log[1].file, Some(fixture_path("multi_page.pdf")));
+    assert_eq!(log[1].pages, vec![4, 3, 2, 1]);
+}
@@ -1,6 +1,6 @@
 //! Integration tests for bookmark functionality.
 
-use pdfcat::config::{CompressionLevel, Config, Metadata, OverwriteMode};
+use pdfcat::config::{Config, OverwriteMode};
 use pdfcat::io::load_pdf;
 use pdfcat::merge::{BookmarkManager, merge_pdfs};
 
@@ -16,24 +16,17 @@ async fn test_merge_with_bookmarks() {
     let config = Config {
         inputs: vec![fixture_path("basic.pdf"), fixture_path("basic.pdf")],
         output: output_path.clone(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
-        bookmarks: true, // Enable bookmarks
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
-        page_range: None,
-        rotation: None,
+        bookmarks: true,
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
     assert!(result.is_ok());
 
-    // Load output and verify bookmarks were added
-    let output_doc = load_pdf(&output_path).await.unwrap();
+    // Verify bookmarks were added
+    let output_doc = result.unwrap().0;
     let bookmark_manager = BookmarkManager::new();
     assert!(
         bookmark_manager.has_bookmarks(&output_doc),
@@ -51,24 +44,16 @@ async fn test_merge_without_bookmarks() {
     let config = Config {
         inputs: vec![fixture_path("basic.pdf"), fixture_path("basic.pdf")],
         output: output_path.clone(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
-        bookmarks: false, // No bookmarks
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
-        page_range: None,
-        rotation: None,
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
     assert!(result.is_ok());
 
-    // Load output and verify no bookmarks
-    let _output_doc = load_pdf(&output_path).await.unwrap();
+    // Verify no bookmarks
+    let _output_doc = result.unwrap().0;
     let _bookmark_manager = BookmarkManager::new();
 
     // Note: If input PDFs have bookmarks, they might be preserved
@@ -90,23 +75,16 @@ async fn test_bookmarks_with_multiple_files() {
             fixture_path("basic.pdf"),
         ],
         output: output_path.clone(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
         bookmarks: true,
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
-        page_range: None,
-        rotation: None,
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
     assert!(result.is_ok());
 
-    let output_doc = load_pdf(&output_path).await.unwrap();
+    let output_doc = result.unwrap().0;
     let bookmark_manager = BookmarkManager::new();
     assert!(bookmark_manager.has_bookmarks(&output_doc));
 }
@@ -126,23 +104,16 @@ async fn test_bookmarks_preserve_existing() {
                 fixture_path("basic.pdf"),
             ],
             output: output_path.clone(),
-            dry_run: false,
-            verbose: false,
             overwrite_mode: OverwriteMode::Force,
             quiet: true,
             bookmarks: true,
-            compression: CompressionLevel::Standard,
-            metadata: Metadata::default(),
-            continue_on_error: false,
-            jobs: None,
-            page_range: None,
-            rotation: None,
+            ..Config::default()
         };
 
         let result = merge_pdfs(&config).await;
         assert!(result.is_ok());
 
-        let output_doc = load_pdf(&output_path).await.unwrap();
+        let output_doc = result.unwrap().0;
         let bookmark_manager = BookmarkManager::new();
         assert!(bookmark_manager.has_bookmarks(&output_doc));
     }
@@ -158,17 +129,10 @@ async fn test_bookmarks_single_file() {
     let config = Config {
         inputs: vec![fixture_path("basic.pdf")],
         output: output.to_path_buf(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
         bookmarks: true,
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
-        page_range: None,
-        rotation: None,
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
@@ -188,23 +152,17 @@ async fn test_bookmarks_with_page_extraction() {
             fixture_path("multi_page.pdf"),
         ],
         output: output_path.clone(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
         bookmarks: true,
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
         page_range: Some(pdfcat::config::PageRange::parse("1-2").unwrap()),
-        rotation: None,
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
     assert!(result.is_ok());
 
-    let output_doc = load_pdf(&output_path).await.unwrap();
+    let output_doc = result.unwrap().0;
     let bookmark_manager = BookmarkManager::new();
     // Should still have bookmarks even with page extraction
     assert!(bookmark_manager.has_bookmarks(&output_doc));
@@ -248,23 +206,17 @@ async fn test_bookmarks_with_rotation() {
     let config = Config {
         inputs: vec![fixture_path("basic.pdf"), fixture_path("basic.pdf")],
         output: output_path.clone(),
-        dry_run: false,
-        verbose: false,
         overwrite_mode: OverwriteMode::Force,
         quiet: true,
         bookmarks: true,
-        compression: CompressionLevel::Standard,
-        metadata: Metadata::default(),
-        continue_on_error: false,
-        jobs: None,
-        page_range: None,
         rotation: Some(pdfcat::config::Rotation::Clockwise90),
+        ..Config::default()
     };
 
     let result = merge_pdfs(&config).await;
     assert!(result.is_ok());
 
-    let output_doc = load_pdf(&output_path).await.unwrap();
+    let output_doc = result.unwrap().0;
     let bookmark_manager = BookmarkManager::new();
     assert!(bookmark_manager.has_bookmarks(&output_doc));
 }
@@ -1,6 +1,9 @@
 //! Integration tests for error handling and edge cases.
 
-use pdfcat::config::{CompressionLevel, Config, Metadata, OverwriteMode, PageRange, Rotation};
+use pdfcat::config::{
+    CompressionLevel, Config, MergeMode, Metadata, OutputFormat, OverwriteMode, PageFit,
+    PageLabels, PageRange, PageRangePolicy, Rotation, ViewerSettings,
+};
 use pdfcat::error::PdfCatError;
 use pdfcat::merge::merge_pdfs;
 use pdfcat::validation::Validator;
@@ -23,9 +26,33 @@ async fn test_error_nonexistent_input() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -47,9 +74,33 @@ async fn test_error_empty_input_list() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = config.validate();
@@ -75,9 +126,33 @@ async fn test_error_corrupted_pdf() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -109,9 +184,33 @@ async fn test_error_continue_on_error_recovers() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: true, // Continue on error
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -121,6 +220,40 @@ async fn test_error_continue_on_error_recovers() {
     assert_eq!(stats.files_merged, 2, "Should merge only valid files");
 }
 
+#[tokio::test]
+async fn test_repair_recovers_damaged_pdf() {
+    require_fixture("multi_page.pdf");
+
+    // Truncate the file right after its last object, losing xref and trailer
+    let content = std::fs::read(fixture_path("multi_page.pdf")).unwrap();
+    let end = content.windows(6).rposition(|w| w == b"endobj").unwrap() + 6;
+    let damaged = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(damaged.path(), &content[..end]).unwrap();
+
+    let output = temp_output_path();
+
+    let mut config = Config {
+        inputs: vec![fixture_path("basic.pdf"), damaged.path().to_path_buf()],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        ..Config::default()
+    };
+
+    let result = merge_pdfs(&config).await;
+    assert!(result.is_err(), "Damaged PDF should fail without --repair");
+
+    config.repair = true;
+    let result = pdfcat::merge::Merger::new().merge(&config).await;
+    assert!(result.is_ok(), "Repair failed: {:?}", result.err());
+
+    let merge_result = result.unwrap();
+    assert_eq!(merge_result.statistics.files_merged, 2);
+    assert!(merge_result.statistics.total_pages > 1);
+    assert_eq!(merge_result.repaired_files.len(), 1);
+    assert!(merge_result.repaired_files[0].1.xref_rebuilt);
+}
+
 #[tokio::test]
 async fn test_error_invalid_page_range() {
     require_fixture("basic.pdf");
@@ -139,9 +272,33 @@ async fn test_error_invalid_page_range() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: Some(PageRange::parse("100-200").unwrap()),
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -163,9 +320,33 @@ async fn test_ok_validate_create_output_subdirs() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let validator = Validator::new();
@@ -194,9 +375,33 @@ async fn test_error_output_same_as_input() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = config.validate();
@@ -222,9 +427,33 @@ async fn test_error_no_clobber_with_existing_file() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let validator = Validator::new();
@@ -255,9 +484,33 @@ async fn test_error_verbose_and_quiet_conflict() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = config.validate();
@@ -281,9 +534,33 @@ async fn test_error_zero_jobs() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: Some(0), // Zero jobs!
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = config.validate();
@@ -301,8 +578,8 @@ async fn test_error_invalid_page_range_format() {
     let result = PageRange::parse("0");
     assert!(result.is_err(), "Should fail with page 0");
 
-    let result = PageRange::parse("5-3");
-    assert!(result.is_err(), "Should fail with reversed range");
+    let result = PageRange::parse("1-2-3");
+    assert!(result.is_err(), "Should fail with too many range bounds");
 
     let result = PageRange::parse("");
     assert!(result.is_err(), "Should fail with empty range");
@@ -336,9 +613,33 @@ async fn test_error_all_files_fail_with_continue() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: true,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -365,9 +666,33 @@ async fn test_edge_case_single_page_extraction() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: Some(PageRange::parse("1").unwrap()),
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: None,
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -377,6 +702,40 @@ async fn test_edge_case_single_page_extraction() {
     assert_eq!(stats.total_pages, 1);
 }
 
+#[tokio::test]
+async fn test_edge_case_reversed_and_repeated_pages() {
+    require_fixture("multi_page.pdf");
+
+    let output = temp_output_path();
+
+    let config = Config {
+        inputs: vec![fixture_path("multi_page.pdf")],
+        output: output.to_path_buf(),
+        overwrite_mode: OverwriteMode::Force,
+        quiet: true,
+        page_range: Some(PageRange::parse("end-1,-1").unwrap()),
+        ..Config::default()
+    };
+
+    let (document, stats) = merge_pdfs(&config).await.unwrap();
+    assert_eq!(stats.total_pages, 5);
+
+    let pages: Vec<_> = document.get_pages().into_values().collect();
+    assert_eq!(
+        document
+            .get_dictionary(pages[0])
+            .unwrap()
+            .get(b"Contents")
+            .ok(),
+        document
+            .get_dictionary(pages[4])
+            .unwrap()
+            .get(b"Contents")
+            .ok()
+    );
+    assert_ne!(pages[0], pages[4]);
+}
+
 #[tokio::test]
 async fn test_edge_case_multiple_rotations() {
     require_fixture("basic.pdf");
@@ -395,9 +754,33 @@ async fn test_edge_case_multiple_rotations() {
         compression: CompressionLevel::Standard,
         metadata: Metadata::default(),
         continue_on_error: false,
+        repair: false,
         jobs: None,
         page_range: None,
+        page_range_policy: PageRangePolicy::Lenient,
         rotation: Some(Rotation::Clockwise90),
+        merge_mode: MergeMode::Concatenate,
+        reverse_inputs: Vec::new(),
+        duplex: false,
+        blank_pages: Vec::new(),
+        separators: false,
+        layout: None,
+        box_edits: Vec::new(),
+        page_size: None,
+        page_fit: PageFit::Fit,
+        stamps: Vec::new(),
+        bates: None,
+        bates_log: None,
+        watermarks: Vec::new(),
+        page_labels: PageLabels::Keep,
+        attachments: Vec::new(),
+        group_layers: false,
+        remove_annotations: None,
+        flatten: false,
+        sanitize: false,
+        viewer: ViewerSettings::default(),
+        pdf_version: None,
+        output_format: OutputFormat::Text,
     };
 
     let result = merge_pdfs(&config).await;
@@ -0,0 +1,59 @@
+//! Integration tests for splitting PDFs.
+
+use pdfcat::io::PdfReader;
+use pdfcat::split::{SplitMode, SplitOptions, Splitter};
+use tempfile::TempDir;
+
+use crate::common::{fixture_path, require_fixture};
+
+#[tokio::test]
+async fn test_split_every_page() {
+    require_fixture("multi_page.pdf");
+
+    let input = fixture_path("multi_page.pdf");
+    let loaded = PdfReader::new().load(&input).await.unwrap();
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut options = SplitOptions::new(SplitMode::EveryPages(1));
+    options.output_dir = temp_dir.path().to_path_buf();
+
+    let outputs = Splitter::new(options)
+        .split(&input, &loaded.document)
+        .await
+        .expect("Split failed");
+
+    assert_eq!(outputs.len(), loaded.page_count);
+
+    for output in &outputs {
+        let part = PdfReader::new().load(&output.part.path).await.unwrap();
+        assert_eq!(part.page_count, 1);
+        assert!(
+            output.file_size < loaded.file_size,
+            "Part should be smaller than the original"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_split_by_bookmarks() {
+    require_fixture("multi_page.pdf");
+
+    let input = fixture_path("multi_page.pdf");
+    let loaded = PdfReader::new().load(&input).await.unwrap();
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut options = SplitOptions::new(SplitMode::Bookmarks);
+    options.output_dir = temp_dir.path().to_path_buf();
+    options.template = "{n}.pdf".to_string();
+
+    let outputs = Splitter::new(options)
+        .split(&input, &loaded.document)
+        .await
+        .expect("Split failed");
+
+    assert!(!outputs.is_empty());
+    assert!(outputs.iter().all(|output| output.part.title.is_some()));
+
+    let total_pages: usize = outputs.iter().map(|o| o.part.pages.len()).sum();
+    assert_eq!(total_pages, loaded.page_count);
+}